@@ -0,0 +1,55 @@
+pragma circom 2.1.9;
+
+include "num2bits.circom";
+
+// out is 1 if lhs >= rhs and 0 otherwise. Both are checked to fit in n bits: otherwise the
+// difference could wrap around the field and the comparison would be meaningless.
+template AtLeast(n) {
+    // The difference takes n + 1 bits, which must stay under the BLS12-381 scalar field.
+    assert(n > 0 && n <= 252);
+    signal input lhs;
+    signal input rhs;
+    signal output out;
+
+    component lhsBits = Num2Bits(n);
+    lhsBits.in <== lhs;
+    component rhsBits = Num2Bits(n);
+    rhsBits.in <== rhs;
+
+    // lhs - rhs >= 0 sets the top bit of lhs - rhs + 2^n
+    component difference = Num2Bits(n + 1);
+    difference.in <== lhs - rhs + (1 << n);
+    out <== difference.out[n];
+}
+
+template LessThan(n) {
+    signal input lhs;
+    signal input rhs;
+    signal input out;
+
+    component atLeast = AtLeast(n);
+    atLeast.lhs <== lhs;
+    atLeast.rhs <== rhs;
+    out === 1 - atLeast.out;
+}
+
+template GreaterEqual(n) {
+    signal input lhs;
+    signal input rhs;
+    signal input out;
+
+    component atLeast = AtLeast(n);
+    atLeast.lhs <== lhs;
+    atLeast.rhs <== rhs;
+    out === atLeast.out;
+}
+
+// out is 1 if in is 0 and 0 otherwise: a non zero in has an inverse, which makes in * inverse 1.
+template IsZero() {
+    signal input in;
+    signal input out;
+
+    signal inverse <-- in != 0 ? 1 / in : 0;
+    out === 1 - in * inverse;
+    in * out === 0;
+}
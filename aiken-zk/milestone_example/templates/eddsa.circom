@@ -0,0 +1,224 @@
+pragma circom 2.1.9;
+
+include "num2bits.circom";
+include "poseidon_bls12_381.circom";
+
+// Jubjub is the twisted Edwards curve a * x^2 + y^2 = 1 + d * x^2 * y^2 over the BLS12-381 scalar
+// field, with a = -1 and d = -(10240/10241).
+function JUBJUB_D() {
+    return 19257038036680949359750312669786877991949435402254120286184196891950884077233;
+}
+
+// Generates the subgroup of prime order, where the signatures live. The whole curve has 8 times
+// as many points.
+function JUBJUB_GENERATOR() {
+    return [
+        8076246640662884909881801758704306714034609987455869804520522091855516602923,
+        13262374693698910701929044844600465831413122818447359594527400194675274060458
+    ];
+}
+
+function JUBJUB_SUBGROUP_ORDER() {
+    return 6554484396890773809930967563523245729705921265872317281365359162392183254199;
+}
+
+// The BLS12-381 scalar field modulus, split at bit 128.
+function BLS12_381_MODULUS_HIGH_AND_LOW() {
+    return [154095187621958656428822154526901524485, 111310594309268602877181240610339684353];
+}
+
+// The 255 bits of in. 255 bits also fit in + p, so the bits are checked to be under the modulus.
+template Num2BitsStrict() {
+    signal input in;
+    signal output out[255];
+
+    component bits = Num2Bits(255);
+    bits.in <== in;
+
+    var low = 0;
+    var high = 0;
+    for (var i = 0; i < 128; i++) {
+        low += bits.out[i] * (1 << i);
+    }
+    for (var i = 128; i < 255; i++) {
+        high += bits.out[i] * (1 << (i - 128));
+    }
+    var MODULUS[2] = BLS12_381_MODULUS_HIGH_AND_LOW();
+
+    // The top bit of x - y + 2^n is set when x >= y, for x and y of n bits
+    component highAtLeast = Num2Bits(128);
+    highAtLeast.in <== high - MODULUS[0] + (1 << 127);
+    component lowAtLeast = Num2Bits(129);
+    lowAtLeast.in <== low - MODULUS[1] + (1 << 128);
+
+    signal highDifferenceInverse <-- high != MODULUS[0] ? 1 / (high - MODULUS[0]) : 0;
+    signal highEqual <== 1 - (high - MODULUS[0]) * highDifferenceInverse;
+    (high - MODULUS[0]) * highEqual === 0;
+
+    // high < MODULUS[0], or high == MODULUS[0] and low < MODULUS[1]
+    signal lowBelowWithHighEqual <== highEqual * (1 - lowAtLeast.out[128]);
+    1 - highAtLeast.out[127] + lowBelowWithHighEqual === 1;
+
+    out <== bits.out;
+}
+
+template JubjubCheck() {
+    signal input x;
+    signal input y;
+
+    signal x2 <== x * x;
+    signal y2 <== y * y;
+    -x2 + y2 === 1 + JUBJUB_D() * x2 * y2;
+}
+
+// The addition formulas of the curve are complete: they also double and add the identity (0, 1).
+template JubjubAdd() {
+    signal input x1;
+    signal input y1;
+    signal input x2;
+    signal input y2;
+    signal output xout;
+    signal output yout;
+
+    var a = -1;
+    var d = JUBJUB_D();
+
+    signal beta <== x1 * y2;
+    signal gamma <== y1 * x2;
+    signal delta <== (-a * x1 + y1) * (x2 + y2);
+    signal tau <== beta * gamma;
+
+    xout <-- (beta + gamma) / (1 + d * tau);
+    (1 + d * tau) * xout === beta + gamma;
+
+    yout <-- (delta + a * beta - gamma) / (1 - d * tau);
+    (1 - d * tau) * yout === delta + a * beta - gamma;
+}
+
+template JubjubMulByCofactor() {
+    signal input x;
+    signal input y;
+    signal output xout;
+    signal output yout;
+
+    component doublers[3];
+    for (var i = 0; i < 3; i++) {
+        doublers[i] = JubjubAdd();
+        doublers[i].x1 <== i == 0 ? x : doublers[i - 1].xout;
+        doublers[i].y1 <== i == 0 ? y : doublers[i - 1].yout;
+        doublers[i].x2 <== i == 0 ? x : doublers[i - 1].xout;
+        doublers[i].y2 <== i == 0 ? y : doublers[i - 1].yout;
+    }
+
+    xout <== doublers[2].xout;
+    yout <== doublers[2].yout;
+}
+
+// Multiplies (x, y) by the scalar with the given bits, least significant first, doubling and
+// adding from the top bit. The bits must already be constrained to 0 or 1.
+template JubjubScalarMul(n) {
+    signal input bits[n];
+    signal input x;
+    signal input y;
+    signal output xout;
+    signal output yout;
+
+    component doublers[n];
+    component adders[n];
+    signal addendX[n];
+    signal addendY[n];
+
+    for (var i = 0; i < n; i++) {
+        doublers[i] = JubjubAdd();
+        doublers[i].x1 <== i == 0 ? 0 : adders[i - 1].xout;
+        doublers[i].y1 <== i == 0 ? 1 : adders[i - 1].yout;
+        doublers[i].x2 <== i == 0 ? 0 : adders[i - 1].xout;
+        doublers[i].y2 <== i == 0 ? 1 : adders[i - 1].yout;
+
+        // (x, y) if the bit is set, the identity otherwise
+        addendX[i] <== bits[n - 1 - i] * x;
+        addendY[i] <== bits[n - 1 - i] * (y - 1) + 1;
+
+        adders[i] = JubjubAdd();
+        adders[i].x1 <== doublers[i].xout;
+        adders[i].y1 <== doublers[i].yout;
+        adders[i].x2 <== addendX[i];
+        adders[i].y2 <== addendY[i];
+    }
+
+    xout <== adders[n - 1].xout;
+    yout <== adders[n - 1].yout;
+}
+
+// Verifies the EdDSA signature (r, s) of msg by the public key: s * B = r + h * pub_key, multiplied
+// by the cofactor, where B is the generator and
+// h = Poseidon(Poseidon(Poseidon(r_x, r_y), Poseidon(pub_key_x, pub_key_y)), msg).
+template EddsaVerify() {
+    signal input pub_key_x;
+    signal input pub_key_y;
+    signal input msg;
+    signal input r_x;
+    signal input r_y;
+    signal input s;
+
+    component pubKeyCheck = JubjubCheck();
+    pubKeyCheck.x <== pub_key_x;
+    pubKeyCheck.y <== pub_key_y;
+    component rCheck = JubjubCheck();
+    rCheck.x <== r_x;
+    rCheck.y <== r_y;
+
+    // A public key of small order would accept any signature
+    component pubKeyByCofactor = JubjubMulByCofactor();
+    pubKeyByCofactor.x <== pub_key_x;
+    pubKeyByCofactor.y <== pub_key_y;
+    signal pubKeyByCofactorInverse <-- pubKeyByCofactor.xout != 0 ? 1 / pubKeyByCofactor.xout : 0;
+    pubKeyByCofactor.xout * pubKeyByCofactorInverse === 1;
+
+    // s < JUBJUB_SUBGROUP_ORDER(), so that there is a single s for each signature
+    component sBits = Num2Bits(252);
+    sBits.in <== s;
+    component sBelowOrder = Num2Bits(253);
+    sBelowOrder.in <== JUBJUB_SUBGROUP_ORDER() - 1 - s + (1 << 252);
+    sBelowOrder.out[252] === 1;
+
+    component rHash = PoseidonHashLeftRight();
+    rHash.left <== r_x;
+    rHash.right <== r_y;
+    component pubKeyHash = PoseidonHashLeftRight();
+    pubKeyHash.left <== pub_key_x;
+    pubKeyHash.right <== pub_key_y;
+    component pointsHash = PoseidonHashLeftRight();
+    pointsHash.left <== rHash.hash;
+    pointsHash.right <== pubKeyHash.hash;
+    component challenge = PoseidonHashLeftRight();
+    challenge.left <== pointsHash.hash;
+    challenge.right <== msg;
+    component challengeBits = Num2BitsStrict();
+    challengeBits.in <== challenge.hash;
+
+    var B[2] = JUBJUB_GENERATOR();
+    component signed = JubjubScalarMul(252);
+    signed.bits <== sBits.out;
+    signed.x <== B[0];
+    signed.y <== B[1];
+
+    component challengeTimesPubKey = JubjubScalarMul(255);
+    challengeTimesPubKey.bits <== challengeBits.out;
+    challengeTimesPubKey.x <== pub_key_x;
+    challengeTimesPubKey.y <== pub_key_y;
+    component expected = JubjubAdd();
+    expected.x1 <== r_x;
+    expected.y1 <== r_y;
+    expected.x2 <== challengeTimesPubKey.xout;
+    expected.y2 <== challengeTimesPubKey.yout;
+
+    component signedByCofactor = JubjubMulByCofactor();
+    signedByCofactor.x <== signed.xout;
+    signedByCofactor.y <== signed.yout;
+    component expectedByCofactor = JubjubMulByCofactor();
+    expectedByCofactor.x <== expected.xout;
+    expectedByCofactor.y <== expected.yout;
+    signedByCofactor.xout === expectedByCofactor.xout;
+    signedByCofactor.yout === expectedByCofactor.yout;
+}
@@ -0,0 +1,41 @@
+pragma circom 2.1.9;
+
+include "merkle_tree_checker.circom";
+
+// Proves that the identity (secret, trapdoor) is a leaf of the tree with the given root, without
+// revealing which one, and that nullifier is its nullifier for external_nullifier:
+//   leaf = Poseidon([Poseidon([secret, 1]), trapdoor])
+//   nullifier = Poseidon([Poseidon([secret, 2]), external_nullifier])
+// The nullifier is the same each time the identity claims under the same external_nullifier, so a
+// validator that records it only accepts one claim per member. The keys derived from the secret
+// with the domains 1 and 2 keep nullifiers apart from commitments.
+template MembershipWithNullifier(levels) {
+    signal input root;
+    signal input nullifier;
+    signal input external_nullifier;
+    signal input secret;
+    signal input trapdoor;
+    signal input pathElements[levels];
+    signal input pathIndices[levels];
+
+    component commitmentKey = PoseidonHashLeftRight();
+    commitmentKey.left <== secret;
+    commitmentKey.right <== 1;
+    component commitment = PoseidonHashLeftRight();
+    commitment.left <== commitmentKey.hash;
+    commitment.right <== trapdoor;
+
+    component membership = MerkleTreeChecker(levels);
+    membership.leaf <== commitment.hash;
+    membership.root <== root;
+    membership.pathElements <== pathElements;
+    membership.pathIndices <== pathIndices;
+
+    component nullifierKey = PoseidonHashLeftRight();
+    nullifierKey.left <== secret;
+    nullifierKey.right <== 2;
+    component nullifierHash = PoseidonHashLeftRight();
+    nullifierHash.left <== nullifierKey.hash;
+    nullifierHash.right <== external_nullifier;
+    nullifier === nullifierHash.hash;
+}
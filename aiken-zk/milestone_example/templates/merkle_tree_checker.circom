@@ -1,14 +1,6 @@
 pragma circom 2.0.0;
 
-
-// Computes DummyHash([left, right]) which is an arbitrary hash function for testing purposes
-template DummyHashLeftRight() {
-    signal input left;
-    signal input right;
-    signal output hash;
-
-    hash <== 3 * left + 7 * right;
-}
+include "poseidon_bls12_381.circom";
 
 // if s == 0 returns [in[0], in[1]]
 // if s == 1 returns [in[1], in[0]]
@@ -39,7 +31,7 @@ template MerkleTreeChecker(levels) {
         selectors[i].in[1] <== pathElements[i];
         selectors[i].s <== pathIndices[i];
 
-        hashers[i] = DummyHashLeftRight();
+        hashers[i] = PoseidonHashLeftRight();
         hashers[i].left <== selectors[i].out[0];
         hashers[i].right <== selectors[i].out[1];
     }
@@ -0,0 +1,30 @@
+pragma circom 2.1.9;
+
+include "num2bits.circom";
+
+// Verifies lower_bound <= value < upper_bound, where value and lower_bound fit in n bits and
+// upper_bound in n + 1 bits, so that x < 2^n is range_check(n, x, 0, 2^n).
+template RangeCheck(n) {
+    // The differences below take n + 2 bits, which must stay under the BLS12-381 scalar field.
+    assert(n > 0 && n <= 252);
+    signal input value;
+    signal input lower_bound;
+    signal input upper_bound;
+
+    component valueBits = Num2Bits(n);
+    valueBits.in <== value;
+    component lowerBoundBits = Num2Bits(n);
+    lowerBoundBits.in <== lower_bound;
+    component upperBoundBits = Num2Bits(n + 1);
+    upperBoundBits.in <== upper_bound;
+
+    // value - lower_bound >= 0 sets the top bit of value - lower_bound + 2^n
+    component lowerDifference = Num2Bits(n + 1);
+    lowerDifference.in <== value - lower_bound + (1 << n);
+    lowerDifference.out[n] === 1;
+
+    // upper_bound - value - 1 >= 0 sets the top bit of upper_bound - value - 1 + 2^(n + 1)
+    component upperDifference = Num2Bits(n + 2);
+    upperDifference.in <== upper_bound - value - 1 + (1 << (n + 1));
+    upperDifference.out[n + 1] === 1;
+}
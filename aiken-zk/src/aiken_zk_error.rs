@@ -0,0 +1,232 @@
+use crate::compressed_groth16_proof_bls12_381::ProofCompressionError;
+use crate::compressed_verification_key_bls12_381::VerificationKeyCompressionError;
+use crate::eddsa::EddsaError;
+use crate::groth16_prover_bls12_381::ProvingError;
+use crate::groth16_verifier_bls12_381::VerificationError;
+use crate::inputs_validator::InputsError;
+use crate::membership::MembershipError;
+use crate::merkle_tree::MerkleTreeError;
+use crate::public_signals::PublicSignalError;
+use crate::r1cs_header::R1csError;
+use crate::witness_calculator::WitnessError;
+use crate::zk_examples::OffchainArgumentError;
+use aiken_lang::ast::Span;
+use aiken_lang::parser::error::ParseError;
+use miette::Diagnostic;
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum AikenZkError {
+    #[error("I couldn't lex the source code")]
+    #[diagnostic(code(aiken_zk::parse))]
+    Parse {
+        #[related]
+        errors: Vec<ParseError>,
+    },
+
+    #[error("{reason}")]
+    #[diagnostic(code(aiken_zk::parse::offchain_argument))]
+    InvalidOffchainArgument {
+        reason: OffchainArgumentError,
+        #[label("in this offchain call")]
+        span: Span,
+    },
+
+    #[error(
+        "Offchain function not present, or present but it is not one of the supported functions"
+    )]
+    #[diagnostic(
+        code(aiken_zk::parse::missing_offchain),
+        help("Write at least one `offchain <function>(...)` statement in the validator")
+    )]
+    MissingOffchain,
+
+    #[error(
+        "Amount of public inputs doesnt match: the circuit declares {expected}, the call passes {actual}"
+    )]
+    #[diagnostic(code(aiken_zk::parse::public_inputs))]
+    PublicInputCountMismatch {
+        expected: usize,
+        actual: usize,
+        #[label("this call passes {actual} public inputs")]
+        span: Span,
+    },
+
+    #[error("Not the expected kind of token: expected {expected}, found {found}")]
+    #[diagnostic(code(aiken_zk::parse::unexpected_token))]
+    UnexpectedToken {
+        expected: &'static str,
+        found: String,
+        #[label("in this offchain call")]
+        span: Option<Span>,
+    },
+
+    #[error("Failed to start command '{command}': {source}")]
+    #[diagnostic(
+        code(aiken_zk::toolchain::missing),
+        help("Check that circom, snarkjs and node are installed and accessible from path")
+    )]
+    ToolchainUnavailable { command: String, source: io::Error },
+
+    #[error("Command '{command}' failed with exit code {exit_code:?}. The error is {output}")]
+    #[diagnostic(code(aiken_zk::toolchain::failed))]
+    ToolchainFailed {
+        command: String,
+        exit_code: Option<i32>,
+        output: String,
+    },
+
+    #[error("Failed to compress the verification key: {0}")]
+    #[diagnostic(code(aiken_zk::toolchain::verification_key))]
+    VerificationKey(#[from] VerificationKeyCompressionError),
+
+    #[error("Failed to compress the proof: {0}")]
+    #[diagnostic(code(aiken_zk::toolchain::proof))]
+    Proof(#[from] ProofCompressionError),
+
+    #[error("Failed to read the compiled circuit: {0}")]
+    #[diagnostic(code(aiken_zk::toolchain::r1cs))]
+    R1cs(#[from] R1csError),
+
+    #[error("Failed to generate the proof: {0}")]
+    #[diagnostic(code(aiken_zk::prove))]
+    Proving(#[from] ProvingError),
+
+    #[error("Failed to verify the proof: {0}")]
+    #[diagnostic(code(aiken_zk::verify))]
+    Verification(#[from] VerificationError),
+
+    #[error("Failed to generate the witness: {0}")]
+    #[diagnostic(code(aiken_zk::witness))]
+    Witness(#[from] WitnessError),
+
+    #[error("Failed to build the merkle tree: {0}")]
+    #[diagnostic(code(aiken_zk::merkle_tree))]
+    MerkleTree(#[from] MerkleTreeError),
+
+    #[error("Failed to sign the message: {0}")]
+    #[diagnostic(code(aiken_zk::eddsa))]
+    Eddsa(#[from] EddsaError),
+
+    #[error("Failed to prove the membership: {0}")]
+    #[diagnostic(code(aiken_zk::membership))]
+    Membership(#[from] MembershipError),
+
+    #[error("{path} is not a valid build manifest: {source}")]
+    #[diagnostic(
+        code(aiken_zk::manifest::invalid),
+        help("Run `aiken-zk build` again to regenerate it")
+    )]
+    InvalidManifest {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[error("I can't tell which circuit of {path} to prove: {reason}")]
+    #[diagnostic(
+        code(aiken_zk::manifest::circuit),
+        help("Pass --proof-index with the position of the proof in the redeemer")
+    )]
+    CircuitSelection { path: String, reason: String },
+
+    #[error("{circuit_path} changed after {manifest_path} was written")]
+    #[diagnostic(
+        code(aiken_zk::manifest::stale),
+        help("Run `aiken-zk build` again so the proving key matches the circuit")
+    )]
+    StaleCircuit {
+        circuit_path: String,
+        manifest_path: String,
+    },
+
+    #[error("{path} doesn't match the inputs of the circuit")]
+    #[diagnostic(
+        code(aiken_zk::inputs),
+        help("Run `aiken-zk inputs template` to see the expected signals")
+    )]
+    InvalidInputs {
+        path: String,
+        #[related]
+        errors: Vec<InputsError>,
+    },
+
+    #[error("The public signals of the proof are not the expected ones")]
+    #[diagnostic(
+        code(aiken_zk::public_signals),
+        help(
+            "Name each expected value after a circuit signal or an Aiken identifier listed above"
+        )
+    )]
+    UnexpectedPublicSignals {
+        #[related]
+        errors: Vec<PublicSignalError>,
+    },
+
+    #[error("I/O error on {path}: {source}")]
+    #[diagnostic(code(aiken_zk::io))]
+    Io { path: String, source: io::Error },
+}
+
+impl AikenZkError {
+    // Exit codes follow sysexits.h: malformed input, unavailable or failing external tools
+    // and file system errors.
+    pub const PARSE_EXIT_CODE: i32 = 65;
+    pub const TOOLCHAIN_EXIT_CODE: i32 = 69;
+    pub const IO_EXIT_CODE: i32 = 74;
+
+    pub fn io(path: impl Into<String>, source: io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub fn located_at(self, offchain_span: Span) -> Self {
+        match self {
+            Self::UnexpectedToken {
+                expected,
+                found,
+                span: None,
+            } => Self::UnexpectedToken {
+                expected,
+                found,
+                span: Some(offchain_span),
+            },
+            error => error,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Io { .. }
+            | Self::Verification(VerificationError::Io { .. })
+            | Self::Witness(WitnessError::Io { .. })
+            | Self::Proving(ProvingError::Io { .. }) => Self::IO_EXIT_CODE,
+            Self::Parse { .. }
+            | Self::InvalidOffchainArgument { .. }
+            | Self::MissingOffchain
+            | Self::PublicInputCountMismatch { .. }
+            | Self::UnexpectedToken { .. }
+            | Self::InvalidManifest { .. }
+            | Self::CircuitSelection { .. }
+            | Self::StaleCircuit { .. }
+            | Self::InvalidInputs { .. }
+            | Self::UnexpectedPublicSignals { .. }
+            | Self::MerkleTree(_)
+            | Self::Eddsa(_)
+            | Self::Membership(_)
+            | Self::Verification(_)
+            | Self::Witness(WitnessError::Input { .. } | WitnessError::Failed { .. }) => {
+                Self::PARSE_EXIT_CODE
+            }
+            Self::ToolchainUnavailable { .. }
+            | Self::ToolchainFailed { .. }
+            | Self::VerificationKey(_)
+            | Self::Proof(_)
+            | Self::R1cs(_)
+            | Self::Proving(_)
+            | Self::Witness(_) => Self::TOOLCHAIN_EXIT_CODE,
+        }
+    }
+}
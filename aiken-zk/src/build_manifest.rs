@@ -0,0 +1,210 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::compiler::build_options::BuildOptions;
+use crate::compiler::compiled_circuit::{CompiledCircuit, PublicInput};
+use crate::entropy_generator::SeedCommitment;
+use crate::filename_without_extension_nor_path;
+use crate::r1cs_header::R1csHeader;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntropySource {
+    Random,
+    Seed,
+    EntropyFile,
+}
+
+// Everything an auditor needs to rebuild a validator and compare the result. The seeds are only
+// recorded as salted, deliberately slow commitments: a plain hash of a guessable seed would reveal
+// it, and with it the setup trapdoor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub source_path: String,
+    pub source_hash: String,
+    pub output_path: String,
+    pub output_hash: String,
+    pub build_dir: String,
+    pub ptau_path: String,
+    pub ptau_hash: String,
+    pub entropy_source: EntropySource,
+    pub seed_commitment: Option<SeedCommitment>,
+    pub contribution_seed_commitments: Vec<SeedCommitment>,
+    pub circuits: Vec<CircuitManifest>,
+}
+
+// One entry per offchain statement, in the order of the proofs expected by the validator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitManifest {
+    pub proof_index: Option<usize>,
+    pub circuit_path: String,
+    pub circuit_hash: String,
+    pub r1cs_path: String,
+    pub r1cs: R1csHeader,
+    pub n_public: u32,
+    pub zkey_path: String,
+    pub verification_key_path: String,
+    pub verification_key_hash: String,
+    pub public_inputs: Vec<PublicInput>,
+}
+
+impl CircuitManifest {
+    pub fn for_circuit(
+        circuit: &CompiledCircuit,
+        build_options: &BuildOptions,
+    ) -> Result<Self, AikenZkError> {
+        let circuit_name = filename_without_extension_nor_path(circuit.circuit_path.clone())
+            .unwrap_or_else(|| circuit.circuit_path.clone());
+        let r1cs_path = build_options.artifact_path(&format!("{}.r1cs", circuit_name));
+        let r1cs = R1csHeader::from_file(&r1cs_path)?;
+        let verification_key_path =
+            build_options.artifact_path(&format!("{}.json", circuit.verification_key_name));
+
+        Ok(Self {
+            proof_index: circuit.proof_index,
+            circuit_hash: BuildManifest::hash_circuit(&circuit.circuit_path)?,
+            circuit_path: circuit.circuit_path.clone(),
+            r1cs_path,
+            n_public: r1cs.n_public(),
+            r1cs,
+            zkey_path: build_options
+                .artifact_path(&format!("{}.zkey", circuit.verification_key_name)),
+            verification_key_hash: BuildManifest::hash_file(&verification_key_path)?,
+            verification_key_path,
+            public_inputs: circuit.public_inputs.clone(),
+        })
+    }
+
+    // The zkey only proves the circuit it was set up for: an edited circuit needs a new build.
+    pub fn ensure_circuit_is_unchanged(&self, manifest_path: &str) -> Result<(), AikenZkError> {
+        if BuildManifest::hash_circuit(&self.circuit_path)? != self.circuit_hash {
+            return Err(AikenZkError::StaleCircuit {
+                circuit_path: self.circuit_path.clone(),
+                manifest_path: manifest_path.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl BuildManifest {
+    const EXTENSION: &'static str = "zk-manifest.json";
+
+    // `validators/foo.ak` is described by `validators/foo.zk-manifest.json`.
+    pub fn path_for_validator(validator_path: &Path) -> PathBuf {
+        validator_path.with_extension(Self::EXTENSION)
+    }
+
+    pub fn hash(bytes: &[u8]) -> String {
+        hex::encode(Sha256::digest(bytes))
+    }
+
+    pub fn hash_file(path: &str) -> Result<String, AikenZkError> {
+        let mut hasher = Sha256::new();
+        File::open(path)
+            .and_then(|mut file| io::copy(&mut file, &mut hasher))
+            .map_err(|error| AikenZkError::io(path, error))?;
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    // A circuit is only unchanged if the files it includes are too. Includes that are not found
+    // from the including file, such as circom libraries, are left out.
+    pub fn hash_circuit(path: &str) -> Result<String, AikenZkError> {
+        let mut hasher = Sha256::new();
+        let mut pending = vec![PathBuf::from(path)];
+        let mut hashed = HashSet::new();
+        while let Some(circuit_path) = pending.pop() {
+            if !hashed.insert(circuit_path.clone()) {
+                continue;
+            }
+            let source = fs::read(&circuit_path)
+                .map_err(|error| AikenZkError::io(circuit_path.to_string_lossy(), error))?;
+            let circuit_dir = circuit_path.parent().unwrap_or(Path::new(""));
+            pending.extend(
+                Self::circom_includes(&String::from_utf8_lossy(&source))
+                    .iter()
+                    .map(|include| circuit_dir.join(include))
+                    .filter(|include_path| include_path.is_file())
+                    .rev(),
+            );
+            hasher.update(&source);
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    fn circom_includes(source: &str) -> Vec<String> {
+        source
+            .lines()
+            .filter(|line| line.trim_start().starts_with("include"))
+            .filter_map(|line| line.split('"').nth(1))
+            .map(String::from)
+            .collect()
+    }
+
+    // Builds record absolute paths. Relative ones are taken from the directory of the manifest,
+    // not from the current one.
+    pub fn from_file(path: &str) -> Result<Self, AikenZkError> {
+        let manifest = fs::read_to_string(path).map_err(|error| AikenZkError::io(path, error))?;
+        let manifest: Self =
+            serde_json::from_str(&manifest).map_err(|source| AikenZkError::InvalidManifest {
+                path: path.to_string(),
+                source,
+            })?;
+        Ok(manifest.anchored_at(Path::new(path).parent().unwrap_or(Path::new(""))))
+    }
+
+    fn anchored_at(self, manifest_dir: &Path) -> Self {
+        let anchored = |path: String| manifest_dir.join(path).to_string_lossy().to_string();
+        Self {
+            source_path: anchored(self.source_path),
+            output_path: anchored(self.output_path),
+            build_dir: anchored(self.build_dir),
+            ptau_path: anchored(self.ptau_path),
+            circuits: self
+                .circuits
+                .into_iter()
+                .map(|circuit| CircuitManifest {
+                    circuit_path: anchored(circuit.circuit_path),
+                    r1cs_path: anchored(circuit.r1cs_path),
+                    zkey_path: anchored(circuit.zkey_path),
+                    verification_key_path: anchored(circuit.verification_key_path),
+                    ..circuit
+                })
+                .collect(),
+            ..self
+        }
+    }
+
+    // Validators with a single offchain statement have a single, unindexed circuit.
+    pub fn circuit(
+        &self,
+        manifest_path: &str,
+        proof_index: Option<usize>,
+    ) -> Result<&CircuitManifest, AikenZkError> {
+        let selection_error = |reason: String| AikenZkError::CircuitSelection {
+            path: manifest_path.to_string(),
+            reason,
+        };
+        match (proof_index, self.circuits.as_slice()) {
+            (None, [circuit]) => Ok(circuit),
+            (None, circuits) => Err(selection_error(format!(
+                "the validator has {} circuits",
+                circuits.len()
+            ))),
+            (Some(index), circuits) => circuits
+                .iter()
+                .find(|circuit| circuit.proof_index == Some(index))
+                .ok_or_else(|| selection_error(format!("there is no proof with index {}", index))),
+        }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), AikenZkError> {
+        let manifest =
+            serde_json::to_string_pretty(self).expect("serializing a manifest cannot fail");
+        fs::write(path, manifest + "\n").map_err(|error| AikenZkError::io(path, error))
+    }
+}
@@ -1,8 +1,20 @@
-use crate::compiler::BUILD_DIR;
+use super::filename_without_extension_nor_path;
+use crate::aiken_zk_error::AikenZkError;
+use crate::build_manifest::BuildManifest;
+use crate::compiler::build_options::BuildOptions;
 use crate::compressed_groth16_proof_bls12_381::CompressedGroth16ProofBls12_381;
-use super::{filename_without_extension_nor_path};
+use crate::groth16_prover_bls12_381::Groth16ProverBls12_381;
+use crate::inputs_template::InputsTemplate;
+use crate::inputs_validator::InputsValidator;
+use crate::phase2_ceremony::Phase2Ceremony;
+use crate::prover::Prover;
+use crate::witness_calculator::{WitnessCalculator, WitnessError};
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Proof;
+use serde_json::Value;
 use std::fs;
-use std::io::{Error, ErrorKind};
+use std::io;
+use std::io::ErrorKind;
 use std::path::Path;
 use std::process::{Command, Stdio};
 
@@ -12,11 +24,18 @@ pub struct CircomCircuit {
 }
 
 impl CircomCircuit {
-    pub fn from(circom_source_code_path: String) -> Self {
-        Self {
-            circom_source_code_path: circom_source_code_path.clone(),
-            filename: filename_without_extension_nor_path(circom_source_code_path).unwrap(),
-        }
+    pub fn from(circom_source_code_path: String) -> Result<Self, AikenZkError> {
+        let filename = filename_without_extension_nor_path(circom_source_code_path.clone())
+            .ok_or_else(|| {
+                AikenZkError::io(
+                    &circom_source_code_path,
+                    io::Error::new(ErrorKind::InvalidInput, "not the path of a circom file"),
+                )
+            })?;
+        Ok(Self {
+            circom_source_code_path,
+            filename,
+        })
     }
 
     pub fn filename(&self) -> String {
@@ -25,58 +44,154 @@ impl CircomCircuit {
 
     // verification key generation
 
-    pub fn generate_verification_key(&self, rand: (&str, &str)) -> Result<(), Error> {
-        let circuit_name = self.filename();
-        let output_path = BUILD_DIR;
-
-        fs::create_dir_all(output_path).expect("Failed to create output directory");
-
-        self.compile_to_r1cs(output_path);
-
-        let r1cs_path = format!("{}{}.r1cs", output_path, circuit_name);
-        let zkey_0 = format!("{}{}_0000.zkey", output_path, circuit_name);
-        let zkey_1 = format!("{}{}_0001.zkey", output_path, circuit_name);
-        let zkey_2 = format!("{}{}_0002.zkey", output_path, circuit_name);
-        let verification_key_zkey = "verification_key.zkey".to_string();
-        let verification_key_json = format!("{}verification_key.json", output_path);
+    pub fn generate_verification_key(
+        &self,
+        rand: (&str, &str),
+        build_options: &BuildOptions,
+    ) -> Result<(), AikenZkError> {
+        self.generate_verification_key_with_name(rand, build_options, "verification_key")
+    }
 
-        self.groth16_setup(&r1cs_path, "ceremony.ptau", &zkey_0);
-        self.contribute(&zkey_0, &zkey_1, "1st Contributor Name", rand.0);
-        self.contribute(&zkey_1, &zkey_2, "Second contribution Name", rand.1);
-        let hex_entr = "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
-        self.beacon(
-            &zkey_0,
+    pub fn generate_verification_key_with_name(
+        &self,
+        rand: (&str, &str),
+        build_options: &BuildOptions,
+        verification_key_name: &str,
+    ) -> Result<(), AikenZkError> {
+        let circuit_name = self.filename();
+        let zkey_0 = build_options.artifact_path(&format!("{}_0000.zkey", circuit_name));
+        let zkey_1 = build_options.artifact_path(&format!("{}_0001.zkey", circuit_name));
+        let zkey_2 = build_options.artifact_path(&format!("{}_0002.zkey", circuit_name));
+        let verification_key_zkey =
+            build_options.artifact_path(&format!("{}.zkey", verification_key_name));
+        let verification_key_json =
+            build_options.artifact_path(&format!("{}.json", verification_key_name));
+
+        self.initialize_ceremony(build_options, &zkey_0)?;
+        if build_options.has_reproducible_setup() {
+            Phase2Ceremony::beacon(
+                &zkey_0,
+                &zkey_1,
+                rand.0,
+                Phase2Ceremony::DEFAULT_BEACON_ITERATIONS,
+                "1st Contributor Name",
+            )?;
+            Phase2Ceremony::beacon(
+                &zkey_1,
+                &zkey_2,
+                rand.1,
+                Phase2Ceremony::DEFAULT_BEACON_ITERATIONS,
+                "Second contribution Name",
+            )?;
+        } else {
+            Phase2Ceremony::contribute(&zkey_0, &zkey_1, "1st Contributor Name", rand.0)?;
+            Phase2Ceremony::contribute(&zkey_1, &zkey_2, "Second contribution Name", rand.1)?;
+        }
+        Phase2Ceremony::beacon(
+            &zkey_2,
             &verification_key_zkey,
-            hex_entr,
-            10,
+            Phase2Ceremony::DEFAULT_BEACON_HASH,
+            Phase2Ceremony::DEFAULT_BEACON_ITERATIONS,
             "Final Beacon phase2",
-        );
-        self.export_to_json_verification_key(&verification_key_zkey, &verification_key_json);
+        )?;
+        Phase2Ceremony::export_verification_key(&verification_key_zkey, &verification_key_json)
+    }
 
-        Ok(())
+    // phase 2 ceremony
+
+    pub fn initialize_ceremony(
+        &self,
+        build_options: &BuildOptions,
+        output_zkey: &str,
+    ) -> Result<(), AikenZkError> {
+        let r1cs_path = self.compile_to_r1cs_in_build_dir(build_options)?;
+        self.groth16_setup(&r1cs_path, &Self::ptau_path(build_options), output_zkey)
+    }
+
+    pub fn verify_ceremony(
+        &self,
+        build_options: &BuildOptions,
+        zkey: &str,
+    ) -> Result<(), AikenZkError> {
+        let r1cs_path = self.compile_to_r1cs_in_build_dir(build_options)?;
+        Self::run_command_or_fail(
+            Command::new("snarkjs").args([
+                "zkey",
+                "verify",
+                &r1cs_path,
+                &Self::ptau_path(build_options),
+                zkey,
+            ]),
+            "zkey verify",
+        )
     }
 
     // proof generation
 
     pub fn generate_groth16_proof(
         &self,
+        build_options: &BuildOptions,
         verification_key_path: &str,
         inputs_path: &str,
-    ) -> CompressedGroth16ProofBls12_381 {
-        let build_path = BUILD_DIR.to_string();
-        self.create_directory_if_not_exists(&build_path);
+    ) -> Result<CompressedGroth16ProofBls12_381, AikenZkError> {
+        let build_dir = build_options.build_dir().to_string_lossy().to_string();
+        let proof = Prover::for_circuit(
+            build_options.project_root(),
+            &self.circom_source_code_path,
+            verification_key_path,
+        )?
+        .with_build_dir(build_dir.clone())
+        .prove_file(&build_options.in_project(inputs_path).to_string_lossy())?;
+        proof.write_snarkjs_files(&build_dir)?;
+        Ok(proof.compressed)
+    }
+
+    // The witness and the proof are computed in memory: only the witness generator is cached in
+    // the build directory.
+    pub fn prove_in_build_dir(
+        &self,
+        build_dir: &str,
+        zkey_path: &str,
+        inputs: &Value,
+        inputs_name: &str,
+    ) -> Result<(Proof<Bls12_381>, Vec<Fr>), AikenZkError> {
+        let build_path = Self::build_path(build_dir);
+        fs::create_dir_all(&build_path).map_err(|error| AikenZkError::io(&build_path, error))?;
+
+        self.compile_witness_generator_unless_cached(&build_path)?;
+        InputsValidator::from_circuit_files(
+            &self.r1cs_path(&build_path),
+            &self.symbols_path(&build_path),
+        )?
+        .ensure_valid(inputs, inputs_name)?;
+        let witness = self.calculate_witness(inputs, inputs_name, &build_path)?;
+        Ok(Groth16ProverBls12_381::from_zkey(zkey_path)?.prove(&witness)?)
+    }
 
-        self.compile_witness_generator(&build_path);
-        self.generate_witness(inputs_path, &build_path);
-        self.generate_groth16_proof_from_witness(verification_key_path, &build_path);
+    // Layout of the inputs, as compiled by the last proof generated in the build directory.
+    pub fn inputs_template_in_build_dir(
+        &self,
+        build_dir: &str,
+    ) -> Result<InputsTemplate, AikenZkError> {
+        let build_path = Self::build_path(build_dir);
+        Ok(InputsTemplate::from_circuit_files(
+            &self.r1cs_path(&build_path),
+            &self.symbols_path(&build_path),
+        )?)
+    }
 
-        CompressedGroth16ProofBls12_381::from_json(&build_path)
+    fn build_path(build_dir: &str) -> String {
+        if build_dir.ends_with('/') {
+            build_dir.to_string()
+        } else {
+            build_dir.to_string() + "/"
+        }
     }
 
     // private - verification key generation
 
-    fn compile_to_r1cs(&self, output_path: &str) {
-        self.run_command_or_fail(
+    fn compile_to_r1cs(&self, output_path: &str) -> Result<(), AikenZkError> {
+        Self::run_command_or_fail(
             Command::new("circom").args([
                 &self.circom_source_code_path,
                 "--r1cs",
@@ -86,11 +201,64 @@ impl CircomCircuit {
                 output_path,
             ]),
             "circom",
-        );
+        )
+    }
+
+    fn compile_to_r1cs_in_build_dir(
+        &self,
+        build_options: &BuildOptions,
+    ) -> Result<String, AikenZkError> {
+        let ptau_path = Self::ptau_path(build_options);
+        if !build_options.ptau_path().is_file() {
+            return Err(AikenZkError::io(
+                &ptau_path,
+                std::io::Error::new(ErrorKind::NotFound, "powers of tau ceremony file not found"),
+            ));
+        }
+        let output_path = build_options.build_dir().to_string_lossy().to_string();
+        fs::create_dir_all(&output_path).map_err(|error| AikenZkError::io(&output_path, error))?;
+
+        self.compile_to_r1cs(&output_path)?;
+        Ok(build_options.artifact_path(&format!("{}.r1cs", self.filename())))
+    }
+
+    // The symbols file maps every wire to its signal name, e.g. `main.in[3]`.
+    pub fn compile_with_symbols(
+        &self,
+        build_options: &BuildOptions,
+    ) -> Result<(String, String), AikenZkError> {
+        let output_path = build_options.build_dir().to_string_lossy().to_string();
+        fs::create_dir_all(&output_path).map_err(|error| AikenZkError::io(&output_path, error))?;
+
+        Self::run_command_or_fail(
+            Command::new("circom").args([
+                &self.circom_source_code_path,
+                "--r1cs",
+                "--sym",
+                "-p",
+                "bls12381",
+                "-o",
+                &output_path,
+            ]),
+            "circom",
+        )?;
+        Ok((
+            build_options.artifact_path(&format!("{}.r1cs", self.filename())),
+            build_options.artifact_path(&format!("{}.sym", self.filename())),
+        ))
+    }
+
+    fn ptau_path(build_options: &BuildOptions) -> String {
+        build_options.ptau_path().to_string_lossy().to_string()
     }
 
-    fn groth16_setup(&self, r1cs_path: &str, ceremony_path: &str, output_zkey: &str) {
-        self.run_command_or_fail(
+    fn groth16_setup(
+        &self,
+        r1cs_path: &str,
+        ceremony_path: &str,
+        output_zkey: &str,
+    ) -> Result<(), AikenZkError> {
+        Self::run_command_or_fail(
             Command::new("snarkjs").args([
                 "groth16",
                 "setup",
@@ -99,160 +267,129 @@ impl CircomCircuit {
                 output_zkey,
             ]),
             "groth16 setup",
-        );
+        )
     }
 
-    fn contribute(&self, input_zkey: &str, output_zkey: &str, name: &str, entropy: &str) {
-        let new_entropy = &format!("{}\n", entropy);
-        let mut child = Command::new("snarkjs")
-            .args([
-                "zkey",
-                "contribute",
-                input_zkey,
-                output_zkey,
-                &format!("--name={}", name),
-                "-v",
-            ])
-            .stdin(Stdio::piped())
-            .spawn()
-            .expect("Failed to start zkey contribute");
-
-        use std::io::Write;
-        let stdin = child.stdin.as_mut().expect("Failed to open stdin");
-        stdin
-            .write_all(new_entropy.as_bytes())
-            .expect("Failed to write entropy");
-
-        let status = child.wait().expect("Failed to wait on zkey contribute");
-        if !status.success() {
-            panic!(
-                "zkey contribute '{}' failed with exit code {:?}",
-                name,
-                status.code()
-            );
-        }
-    }
-
-    fn beacon(
+    pub fn export_r1cs_to_json(
         &self,
-        zkey_input: &str,
-        zkey_output: &str,
-        entropy_hex: &str,
-        rounds: u32,
-        name: &str,
-    ) {
-        self.run_command_or_fail(
-            Command::new("snarkjs").args([
-                "zkey",
-                "beacon",
-                zkey_input,
-                zkey_output,
-                entropy_hex,
-                &rounds.to_string(),
-                &format!("-n={}", name),
-            ]),
-            "zkey beacon",
-        );
-    }
-
-    fn export_to_json_verification_key(&self, zkey: &str, output_json: &str) {
-        self.run_command_or_fail(
-            Command::new("snarkjs").args(["zkey", "export", "verificationkey", zkey, output_json]),
-            "export verification key",
-        );
-    }
-
-    pub fn export_r1cs_to_json(&self, r1cs_path: &String, r1cs_json_path: &String) {
-        self.run_command_or_fail(
+        r1cs_path: &String,
+        r1cs_json_path: &String,
+    ) -> Result<(), AikenZkError> {
+        Self::run_command_or_fail(
             Command::new("snarkjs").args(["r1cs", "export", "json", &r1cs_path, &r1cs_json_path]),
             "export r1cs",
-        );
+        )
     }
 
     // private - proof generation
 
-    fn compile_witness_generator(&self, output_path: &str) {
-        self.run_command_or_fail(
+    // The r1cs and symbols describe the input signals, to validate the inputs before proving.
+    fn compile_witness_generator(&self, output_path: &str) -> Result<(), AikenZkError> {
+        Self::run_command_or_fail(
             Command::new("circom").args([
                 &self.circom_source_code_path,
                 "--wasm",
+                "--r1cs",
+                "--sym",
                 "-p",
                 "bls12381",
                 "-o",
                 output_path,
             ]),
             "circom",
-        );
+        )
     }
 
-    fn generate_witness(&self, inputs_path: &str, build_path: &str) {
-        let circuit_filename = self.circuit_filename();
-        let mut binding = Command::new("node");
-        let cmd = binding
-            .arg(build_path.to_string() + circuit_filename + "_js/generate_witness.js")
-            .arg(build_path.to_string() + circuit_filename + "_js/" + circuit_filename + ".wasm")
-            .arg(inputs_path)
-            .arg(build_path.to_string() + "witness.wtns");
-        self.run_command_or_fail(cmd, "Generate witnesses");
+    // The wasm only depends on the circuit and its includes, so it is compiled again only when
+    // they change.
+    fn compile_witness_generator_unless_cached(
+        &self,
+        build_path: &str,
+    ) -> Result<(), AikenZkError> {
+        let wasm_path = self.wasm_path(build_path);
+        let circuit_hash_path = wasm_path.clone() + ".sha256";
+        let circuit_hash = BuildManifest::hash_circuit(&self.circom_source_code_path)?;
+
+        let cached_hash = fs::read_to_string(&circuit_hash_path).ok();
+        let cached_files = [
+            wasm_path,
+            self.r1cs_path(build_path),
+            self.symbols_path(build_path),
+        ];
+        if cached_files.iter().all(|path| Path::new(path).is_file())
+            && cached_hash.as_deref() == Some(&circuit_hash)
+        {
+            return Ok(());
+        }
+        self.compile_witness_generator(build_path)?;
+        fs::write(&circuit_hash_path, circuit_hash)
+            .map_err(|error| AikenZkError::io(&circuit_hash_path, error))
     }
 
-    fn generate_groth16_proof_from_witness(&self, verification_key_path: &str, build_path: &str) {
-        Command::new("snarkjs")
-            .arg("groth16")
-            .arg("prove")
-            .arg(verification_key_path)
-            .arg(build_path.to_string() + "witness.wtns")
-            .arg(build_path.to_string() + "proof.json")
-            .arg(build_path.to_string() + "public.json")
-            .output()
-            .unwrap();
+    fn r1cs_path(&self, build_path: &str) -> String {
+        build_path.to_string() + self.circuit_filename() + ".r1cs"
     }
 
-    fn circuit_filename(&self) -> &str {
-        &Self::filename_from_path(&self.circom_source_code_path)
+    fn symbols_path(&self, build_path: &str) -> String {
+        build_path.to_string() + self.circuit_filename() + ".sym"
     }
 
-    // file system
+    fn wasm_path(&self, build_path: &str) -> String {
+        let circuit_filename = self.circuit_filename();
+        build_path.to_string() + circuit_filename + "_js/" + circuit_filename + ".wasm"
+    }
 
-    fn filename_from_path(path: &String) -> &str {
-        Path::new(path).file_stem().unwrap().to_str().unwrap()
+    fn calculate_witness(
+        &self,
+        inputs: &Value,
+        inputs_name: &str,
+        build_path: &str,
+    ) -> Result<Vec<Fr>, AikenZkError> {
+        let Value::Object(inputs) = inputs else {
+            return Err(WitnessError::Input {
+                signal: inputs_name.to_string(),
+                reason: "the inputs are not a json object".to_string(),
+            }
+            .into());
+        };
+        let witness =
+            WitnessCalculator::from_file(&self.wasm_path(build_path))?.calculate(inputs)?;
+        Ok(witness.into_iter().map(Fr::from).collect())
     }
 
-    fn create_directory_if_not_exists(&self, build_path: &str) {
-        fs::create_dir(build_path)
-            .or_else(|error| {
-                if error.kind() == ErrorKind::AlreadyExists {
-                    Ok(())
-                } else {
-                    Err(error)
-                }
-            })
-            .expect("Couldnt create directory");
+    fn circuit_filename(&self) -> &str {
+        &self.filename
     }
 
-    pub fn run_command_or_fail(&self, cmd: &mut Command, label: &str) {
+    // file system
+
+    pub fn run_command_or_fail(cmd: &mut Command, label: &str) -> Result<(), AikenZkError> {
         let command_output = cmd
             .stdout(Stdio::null())
             .output()
-            .unwrap_or_else(|_| panic!("Failed to start command '{}'", label));
+            .map_err(|error| Self::toolchain_unavailable(label, error))?;
         if !command_output.status.success() {
-            panic!(
-                "Command '{}' failed with exit code {:?}{}{}",
-                label,
-                command_output.status.code(),
-                ". The error is ".to_string()
-                    + &String::from_utf8_lossy(&command_output.stderr)
-                        .trim()
-                        .to_string(),
-                if command_output.stdout.len() > 0 {
-                    ". The stdout is ".to_string()
-                        + &String::from_utf8_lossy(&command_output.stdout)
-                            .trim()
-                            .to_string()
-                } else {
-                    "".to_string()
-                },
-            );
+            return Err(AikenZkError::ToolchainFailed {
+                command: label.to_string(),
+                exit_code: command_output.status.code(),
+                output: String::from_utf8_lossy(&command_output.stderr)
+                    .trim()
+                    .to_string()
+                    + &if !command_output.stdout.is_empty() {
+                        ". The stdout is ".to_string()
+                            + String::from_utf8_lossy(&command_output.stdout).trim()
+                    } else {
+                        "".to_string()
+                    },
+            });
+        }
+        Ok(())
+    }
+
+    pub fn toolchain_unavailable(label: &str, error: std::io::Error) -> AikenZkError {
+        AikenZkError::ToolchainUnavailable {
+            command: label.to_string(),
+            source: error,
         }
     }
 }
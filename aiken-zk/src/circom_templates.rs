@@ -0,0 +1,56 @@
+use crate::aiken_zk_error::AikenZkError;
+use rust_embed::RustEmbed;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(RustEmbed)]
+#[folder = "milestone_example/templates/"]
+struct EmbeddedTemplates;
+
+// The templates of the built-in offchain functions ship inside the binary. The build writes the
+// ones it uses into the build directory, so projects do not need a copy of their own.
+pub struct CircomTemplates;
+
+impl CircomTemplates {
+    pub const DIRECTORY: &'static str = "templates";
+
+    pub fn file_name(template_file_name: &str) -> String {
+        format!("{}.circom", template_file_name)
+    }
+
+    pub fn source(template_file_name: &str) -> Option<Vec<u8>> {
+        EmbeddedTemplates::get(&Self::file_name(template_file_name))
+            .map(|file| file.data.into_owned())
+    }
+
+    // Rewritten only when the embedded template differs, so the file keeps its timestamp. The
+    // templates it includes are written next to it.
+    pub fn write_to_build_dir(
+        template_file_name: &str,
+        build_dir: &Path,
+    ) -> Result<PathBuf, AikenZkError> {
+        let templates_dir = build_dir.join(Self::DIRECTORY);
+        let template_path = templates_dir.join(Self::file_name(template_file_name));
+        let source = Self::source(template_file_name).expect("Embedded template missing");
+
+        if fs::read(&template_path).ok().as_deref() != Some(source.as_slice()) {
+            fs::create_dir_all(&templates_dir)
+                .map_err(|error| AikenZkError::io(templates_dir.to_string_lossy(), error))?;
+            fs::write(&template_path, &source)
+                .map_err(|error| AikenZkError::io(template_path.to_string_lossy(), error))?;
+        }
+        for included_template in Self::included_templates(&source) {
+            Self::write_to_build_dir(&included_template, build_dir)?;
+        }
+        Ok(template_path)
+    }
+
+    fn included_templates(source: &[u8]) -> Vec<String> {
+        String::from_utf8_lossy(source)
+            .lines()
+            .filter(|line| line.trim_start().starts_with("include"))
+            .filter_map(|line| line.split('"').nth(1)?.strip_suffix(".circom"))
+            .map(String::from)
+            .collect()
+    }
+}
@@ -1,10 +1,13 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::build_manifest::EntropySource;
 use crate::cli::subcommand::Subcommand;
-use crate::compiler::aiken_zk_compiler::AikenZkCompiler;
+use crate::compiler::build_options::BuildOptions;
 use crate::entropy_generator::EntropyGenerator;
-use clap::{ArgMatches, Command};
+use crate::project::Project;
+use clap::{Arg, ArgMatches, Command};
+use miette::NamedSource;
 use std::fs;
-use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct BuildCommand {}
 
@@ -14,10 +17,36 @@ impl Subcommand for BuildCommand {
     fn create_subcommand() -> Command {
         let input = Self::create_required_argument_with_id(Self::BUILD_COMMAND_SOURCE_ARG_NAME);
         let output = Self::create_required_argument_with_id(Self::BUILD_COMMAND_OUTPUT_ARG_NAME);
+        let build_dir =
+            Self::create_optional_argument_with_id(Self::BUILD_COMMAND_BUILD_DIR_ARG_NAME)
+                .help("Directory for the circuit artifacts [default: build/<source name>]");
+        let ptau = Self::create_optional_argument_with_id(Self::BUILD_COMMAND_PTAU_ARG_NAME)
+            .help("Powers of tau ceremony file [default: ceremony.ptau]");
+        let circuit_name = Arg::new(Self::BUILD_COMMAND_CIRCUIT_NAME_ARG_NAME)
+            .long(Self::BUILD_COMMAND_CIRCUIT_NAME_ARG_NAME)
+            .required(false)
+            .help("Name of the generated circom circuit [default: <source name>]");
+        let seed = Arg::new(Self::BUILD_COMMAND_SEED_ARG_NAME)
+            .long(Self::BUILD_COMMAND_SEED_ARG_NAME)
+            .required(false)
+            .help("Secret seed for a reproducible setup. Whoever knows it can forge proofs");
+        let project_root =
+            Self::create_optional_argument_with_id(Self::BUILD_COMMAND_PROJECT_ROOT_ARG_NAME)
+                .help("Directory the other paths are relative to [default: current directory]");
+        let entropy_file =
+            Self::create_optional_argument_with_id(Self::BUILD_COMMAND_ENTROPY_FILE_ARG_NAME)
+                .conflicts_with(Self::BUILD_COMMAND_SEED_ARG_NAME)
+                .help("File whose contents are used as the secret seed");
 
         Command::new(Self::SUBCOMMAND_NAME)
             .arg(input.clone())
             .arg(output.clone())
+            .arg(project_root)
+            .arg(build_dir)
+            .arg(ptau)
+            .arg(circuit_name)
+            .arg(seed)
+            .arg(entropy_file)
     }
 
     fn for_name(name: &str) -> bool {
@@ -26,26 +55,62 @@ impl Subcommand for BuildCommand {
 
     fn evaluate(&self, matches: &ArgMatches) {
         let (source_path, output_path) = Self::get_arguments(matches);
-        Self::create_validators_dir_lazy();
-        Self::execute_command(source_path, output_path);
+        let (entropy_generator, entropy_source) = Self::get_entropy_generator(matches)
+            .unwrap_or_else(|error| Self::exit_with_diagnostic(error, None));
+        let build_options = Self::get_build_options(matches, source_path)
+            .unwrap_or_else(|error| Self::exit_with_diagnostic(error, None));
+        let project = Project::new(build_options).with_entropy(entropy_generator, entropy_source);
+        let project_source_path = project.build_options().in_project(source_path);
+        let source_offchain_aiken = fs::read_to_string(&project_source_path)
+            .map_err(|error| AikenZkError::io(project_source_path.to_string_lossy(), error))
+            .unwrap_or_else(|error| Self::exit_with_diagnostic(error, None));
+
+        let project_output_path = project.build_options().in_project(output_path);
+        let result = Self::create_output_dir_lazy(&project_output_path).and_then(|_| {
+            project
+                .build(source_path, &source_offchain_aiken, output_path)?
+                .write()
+        });
+        if let Err(error) = result {
+            let source_code =
+                NamedSource::new(project_source_path.to_string_lossy(), source_offchain_aiken);
+            Self::exit_with_diagnostic(error, Some(source_code));
+        }
     }
 }
 
 impl BuildCommand {
     const BUILD_COMMAND_SOURCE_ARG_NAME: &'static str = "source_path";
     const BUILD_COMMAND_OUTPUT_ARG_NAME: &'static str = "output_path";
+    const BUILD_COMMAND_PROJECT_ROOT_ARG_NAME: &'static str = "project-root";
+    const BUILD_COMMAND_BUILD_DIR_ARG_NAME: &'static str = "build-dir";
+    const BUILD_COMMAND_PTAU_ARG_NAME: &'static str = "ptau";
+    const BUILD_COMMAND_CIRCUIT_NAME_ARG_NAME: &'static str = "circuit-name";
+    const BUILD_COMMAND_SEED_ARG_NAME: &'static str = "seed";
+    const BUILD_COMMAND_ENTROPY_FILE_ARG_NAME: &'static str = "entropy-file";
 
-    fn execute_command(source_path: &PathBuf, output_path: &PathBuf) {
-        let source_offchain_aiken = fs::read_to_string(source_path).unwrap();
-
-        let entropy_generator = EntropyGenerator::new();
-        let output_zk_aiken = AikenZkCompiler::apply_modifications_to_src_for_token(
-            source_offchain_aiken,
-            "output".to_string(),
-            (&entropy_generator.generate(), &entropy_generator.generate()),
-        );
-
-        fs::write(output_path, output_zk_aiken).expect("output file write failed");
+    fn get_entropy_generator(
+        subcommand_matches: &ArgMatches,
+    ) -> Result<(EntropyGenerator, EntropySource), AikenZkError> {
+        if let Some(seed) = subcommand_matches.get_one::<String>(Self::BUILD_COMMAND_SEED_ARG_NAME)
+        {
+            return Ok((
+                EntropyGenerator::from_seed(seed.as_bytes()),
+                EntropySource::Seed,
+            ));
+        }
+        if let Some(entropy_file) = Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::BUILD_COMMAND_ENTROPY_FILE_ARG_NAME,
+        ) {
+            let seed = fs::read(entropy_file)
+                .map_err(|error| AikenZkError::io(entropy_file.to_string_lossy(), error))?;
+            return Ok((
+                EntropyGenerator::from_seed(&seed),
+                EntropySource::EntropyFile,
+            ));
+        }
+        Ok((EntropyGenerator::new(), EntropySource::Random))
     }
 
     fn get_arguments(subcommand_matches: &ArgMatches) -> (&PathBuf, &PathBuf) {
@@ -56,15 +121,41 @@ impl BuildCommand {
         (source_path, output_path)
     }
 
-    pub fn create_validators_dir_lazy() {
-        fs::create_dir("validators")
-            .or_else(|error| {
-                if error.kind() == ErrorKind::AlreadyExists {
-                    Ok(())
-                } else {
-                    Err(error)
-                }
-            })
-            .expect("Couldnt create dir");
+    fn get_build_options(
+        subcommand_matches: &ArgMatches,
+        source_path: &Path,
+    ) -> Result<BuildOptions, AikenZkError> {
+        let project_root = Self::project_root(Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::BUILD_COMMAND_PROJECT_ROOT_ARG_NAME,
+        ))?;
+        let mut build_options = BuildOptions::for_source(project_root, source_path);
+        if let Some(circuit_name) =
+            subcommand_matches.get_one::<String>(Self::BUILD_COMMAND_CIRCUIT_NAME_ARG_NAME)
+        {
+            build_options = build_options.with_circuit_name(circuit_name.clone());
+        }
+        if let Some(build_dir) = Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::BUILD_COMMAND_BUILD_DIR_ARG_NAME,
+        ) {
+            build_options = build_options.with_build_dir(build_dir.clone());
+        }
+        if let Some(ptau_path) =
+            Self::get_optional_argument_value(subcommand_matches, Self::BUILD_COMMAND_PTAU_ARG_NAME)
+        {
+            build_options = build_options.with_ptau_path(ptau_path.clone());
+        }
+        Ok(build_options)
+    }
+
+    fn create_output_dir_lazy(output_path: &Path) -> Result<(), AikenZkError> {
+        match output_path.parent() {
+            Some(output_dir) if !output_dir.as_os_str().is_empty() => {
+                fs::create_dir_all(output_dir)
+                    .map_err(|error| AikenZkError::io(output_dir.to_string_lossy(), error))
+            }
+            _ => Ok(()),
+        }
     }
 }
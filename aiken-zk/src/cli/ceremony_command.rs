@@ -0,0 +1,247 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::circom_circuit::CircomCircuit;
+use crate::cli::subcommand::Subcommand;
+use crate::compiler::build_options::BuildOptions;
+use crate::entropy_generator::EntropyGenerator;
+use crate::phase2_ceremony::Phase2Ceremony;
+use clap::{Arg, ArgMatches, Command, value_parser};
+use colored::Colorize;
+use std::path::Path;
+
+pub struct CeremonyCommand {}
+
+impl Subcommand for CeremonyCommand {
+    const SUBCOMMAND_NAME: &'static str = "ceremony";
+
+    fn create_subcommand() -> Command {
+        Command::new(Self::SUBCOMMAND_NAME)
+            .about("Run the circuit specific (phase 2) trusted setup step by step")
+            .subcommand_required(true)
+            .subcommand(Self::create_init_command())
+            .subcommand(Self::create_contribute_command())
+            .subcommand(Self::create_beacon_command())
+            .subcommand(Self::create_verify_command())
+    }
+
+    fn for_name(name: &str) -> bool {
+        name == Self::SUBCOMMAND_NAME
+    }
+
+    fn evaluate(&self, matches: &ArgMatches) {
+        let result = match matches.subcommand() {
+            Some((Self::INIT_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_init_command(sub_matches)
+            }
+            Some((Self::CONTRIBUTE_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_contribute_command(sub_matches)
+            }
+            Some((Self::BEACON_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_beacon_command(sub_matches)
+            }
+            Some((Self::VERIFY_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_verify_command(sub_matches)
+            }
+            _ => {
+                panic!("Unknown or missing subcommand for `ceremony`");
+            }
+        };
+        if let Err(error) = result {
+            Self::exit_with_diagnostic(error, None);
+        }
+    }
+}
+
+impl CeremonyCommand {
+    const INIT_SUBCOMMAND_NAME: &'static str = "init";
+    const CONTRIBUTE_SUBCOMMAND_NAME: &'static str = "contribute";
+    const BEACON_SUBCOMMAND_NAME: &'static str = "beacon";
+    const VERIFY_SUBCOMMAND_NAME: &'static str = "verify";
+
+    const CEREMONY_COMMAND_CIRCOM_ARG_NAME: &'static str = "circom_path";
+    const CEREMONY_COMMAND_ZKEY_ARG_NAME: &'static str = "zkey_path";
+    const CEREMONY_COMMAND_INPUT_ZKEY_ARG_NAME: &'static str = "input_zkey_path";
+    const CEREMONY_COMMAND_OUTPUT_ZKEY_ARG_NAME: &'static str = "output_zkey_path";
+    const CEREMONY_COMMAND_VK_ARG_NAME: &'static str = "verification_key_path";
+    const CEREMONY_COMMAND_BUILD_DIR_ARG_NAME: &'static str = "build-dir";
+    const CEREMONY_COMMAND_PTAU_ARG_NAME: &'static str = "ptau";
+    const CEREMONY_COMMAND_NAME_ARG_NAME: &'static str = "name";
+    const CEREMONY_COMMAND_BEACON_HASH_ARG_NAME: &'static str = "beacon-hash";
+    const CEREMONY_COMMAND_ITERATIONS_ARG_NAME: &'static str = "iterations";
+
+    const DEFAULT_CONTRIBUTOR_NAME: &'static str = "aiken-zk contributor";
+    const BEACON_NAME: &'static str = "Final Beacon phase2";
+
+    fn create_init_command() -> Command {
+        Command::new(Self::INIT_SUBCOMMAND_NAME)
+            .about("Compile the circuit and create the initial zkey of the ceremony")
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_CIRCOM_ARG_NAME,
+            ))
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_OUTPUT_ZKEY_ARG_NAME,
+            ))
+            .args(Self::create_circuit_options())
+    }
+
+    fn create_contribute_command() -> Command {
+        Command::new(Self::CONTRIBUTE_SUBCOMMAND_NAME)
+            .about("Add a contribution with fresh random entropy on top of the given zkey")
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_INPUT_ZKEY_ARG_NAME,
+            ))
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_OUTPUT_ZKEY_ARG_NAME,
+            ))
+            .arg(
+                Arg::new(Self::CEREMONY_COMMAND_NAME_ARG_NAME)
+                    .long(Self::CEREMONY_COMMAND_NAME_ARG_NAME)
+                    .default_value(Self::DEFAULT_CONTRIBUTOR_NAME)
+                    .help("Name recorded with the contribution"),
+            )
+    }
+
+    fn create_beacon_command() -> Command {
+        Command::new(Self::BEACON_SUBCOMMAND_NAME)
+            .about("Finalize the ceremony and export the verification key")
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_INPUT_ZKEY_ARG_NAME,
+            ))
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_OUTPUT_ZKEY_ARG_NAME,
+            ))
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_VK_ARG_NAME,
+            ))
+            .arg(
+                Arg::new(Self::CEREMONY_COMMAND_BEACON_HASH_ARG_NAME)
+                    .long(Self::CEREMONY_COMMAND_BEACON_HASH_ARG_NAME)
+                    .default_value(Phase2Ceremony::DEFAULT_BEACON_HASH)
+                    .help("Public random value (hex) applied as the last contribution"),
+            )
+            .arg(
+                Arg::new(Self::CEREMONY_COMMAND_ITERATIONS_ARG_NAME)
+                    .long(Self::CEREMONY_COMMAND_ITERATIONS_ARG_NAME)
+                    .value_parser(value_parser!(u32))
+                    .help("Beacon hash iterations, as a power of two [default: 10]"),
+            )
+    }
+
+    fn create_verify_command() -> Command {
+        Command::new(Self::VERIFY_SUBCOMMAND_NAME)
+            .about("Check that a zkey was derived from the circuit and the powers of tau")
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_CIRCOM_ARG_NAME,
+            ))
+            .arg(Self::create_required_argument_with_id(
+                Self::CEREMONY_COMMAND_ZKEY_ARG_NAME,
+            ))
+            .args(Self::create_circuit_options())
+    }
+
+    fn create_circuit_options() -> [Arg; 2] {
+        [
+            Self::create_optional_argument_with_id(Self::CEREMONY_COMMAND_BUILD_DIR_ARG_NAME)
+                .help("Directory for the circuit artifacts [default: build/<circuit name>]"),
+            Self::create_optional_argument_with_id(Self::CEREMONY_COMMAND_PTAU_ARG_NAME)
+                .help("Powers of tau ceremony file [default: ceremony.ptau]"),
+        ]
+    }
+
+    fn execute_init_command(matches: &ArgMatches) -> Result<(), AikenZkError> {
+        let (circuit, build_options) = Self::get_circuit_arguments(matches)?;
+        let output_zkey =
+            Self::get_path_argument(matches, Self::CEREMONY_COMMAND_OUTPUT_ZKEY_ARG_NAME);
+
+        circuit.initialize_ceremony(&build_options, &output_zkey)?;
+        println!(
+            "{}",
+            format!("Initial zkey written to {}", output_zkey).green()
+        );
+        Ok(())
+    }
+
+    fn execute_contribute_command(matches: &ArgMatches) -> Result<(), AikenZkError> {
+        let input_zkey =
+            Self::get_path_argument(matches, Self::CEREMONY_COMMAND_INPUT_ZKEY_ARG_NAME);
+        let output_zkey =
+            Self::get_path_argument(matches, Self::CEREMONY_COMMAND_OUTPUT_ZKEY_ARG_NAME);
+        let name = matches
+            .get_one::<String>(Self::CEREMONY_COMMAND_NAME_ARG_NAME)
+            .expect("Value for command not found");
+
+        Phase2Ceremony::contribute(
+            &input_zkey,
+            &output_zkey,
+            name,
+            &EntropyGenerator::new().generate(),
+        )
+    }
+
+    fn execute_beacon_command(matches: &ArgMatches) -> Result<(), AikenZkError> {
+        let input_zkey =
+            Self::get_path_argument(matches, Self::CEREMONY_COMMAND_INPUT_ZKEY_ARG_NAME);
+        let output_zkey =
+            Self::get_path_argument(matches, Self::CEREMONY_COMMAND_OUTPUT_ZKEY_ARG_NAME);
+        let verification_key = Self::get_path_argument(matches, Self::CEREMONY_COMMAND_VK_ARG_NAME);
+        let beacon_hash = matches
+            .get_one::<String>(Self::CEREMONY_COMMAND_BEACON_HASH_ARG_NAME)
+            .expect("Value for command not found");
+        let iterations = matches
+            .get_one::<u32>(Self::CEREMONY_COMMAND_ITERATIONS_ARG_NAME)
+            .copied()
+            .unwrap_or(Phase2Ceremony::DEFAULT_BEACON_ITERATIONS);
+
+        Phase2Ceremony::beacon(
+            &input_zkey,
+            &output_zkey,
+            beacon_hash,
+            iterations,
+            Self::BEACON_NAME,
+        )?;
+        Phase2Ceremony::export_verification_key(&output_zkey, &verification_key)?;
+        println!(
+            "{}",
+            format!("Verification key written to {}", verification_key).green()
+        );
+        Ok(())
+    }
+
+    fn execute_verify_command(matches: &ArgMatches) -> Result<(), AikenZkError> {
+        let (circuit, build_options) = Self::get_circuit_arguments(matches)?;
+        let zkey = Self::get_path_argument(matches, Self::CEREMONY_COMMAND_ZKEY_ARG_NAME);
+
+        circuit.verify_ceremony(&build_options, &zkey)?;
+        println!(
+            "{}",
+            format!("{} is a valid zkey for the circuit", zkey).green()
+        );
+        Ok(())
+    }
+
+    fn get_circuit_arguments(
+        matches: &ArgMatches,
+    ) -> Result<(CircomCircuit, BuildOptions), AikenZkError> {
+        let circom_path = Self::get_argument_value(matches, Self::CEREMONY_COMMAND_CIRCOM_ARG_NAME);
+        let mut build_options = BuildOptions::for_source(Self::project_root(None)?, circom_path);
+        if let Some(build_dir) =
+            Self::get_optional_argument_value(matches, Self::CEREMONY_COMMAND_BUILD_DIR_ARG_NAME)
+        {
+            build_options = build_options.with_build_dir(build_dir.clone());
+        }
+        if let Some(ptau_path) =
+            Self::get_optional_argument_value(matches, Self::CEREMONY_COMMAND_PTAU_ARG_NAME)
+        {
+            build_options = build_options.with_ptau_path(ptau_path.clone());
+        }
+        let circuit = CircomCircuit::from(Self::path_to_string(circom_path))?;
+        Ok((circuit, build_options))
+    }
+
+    fn get_path_argument(matches: &ArgMatches, argument_id: &str) -> String {
+        Self::path_to_string(Self::get_argument_value(matches, argument_id))
+    }
+
+    fn path_to_string(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+}
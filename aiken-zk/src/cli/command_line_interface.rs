@@ -1,7 +1,10 @@
 use crate::cli::build_command::BuildCommand;
+use crate::cli::ceremony_command::CeremonyCommand;
+use crate::cli::inputs_command::InputsCommand;
 use crate::cli::new_command::NewCommand;
 use crate::cli::prove_command::ProveCommand;
 use crate::cli::subcommand::Subcommand;
+use crate::cli::verify_command::VerifyCommand;
 use clap::Command;
 
 pub struct CommandLineInterface {
@@ -35,7 +38,10 @@ impl CommandLineInterface {
             .subcommand_required(true)
             .subcommand(BuildCommand::create_subcommand())
             .subcommand(ProveCommand::create_subcommand())
-            .subcommand(NewCommand::create_subcommand());
+            .subcommand(NewCommand::create_subcommand())
+            .subcommand(VerifyCommand::create_subcommand())
+            .subcommand(CeremonyCommand::create_subcommand())
+            .subcommand(InputsCommand::create_subcommand());
         Self { main_command }
     }
 
@@ -43,7 +49,17 @@ impl CommandLineInterface {
         let main_command_matches = self.main_command.clone().get_matches();
         match main_command_matches.subcommand() {
             Some(subcommand) => {
-                execute_subcommand!(subcommand, [BuildCommand, ProveCommand, NewCommand]);
+                execute_subcommand!(
+                    subcommand,
+                    [
+                        BuildCommand,
+                        ProveCommand,
+                        NewCommand,
+                        VerifyCommand,
+                        CeremonyCommand,
+                        InputsCommand
+                    ]
+                );
             }
             None => {
                 panic!("No command given");
@@ -0,0 +1,348 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::cli::subcommand::Subcommand;
+use crate::compiler::aiken_zk_compiler::AikenZkCompiler;
+use crate::compiler::build_options::BuildOptions;
+use crate::compiler::token_zk::TokenZK;
+use crate::eddsa::EddsaSignature;
+use crate::inputs_template::InputsTemplate;
+use crate::membership::MembershipIdentity;
+use crate::merkle_tree::MerkleTree;
+use aiken_lang::ast::Span;
+use clap::{Arg, ArgMatches, Command, value_parser};
+use miette::NamedSource;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct InputsCommand {}
+
+impl Subcommand for InputsCommand {
+    const SUBCOMMAND_NAME: &'static str = "inputs";
+
+    fn create_subcommand() -> Command {
+        Command::new(Self::SUBCOMMAND_NAME)
+            .about("Help writing the inputs json of a proof")
+            .subcommand_required(true)
+            .subcommand(Self::create_template_command())
+            .subcommand(Self::create_merkle_command())
+            .subcommand(Self::create_eddsa_command())
+            .subcommand(Self::create_membership_command())
+    }
+
+    fn for_name(name: &str) -> bool {
+        name == Self::SUBCOMMAND_NAME
+    }
+
+    fn evaluate(&self, matches: &ArgMatches) {
+        match matches.subcommand() {
+            Some((Self::TEMPLATE_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_template_command(sub_matches)
+            }
+            Some((Self::MERKLE_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_merkle_command(sub_matches)
+            }
+            Some((Self::EDDSA_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_eddsa_command(sub_matches)
+            }
+            Some((Self::MEMBERSHIP_SUBCOMMAND_NAME, sub_matches)) => {
+                Self::execute_membership_command(sub_matches)
+            }
+            _ => {
+                panic!("Unknown or missing subcommand for `inputs`");
+            }
+        }
+    }
+}
+
+impl InputsCommand {
+    const TEMPLATE_SUBCOMMAND_NAME: &'static str = "template";
+    const MERKLE_SUBCOMMAND_NAME: &'static str = "merkle";
+    const EDDSA_SUBCOMMAND_NAME: &'static str = "eddsa";
+    const MEMBERSHIP_SUBCOMMAND_NAME: &'static str = "membership";
+
+    const INPUTS_COMMAND_SOURCE_ARG_NAME: &'static str = "source_path";
+    const INPUTS_COMMAND_OUTPUT_ARG_NAME: &'static str = "output_path";
+    const INPUTS_COMMAND_PROOF_INDEX_ARG_NAME: &'static str = "proof-index";
+    const INPUTS_COMMAND_LEAVES_ARG_NAME: &'static str = "leaves_path";
+    const INPUTS_COMMAND_LEAF_INDEX_ARG_NAME: &'static str = "leaf_index";
+    const INPUTS_COMMAND_DEPTH_ARG_NAME: &'static str = "depth";
+    const INPUTS_COMMAND_PRIVATE_KEY_ARG_NAME: &'static str = "private_key_path";
+    const INPUTS_COMMAND_MSG_ARG_NAME: &'static str = "msg";
+    const INPUTS_COMMAND_IDENTITY_ARG_NAME: &'static str = "identity_path";
+    const INPUTS_COMMAND_EXTERNAL_NULLIFIER_ARG_NAME: &'static str = "external_nullifier";
+
+    fn create_template_command() -> Command {
+        Command::new(Self::TEMPLATE_SUBCOMMAND_NAME)
+            .about("Write a skeleton of the inputs json of an offchain statement")
+            .arg(Self::create_required_argument_with_id(
+                Self::INPUTS_COMMAND_SOURCE_ARG_NAME,
+            ))
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_OUTPUT_ARG_NAME)
+                    .required(false)
+                    .value_parser(value_parser!(PathBuf))
+                    .help("Where to write the skeleton [default: standard output]"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_PROOF_INDEX_ARG_NAME)
+                    .long(Self::INPUTS_COMMAND_PROOF_INDEX_ARG_NAME)
+                    .required(false)
+                    .value_parser(value_parser!(usize))
+                    .help("Which offchain statement, when the source has several"),
+            )
+    }
+
+    fn create_merkle_command() -> Command {
+        Command::new(Self::MERKLE_SUBCOMMAND_NAME)
+            .about("Write the merkle_tree_checker inputs of a leaf of a Poseidon Merkle tree")
+            .arg(
+                Self::create_required_argument_with_id(Self::INPUTS_COMMAND_LEAVES_ARG_NAME)
+                    .help("A json list with the leaves of the tree"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_LEAF_INDEX_ARG_NAME)
+                    .required(true)
+                    .value_parser(value_parser!(usize))
+                    .help("The position of the leaf in the list"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_OUTPUT_ARG_NAME)
+                    .required(false)
+                    .value_parser(value_parser!(PathBuf))
+                    .help("Where to write the inputs [default: standard output]"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_DEPTH_ARG_NAME)
+                    .long(Self::INPUTS_COMMAND_DEPTH_ARG_NAME)
+                    .required(false)
+                    .value_parser(value_parser!(usize))
+                    .help(
+                        "The merklePathLength of the statement [default: the smallest that fits]",
+                    ),
+            )
+    }
+
+    fn create_eddsa_command() -> Command {
+        Command::new(Self::EDDSA_SUBCOMMAND_NAME)
+            .about("Sign a message and write the eddsa_verify inputs of the signature")
+            .arg(
+                Self::create_required_argument_with_id(Self::INPUTS_COMMAND_PRIVATE_KEY_ARG_NAME)
+                    .help("A file with the private key, a number below the Jubjub subgroup order"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_MSG_ARG_NAME)
+                    .required(true)
+                    .help("The message, a number of the BLS12-381 scalar field"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_OUTPUT_ARG_NAME)
+                    .required(false)
+                    .value_parser(value_parser!(PathBuf))
+                    .help("Where to write the inputs [default: standard output]"),
+            )
+    }
+
+    fn create_membership_command() -> Command {
+        Command::new(Self::MEMBERSHIP_SUBCOMMAND_NAME)
+            .about("Write the membership_with_nullifier inputs of a member of a Merkle tree")
+            .arg(
+                Self::create_required_argument_with_id(Self::INPUTS_COMMAND_LEAVES_ARG_NAME)
+                    .help("A json list with the commitments of the members"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_LEAF_INDEX_ARG_NAME)
+                    .required(true)
+                    .value_parser(value_parser!(usize))
+                    .help("The position of the commitment of the member in the list"),
+            )
+            .arg(
+                Self::create_required_argument_with_id(Self::INPUTS_COMMAND_IDENTITY_ARG_NAME)
+                    .help("A json object with the secret and the trapdoor of the member"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_EXTERNAL_NULLIFIER_ARG_NAME)
+                    .required(true)
+                    .help("The external nullifier of the claim"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_OUTPUT_ARG_NAME)
+                    .required(false)
+                    .value_parser(value_parser!(PathBuf))
+                    .help("Where to write the inputs [default: standard output]"),
+            )
+            .arg(
+                Arg::new(Self::INPUTS_COMMAND_DEPTH_ARG_NAME)
+                    .long(Self::INPUTS_COMMAND_DEPTH_ARG_NAME)
+                    .required(false)
+                    .value_parser(value_parser!(usize))
+                    .help("The levels of the statement [default: the smallest that fits]"),
+            )
+    }
+
+    fn execute_template_command(subcommand_matches: &ArgMatches) {
+        let source_path =
+            Self::get_argument_value(subcommand_matches, Self::INPUTS_COMMAND_SOURCE_ARG_NAME);
+        let output_path = Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::INPUTS_COMMAND_OUTPUT_ARG_NAME,
+        );
+        let proof_index = subcommand_matches
+            .get_one::<usize>(Self::INPUTS_COMMAND_PROOF_INDEX_ARG_NAME)
+            .copied();
+        let source_offchain_aiken = fs::read_to_string(source_path)
+            .map_err(|error| AikenZkError::io(source_path.to_string_lossy(), error))
+            .unwrap_or_else(|error| Self::exit_with_diagnostic(error, None));
+
+        let result = Self::inputs_template(source_path, &source_offchain_aiken, proof_index)
+            .and_then(|inputs_template| Self::write_template(&inputs_template, output_path));
+        if let Err(error) = result {
+            let source_code =
+                NamedSource::new(source_path.to_string_lossy(), source_offchain_aiken);
+            Self::exit_with_diagnostic(error, Some(source_code));
+        }
+    }
+
+    fn execute_merkle_command(subcommand_matches: &ArgMatches) {
+        let leaves_path =
+            Self::get_argument_value(subcommand_matches, Self::INPUTS_COMMAND_LEAVES_ARG_NAME);
+        let leaf_index = *subcommand_matches
+            .get_one::<usize>(Self::INPUTS_COMMAND_LEAF_INDEX_ARG_NAME)
+            .expect("Value for command not found");
+        let output_path = Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::INPUTS_COMMAND_OUTPUT_ARG_NAME,
+        );
+        let depth = subcommand_matches
+            .get_one::<usize>(Self::INPUTS_COMMAND_DEPTH_ARG_NAME)
+            .copied();
+
+        let result = MerkleTree::leaves_from_file(&leaves_path.to_string_lossy())
+            .and_then(|leaves| Ok(MerkleTree::from_leaves(&leaves, depth)?.proof(leaf_index)?))
+            .and_then(|proof| {
+                Self::write_json(&format!("{:#}", proof.to_inputs_json()), output_path)
+            });
+        if let Err(error) = result {
+            Self::exit_with_diagnostic(error, None);
+        }
+    }
+
+    fn execute_eddsa_command(subcommand_matches: &ArgMatches) {
+        let private_key_path = Self::get_argument_value(
+            subcommand_matches,
+            Self::INPUTS_COMMAND_PRIVATE_KEY_ARG_NAME,
+        );
+        let msg = subcommand_matches
+            .get_one::<String>(Self::INPUTS_COMMAND_MSG_ARG_NAME)
+            .expect("Value for command not found");
+        let output_path = Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::INPUTS_COMMAND_OUTPUT_ARG_NAME,
+        );
+
+        let result = EddsaSignature::private_key_from_file(&private_key_path.to_string_lossy())
+            .and_then(|private_key| {
+                Ok(EddsaSignature::sign(
+                    &private_key,
+                    EddsaSignature::msg_from_text(msg)?,
+                )?)
+            })
+            .and_then(|signature| {
+                Self::write_json(&format!("{:#}", signature.to_inputs_json()), output_path)
+            });
+        if let Err(error) = result {
+            Self::exit_with_diagnostic(error, None);
+        }
+    }
+
+    fn execute_membership_command(subcommand_matches: &ArgMatches) {
+        let leaves_path =
+            Self::get_argument_value(subcommand_matches, Self::INPUTS_COMMAND_LEAVES_ARG_NAME);
+        let leaf_index = *subcommand_matches
+            .get_one::<usize>(Self::INPUTS_COMMAND_LEAF_INDEX_ARG_NAME)
+            .expect("Value for command not found");
+        let identity_path =
+            Self::get_argument_value(subcommand_matches, Self::INPUTS_COMMAND_IDENTITY_ARG_NAME);
+        let external_nullifier = subcommand_matches
+            .get_one::<String>(Self::INPUTS_COMMAND_EXTERNAL_NULLIFIER_ARG_NAME)
+            .expect("Value for command not found");
+        let output_path = Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::INPUTS_COMMAND_OUTPUT_ARG_NAME,
+        );
+        let depth = subcommand_matches
+            .get_one::<usize>(Self::INPUTS_COMMAND_DEPTH_ARG_NAME)
+            .copied();
+
+        let result = MembershipIdentity::from_file(&identity_path.to_string_lossy())
+            .and_then(|identity| {
+                let leaves = MerkleTree::leaves_from_file(&leaves_path.to_string_lossy())?;
+                let tree = MerkleTree::from_leaves(&leaves, depth)?;
+                let external_nullifier = MembershipIdentity::field_element(
+                    Self::INPUTS_COMMAND_EXTERNAL_NULLIFIER_ARG_NAME,
+                    &Value::String(external_nullifier.clone()),
+                )?;
+                identity.to_inputs_json(&tree, leaf_index, external_nullifier)
+            })
+            .and_then(|inputs| Self::write_json(&format!("{:#}", inputs), output_path));
+        if let Err(error) = result {
+            Self::exit_with_diagnostic(error, None);
+        }
+    }
+
+    fn inputs_template(
+        source_path: &Path,
+        source_offchain_aiken: &str,
+        proof_index: Option<usize>,
+    ) -> Result<InputsTemplate, AikenZkError> {
+        let offchain_tokens = AikenZkCompiler::detect_code_to_replace(source_offchain_aiken)?;
+        let (offchain_token, offchain_token_span) =
+            Self::select_offchain_token(source_path, &offchain_tokens, proof_index)?;
+
+        let build_options = BuildOptions::for_source(Self::project_root(None)?, source_path);
+        InputsTemplate::for_offchain(offchain_token, &build_options)
+            .map_err(|error| error.located_at(*offchain_token_span))
+    }
+
+    // Statements are numbered like the proofs of the built validator.
+    fn select_offchain_token<'a>(
+        source_path: &Path,
+        offchain_tokens: &'a [(TokenZK, Span)],
+        proof_index: Option<usize>,
+    ) -> Result<&'a (TokenZK, Span), AikenZkError> {
+        let selection_error = |reason: String| AikenZkError::CircuitSelection {
+            path: source_path.to_string_lossy().to_string(),
+            reason,
+        };
+        match (proof_index, offchain_tokens) {
+            (_, []) => Err(AikenZkError::MissingOffchain),
+            (None, [offchain_token]) => Ok(offchain_token),
+            (None, offchain_tokens) => Err(selection_error(format!(
+                "the validator has {} offchain statements",
+                offchain_tokens.len()
+            ))),
+            (Some(index), offchain_tokens) => offchain_tokens
+                .get(index)
+                .filter(|_| offchain_tokens.len() > 1)
+                .ok_or_else(|| selection_error(format!("there is no proof with index {}", index))),
+        }
+    }
+
+    fn write_template(
+        inputs_template: &InputsTemplate,
+        output_path: Option<&PathBuf>,
+    ) -> Result<(), AikenZkError> {
+        Self::write_json(&inputs_template.to_json(), output_path)
+    }
+
+    fn write_json(json: &str, output_path: Option<&PathBuf>) -> Result<(), AikenZkError> {
+        let json = json.to_string() + "\n";
+        match output_path {
+            Some(output_path) => fs::write(output_path, json)
+                .map_err(|error| AikenZkError::io(output_path.to_string_lossy(), error)),
+            None => {
+                print!("{}", json);
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,5 +1,8 @@
 mod build_command;
+mod ceremony_command;
 pub mod command_line_interface;
+mod inputs_command;
 mod new_command;
 mod prove_command;
 mod subcommand;
+mod verify_command;
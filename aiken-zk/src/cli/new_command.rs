@@ -1,10 +1,11 @@
+use crate::aiken_zk_error::AikenZkError;
 use crate::cli::subcommand::Subcommand;
 use crate::filename_without_extension_nor_path;
 use clap::{ArgMatches, Command};
 use colored::Colorize;
 use rust_embed::RustEmbed;
 use std::path::{Path, PathBuf};
-use std::{env, fs, io};
+use std::{fs, io};
 
 #[derive(RustEmbed)]
 #[folder = "milestone_example/"]
@@ -44,17 +45,20 @@ impl NewCommand {
 
         // Change aiken.toml to match the project name
 
-        fs::create_dir(&project_name).expect("Unable to create working directory");
-        Self::copy_embedded_dir(project_name).expect("Failed to populate working directory");
-        Self::move_to_working_dir(project_name).expect("Failed to jump into working directory");
-        Self::install_javascript_dependencies();
+        let project_path = project_name.to_string_lossy();
+        fs::create_dir(project_name)
+            .and_then(|_| Self::copy_embedded_dir(project_name))
+            .unwrap_or_else(|error| {
+                Self::exit_with_diagnostic(AikenZkError::io(project_path, error), None)
+            });
+        Self::install_javascript_dependencies(project_name);
         Self::check_presence_of_dependencies();
     }
 
-    fn install_javascript_dependencies() {
+    fn install_javascript_dependencies(project_path: &Path) {
         let usable_manager = Self::obtain_system_usable_package_manager();
         if let Some(usable_manager_name) = usable_manager {
-            Self::install_dependencies_in_directory(usable_manager_name, ".");
+            Self::install_dependencies_in_directory(usable_manager_name, project_path);
         } else {
             eprintln!(
                 "{}",
@@ -79,15 +83,19 @@ impl NewCommand {
         })
     }
 
-    fn install_dependencies_in_directory(usable_manager: &str, directory: &str) {
-        let package_manager_status_curve_compress = std::process::Command::new(usable_manager)
+    fn install_dependencies_in_directory(usable_manager: &str, directory: &Path) {
+        let package_manager_status = std::process::Command::new(usable_manager)
             .arg("install")
-            .current_dir(Path::new(directory))
+            .current_dir(directory)
             .status()
-            .expect(&format!("Unable to install dependencies in {directory}"));
-        if !package_manager_status_curve_compress.success() {
+            .expect(&format!(
+                "Unable to install dependencies in {}",
+                directory.display()
+            ));
+        if !package_manager_status.success() {
             eprintln!(
-                "{usable_manager} installation failed in {directory}, you need to install manually"
+                "{usable_manager} installation failed in {}, you need to install manually",
+                directory.display()
             )
         }
     }
@@ -109,8 +117,7 @@ impl NewCommand {
         fs::write(
             root.join(Path::new("aiken.toml")),
             Self::aiken_toml_for_project_with_name(&project_name),
-        )
-        .expect("Couldn't create aiken.toml");
+        )?;
 
         for file in ProjectTemplate::iter().filter(|f| {
             let splitted_path = f.split('/').collect::<Vec<&str>>();
@@ -136,10 +143,6 @@ impl NewCommand {
         Ok(())
     }
 
-    fn move_to_working_dir(working_directory_path: &PathBuf) -> Result<(), io::Error> {
-        env::set_current_dir(working_directory_path)
-    }
-
     fn check_presence_of_dependencies() {
         if std::process::Command::new("aiken")
             .arg("--version")
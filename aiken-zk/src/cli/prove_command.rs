@@ -1,11 +1,14 @@
-use crate::circom_circuit::CircomCircuit;
+use crate::aiken_zk_error::AikenZkError;
 use crate::cli::subcommand::Subcommand;
+use crate::compressed_groth16_proof_bls12_381::CompressedGroth16ProofBls12_381;
 use crate::presenter::compressed_groth16_proof_bls12_381_to_aiken_presenter::CompressedGroth16ProofBls12_381ToAikenPresenter;
+use crate::presenter::compressed_groth16_proof_bls12_381_to_json_presenter::CompressedGroth16ProofBls12_381ToJsonPresenter;
 use crate::presenter::meshjs_zk_redeemer_presenter::MeshJsZKRedeemerPresenter;
-use clap::{Arg, ArgMatches, Command};
+use crate::prover::Prover;
+use crate::public_signals::Expectation;
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command, value_parser};
 use std::fs;
-use std::path::{Path, PathBuf};
-use crate::presenter::compressed_groth16_proof_bls12_381_to_json_presenter::CompressedGroth16ProofBls12_381ToJsonPresenter;
+use std::path::PathBuf;
 
 pub struct ProveCommand {}
 
@@ -13,35 +16,11 @@ impl Subcommand for ProveCommand {
     const SUBCOMMAND_NAME: &'static str = "prove";
 
     fn create_subcommand() -> Command {
-        let circom_path =
-            Self::create_required_argument_with_id(Self::PROVE_COMMAND_CIRCOM_ARG_NAME);
-        let verification_key_path =
-            Self::create_required_argument_with_id(Self::PROVE_COMMAND_VK_ARG_NAME);
-        let inputs_path =
-            Self::create_required_argument_with_id(Self::PROVE_COMMAND_INPUT_ARG_NAME);
-        let output_path =
-            Self::create_required_argument_with_id(Self::PROVE_COMMAND_OUTPUT_ARG_NAME);
-
         Command::new(Self::SUBCOMMAND_NAME)
             .subcommand_required(true)
-            .subcommand(Self::create_proof_for_aiken_command(
-                &circom_path,
-                &verification_key_path,
-                &inputs_path,
-                &output_path,
-            ))
-            .subcommand(Self::create_json_proof_command(
-                &circom_path,
-                &verification_key_path,
-                &inputs_path,
-                &output_path,
-            ))
-            .subcommand(Self::create_library_for_meshjs_command(
-                circom_path,
-                verification_key_path,
-                inputs_path,
-                output_path,
-            ))
+            .subcommand(Self::create_prove_command_for_format(Self::AIKEN_FORMAT))
+            .subcommand(Self::create_prove_command_for_format(Self::JSON_FORMAT))
+            .subcommand(Self::create_prove_command_for_format(Self::MESHJS_FORMAT))
     }
 
     fn for_name(name: &str) -> bool {
@@ -50,32 +29,9 @@ impl Subcommand for ProveCommand {
 
     fn evaluate(&self, matches: &ArgMatches) {
         match matches.subcommand() {
-            Some(subcommand) => {
-                let (match_name, sub_matches) = subcommand;
-                let (circom_path, verification_key_path, inputs_path, output_path) =
-                    Self::get_prove_arguments(sub_matches);
-
-                if match_name == "aiken" {
-                    Self::execute_aiken_prove_command(
-                        circom_path,
-                        verification_key_path,
-                        inputs_path,
-                        output_path,
-                    );
-                } else if match_name == "meshjs" {
-                    Self::execute_meshjs_prove_command(
-                        circom_path,
-                        verification_key_path,
-                        inputs_path,
-                        output_path,
-                    );
-                } else if match_name == "json" {
-                    Self::execute_json_prove_command(
-                        circom_path,
-                        verification_key_path,
-                        inputs_path,
-                        output_path,
-                    );
+            Some((format, sub_matches)) => {
+                if let Err(error) = Self::execute_prove_command(format, sub_matches) {
+                    Self::exit_with_diagnostic(error, None);
                 }
             }
             _ => {
@@ -86,124 +42,138 @@ impl Subcommand for ProveCommand {
 }
 
 impl ProveCommand {
-    const PROVE_COMMAND_CIRCOM_ARG_NAME: &'static str = "circom_path";
-    const PROVE_COMMAND_VK_ARG_NAME: &'static str = "verification_key_path";
+    const AIKEN_FORMAT: &'static str = "aiken";
+    const JSON_FORMAT: &'static str = "json";
+    const MESHJS_FORMAT: &'static str = "meshjs";
+
+    const PROVE_COMMAND_VALIDATOR_ARG_NAME: &'static str = "validator";
+    const PROVE_COMMAND_PROOF_INDEX_ARG_NAME: &'static str = "proof-index";
+    const PROVE_COMMAND_CIRCOM_ARG_NAME: &'static str = "circom";
+    const PROVE_COMMAND_ZKEY_ARG_NAME: &'static str = "zkey";
+    const PROVE_COMMAND_EXPECT_ARG_NAME: &'static str = "expect";
     const PROVE_COMMAND_INPUT_ARG_NAME: &'static str = "inputs_path";
     const PROVE_COMMAND_OUTPUT_ARG_NAME: &'static str = "output_proof_path";
 
-    fn create_proof_for_aiken_command(
-        circom_path: &Arg,
-        verification_key_path: &Arg,
-        inputs_path: &Arg,
-        output_path: &Arg,
-    ) -> Command {
-        Command::new("aiken")
-            .arg(circom_path.clone())
-            .arg(verification_key_path.clone())
-            .arg(inputs_path.clone())
-            .arg(output_path.clone())
-    }
-
-    fn create_json_proof_command(
-        circom_path: &Arg,
-        verification_key_path: &Arg,
-        inputs_path: &Arg,
-        output_path: &Arg,
-    ) -> Command {
-        Command::new("json")
-            .arg(circom_path.clone())
-            .arg(verification_key_path.clone())
-            .arg(inputs_path.clone())
-            .arg(output_path.clone())
-    }
-
-    fn create_library_for_meshjs_command(
-        circom_path: Arg,
-        verification_key_path: Arg,
-        inputs_path: Arg,
-        output_path: Arg,
-    ) -> Command {
-        Command::new("meshjs")
-            .arg(circom_path.clone())
-            .arg(verification_key_path.clone())
-            .arg(inputs_path.clone())
+    fn create_prove_command_for_format(format: &'static str) -> Command {
+        let validator =
+            Self::create_optional_argument_with_id(Self::PROVE_COMMAND_VALIDATOR_ARG_NAME)
+                .help("Validator generated by `build`: its manifest locates the circuit and zkey");
+        let proof_index = Arg::new(Self::PROVE_COMMAND_PROOF_INDEX_ARG_NAME)
+            .long(Self::PROVE_COMMAND_PROOF_INDEX_ARG_NAME)
+            .required(false)
+            .value_parser(value_parser!(usize))
+            .requires(Self::PROVE_COMMAND_VALIDATOR_ARG_NAME)
+            .help("Which proof of a validator with several offchain statements");
+        let circom_path =
+            Self::create_optional_argument_with_id(Self::PROVE_COMMAND_CIRCOM_ARG_NAME)
+                .requires(Self::PROVE_COMMAND_ZKEY_ARG_NAME)
+                .help("Circom circuit, when not proving for a validator");
+        let zkey_path = Self::create_optional_argument_with_id(Self::PROVE_COMMAND_ZKEY_ARG_NAME)
+            .requires(Self::PROVE_COMMAND_CIRCOM_ARG_NAME)
+            .help("Proving key of the circuit, when not proving for a validator");
+        let expect = Arg::new(Self::PROVE_COMMAND_EXPECT_ARG_NAME)
+            .long(Self::PROVE_COMMAND_EXPECT_ARG_NAME)
+            .value_name("NAME=VALUE")
+            .required(false)
+            .action(ArgAction::Append)
+            .value_parser(Expectation::parse)
+            .help("Fail unless the named public signal of the proof has this value");
+        let inputs_path =
+            Self::create_required_argument_with_id(Self::PROVE_COMMAND_INPUT_ARG_NAME);
+        let output_path = Arg::new(Self::PROVE_COMMAND_OUTPUT_ARG_NAME)
+            .required(false)
+            .value_parser(value_parser!(PathBuf))
+            .help(format!(
+                "Where to write the proof [default: {}]",
+                Self::default_output_path(format)
+            ));
+
+        Command::new(format)
+            .arg(validator)
+            .arg(proof_index)
+            .arg(circom_path)
+            .arg(zkey_path)
+            .arg(expect)
+            .arg(inputs_path)
             .arg(output_path)
+            .group(
+                ArgGroup::new("circuit")
+                    .args([
+                        Self::PROVE_COMMAND_VALIDATOR_ARG_NAME,
+                        Self::PROVE_COMMAND_CIRCOM_ARG_NAME,
+                    ])
+                    .required(true),
+            )
     }
 
-    fn get_prove_arguments(
+    fn execute_prove_command(
+        format: &str,
         subcommand_matches: &ArgMatches,
-    ) -> (&PathBuf, &PathBuf, &PathBuf, &PathBuf) {
-        let circom_path =
-            Self::get_argument_value(subcommand_matches, Self::PROVE_COMMAND_CIRCOM_ARG_NAME);
-        let verification_key_path =
-            Self::get_argument_value(subcommand_matches, Self::PROVE_COMMAND_VK_ARG_NAME);
+    ) -> Result<(), AikenZkError> {
+        let prover = Self::get_prover(subcommand_matches)?;
         let inputs_path =
             Self::get_argument_value(subcommand_matches, Self::PROVE_COMMAND_INPUT_ARG_NAME);
-        let output_path =
-            Self::get_argument_value(subcommand_matches, Self::PROVE_COMMAND_OUTPUT_ARG_NAME);
-        (circom_path, verification_key_path, inputs_path, output_path)
+        let output_path = Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::PROVE_COMMAND_OUTPUT_ARG_NAME,
+        )
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(Self::default_output_path(format)));
+
+        let expectations: Vec<Expectation> = subcommand_matches
+            .get_many::<Expectation>(Self::PROVE_COMMAND_EXPECT_ARG_NAME)
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+
+        let proof = prover.prove_file(&inputs_path.to_string_lossy())?;
+        proof.write_snarkjs_files(prover.build_dir())?;
+
+        // The proof is only written once its public signals are the ones the validator expects.
+        print!("{}", proof.public_signals);
+        proof.public_signals.check(&expectations)?;
+
+        let presented_proof = Self::present(format, proof.compressed);
+        fs::write(&output_path, presented_proof)
+            .map_err(|error| AikenZkError::io(output_path.to_string_lossy(), error))
     }
 
-    fn execute_aiken_prove_command(
-        circom_path: &Path,
-        verification_key_path: &Path,
-        inputs_path: &Path,
-        output_path: &Path,
-    ) {
-        let circom_path_string = circom_path.to_str().unwrap();
-        let verification_key_path_string = verification_key_path.to_str().unwrap();
-        let inputs_path_string = inputs_path.to_str().unwrap();
-        let output_path_string = output_path.to_str().unwrap();
-
-        let circuit = CircomCircuit::from(circom_path_string.to_string());
-        let proof =
-            circuit.generate_groth16_proof(verification_key_path_string, inputs_path_string);
-
-        let aiken_presenter = CompressedGroth16ProofBls12_381ToAikenPresenter::new(proof);
-
-        let presented_aiken_proof = aiken_presenter.present();
-        fs::write(output_path_string, presented_aiken_proof).expect("failed to create output file");
+    fn get_prover(subcommand_matches: &ArgMatches) -> Result<Prover, AikenZkError> {
+        match Self::get_optional_argument_value(
+            subcommand_matches,
+            Self::PROVE_COMMAND_VALIDATOR_ARG_NAME,
+        ) {
+            Some(validator_path) => {
+                let proof_index = subcommand_matches
+                    .get_one::<usize>(Self::PROVE_COMMAND_PROOF_INDEX_ARG_NAME)
+                    .copied();
+                Prover::for_validator(validator_path, proof_index)
+            }
+            None => Prover::for_circuit(
+                &Self::project_root(None)?,
+                &Self::get_argument_value(subcommand_matches, Self::PROVE_COMMAND_CIRCOM_ARG_NAME)
+                    .to_string_lossy(),
+                &Self::get_argument_value(subcommand_matches, Self::PROVE_COMMAND_ZKEY_ARG_NAME)
+                    .to_string_lossy(),
+            ),
+        }
     }
 
-    fn execute_meshjs_prove_command(
-        circom_path: &PathBuf,
-        verification_key_path: &PathBuf,
-        inputs_path: &PathBuf,
-        output_path: &PathBuf,
-    ) {
-        let circom_path_string = circom_path.to_str().unwrap();
-        let verification_key_path_string = verification_key_path.to_str().unwrap();
-        let inputs_path_string = inputs_path.to_str().unwrap();
-        let output_path_string = output_path.to_str().unwrap();
-
-        let circuit = CircomCircuit::from(circom_path_string.to_string());
-        let proof =
-            circuit.generate_groth16_proof(verification_key_path_string, inputs_path_string);
-
-        let mesh_js_presenter = MeshJsZKRedeemerPresenter::new_for_proof(proof);
-        let zk_redeemer = mesh_js_presenter.present();
-
-        fs::write(output_path_string, zk_redeemer).expect("output file write failed");
+    fn present(format: &str, proof: CompressedGroth16ProofBls12_381) -> String {
+        match format {
+            Self::AIKEN_FORMAT => {
+                CompressedGroth16ProofBls12_381ToAikenPresenter::new(proof).present()
+            }
+            Self::MESHJS_FORMAT => MeshJsZKRedeemerPresenter::new_for_proof(proof).present(),
+            _ => CompressedGroth16ProofBls12_381ToJsonPresenter::new(proof).present(),
+        }
     }
 
-    fn execute_json_prove_command(
-        circom_path: &PathBuf,
-        verification_key_path: &PathBuf,
-        inputs_path: &PathBuf,
-        output_path: &PathBuf,
-    ) {
-        let circom_path_string = circom_path.to_str().unwrap();
-        let verification_key_path_string = verification_key_path.to_str().unwrap();
-        let inputs_path_string = inputs_path.to_str().unwrap();
-        let output_path_string = output_path.to_str().unwrap();
-
-        let circuit = CircomCircuit::from(circom_path_string.to_string());
-        let proof =
-            circuit.generate_groth16_proof(verification_key_path_string, inputs_path_string);
-
-        let mesh_js_presenter = CompressedGroth16ProofBls12_381ToJsonPresenter::new(proof);
-        let zk_redeemer = mesh_js_presenter.present();
-
-        fs::write(output_path_string, zk_redeemer).expect("output file write failed");
+    fn default_output_path(format: &str) -> &'static str {
+        match format {
+            Self::AIKEN_FORMAT => "proof.ak",
+            Self::MESHJS_FORMAT => "zk_redeemer.ts",
+            _ => "proof.json",
+        }
     }
 }
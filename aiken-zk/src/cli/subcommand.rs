@@ -1,5 +1,9 @@
+use crate::aiken_zk_error::AikenZkError;
 use clap::{Arg, ArgMatches, Command, value_parser};
-use std::path::PathBuf;
+use miette::{NamedSource, Report};
+use std::path;
+use std::path::{Path, PathBuf};
+use std::process;
 
 pub trait Subcommand {
     const SUBCOMMAND_NAME: &'static str;
@@ -16,6 +20,13 @@ pub trait Subcommand {
             .value_parser(value_parser!(PathBuf))
     }
 
+    fn create_optional_argument_with_id(id: &'static str) -> Arg {
+        Arg::new(id)
+            .long(id)
+            .required(false)
+            .value_parser(value_parser!(PathBuf))
+    }
+
     fn get_argument_value<'a>(
         subcommand_matches: &'a ArgMatches,
         argument_id: &str,
@@ -24,4 +35,28 @@ pub trait Subcommand {
             .get_one::<PathBuf>(argument_id)
             .expect("Value for command not found")
     }
+
+    fn get_optional_argument_value<'a>(
+        subcommand_matches: &'a ArgMatches,
+        argument_id: &str,
+    ) -> Option<&'a PathBuf> {
+        subcommand_matches.get_one::<PathBuf>(argument_id)
+    }
+
+    // The current directory, unless another one is given.
+    fn project_root(project_root: Option<&PathBuf>) -> Result<PathBuf, AikenZkError> {
+        let project_root = project_root.map_or(Path::new("."), PathBuf::as_path);
+        path::absolute(project_root)
+            .map_err(|error| AikenZkError::io(project_root.to_string_lossy(), error))
+    }
+
+    fn exit_with_diagnostic(error: AikenZkError, source_code: Option<NamedSource<String>>) -> ! {
+        let exit_code = error.exit_code();
+        let report = match source_code {
+            Some(source_code) => Report::new(error).with_source_code(source_code),
+            None => Report::new(error),
+        };
+        eprintln!("{:?}", report);
+        process::exit(exit_code)
+    }
 }
@@ -0,0 +1,76 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::cli::subcommand::Subcommand;
+use crate::groth16_verifier_bls12_381::Groth16VerifierBls12_381;
+use clap::{ArgMatches, Command};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::process;
+
+pub struct VerifyCommand {}
+
+impl Subcommand for VerifyCommand {
+    const SUBCOMMAND_NAME: &'static str = "verify";
+
+    fn create_subcommand() -> Command {
+        let verification_key_path =
+            Self::create_required_argument_with_id(Self::VERIFY_COMMAND_VK_ARG_NAME);
+        let public_inputs_path =
+            Self::create_required_argument_with_id(Self::VERIFY_COMMAND_PUBLIC_INPUTS_ARG_NAME);
+        let proof_path =
+            Self::create_required_argument_with_id(Self::VERIFY_COMMAND_PROOF_ARG_NAME);
+
+        Command::new(Self::SUBCOMMAND_NAME)
+            .arg(verification_key_path)
+            .arg(public_inputs_path)
+            .arg(proof_path)
+    }
+
+    fn for_name(name: &str) -> bool {
+        name == Self::SUBCOMMAND_NAME
+    }
+
+    fn evaluate(&self, matches: &ArgMatches) {
+        let (verification_key_path, public_inputs_path, proof_path) = Self::get_arguments(matches);
+        match Self::execute_command(verification_key_path, public_inputs_path, proof_path) {
+            Ok(true) => println!("{}", "The proof is valid".green()),
+            Ok(false) => {
+                eprintln!("{}", "The proof is NOT valid".red());
+                process::exit(Self::INVALID_PROOF_EXIT_CODE);
+            }
+            Err(error) => Self::exit_with_diagnostic(error, None),
+        }
+    }
+}
+
+impl VerifyCommand {
+    const VERIFY_COMMAND_VK_ARG_NAME: &'static str = "verification_key_path";
+    const VERIFY_COMMAND_PUBLIC_INPUTS_ARG_NAME: &'static str = "public_inputs_path";
+    const VERIFY_COMMAND_PROOF_ARG_NAME: &'static str = "proof_path";
+
+    const INVALID_PROOF_EXIT_CODE: i32 = 1;
+
+    fn execute_command(
+        verification_key_path: &Path,
+        public_inputs_path: &Path,
+        proof_path: &Path,
+    ) -> Result<bool, AikenZkError> {
+        let verifier =
+            Groth16VerifierBls12_381::from_json(&verification_key_path.to_string_lossy())?;
+        Ok(verifier.verify_files(
+            &public_inputs_path.to_string_lossy(),
+            &proof_path.to_string_lossy(),
+        )?)
+    }
+
+    fn get_arguments(subcommand_matches: &ArgMatches) -> (&PathBuf, &PathBuf, &PathBuf) {
+        let verification_key_path =
+            Self::get_argument_value(subcommand_matches, Self::VERIFY_COMMAND_VK_ARG_NAME);
+        let public_inputs_path = Self::get_argument_value(
+            subcommand_matches,
+            Self::VERIFY_COMMAND_PUBLIC_INPUTS_ARG_NAME,
+        );
+        let proof_path =
+            Self::get_argument_value(subcommand_matches, Self::VERIFY_COMMAND_PROOF_ARG_NAME);
+        (verification_key_path, public_inputs_path, proof_path)
+    }
+}
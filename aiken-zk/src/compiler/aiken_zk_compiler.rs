@@ -1,206 +1,298 @@
+use crate::aiken_zk_error::AikenZkError;
 use crate::circom_circuit::CircomCircuit;
-use crate::compiler::BUILD_DIR;
+use crate::circom_templates::CircomTemplates;
+use crate::compiler::build_options::BuildOptions;
+use crate::compiler::compiled_circuit::{Cardinality, CompiledCircuit, PublicInput, ZkCompilation};
 use crate::compiler::lexer::{LexInfo, Lexer};
 use crate::compiler::token_zk::{TokenZK as Token, TokenZK};
 use crate::component_creator::ComponentCreator;
+pub use crate::compressed_verification_key_bls12_381::Groth16CompressedData;
 use crate::zk_examples::{InputVisibility, InputZK, TokenWithCardinality, ZkExample};
 use aiken_lang::ast::Span;
-use serde::Deserialize;
 use serde_json::Value;
 use std::fs;
-use std::io::Error;
-use std::process::Command;
-
-#[derive(Deserialize, Debug)]
-#[allow(non_snake_case)]
-pub struct Groth16CompressedData {
-    pub vk_alpha_1: String,
-    pub vk_beta_2: String,
-    pub vk_gamma_2: String,
-    pub vk_delta_2: String,
-    pub IC: Vec<String>,
-}
 
 pub struct AikenZkCompiler;
 
+struct CompiledOffchain {
+    span: Span,
+    verify_function_call: String,
+    vk_compressed_data: Groth16CompressedData,
+    circuit: CompiledCircuit,
+}
+
 impl AikenZkCompiler {
+    const VERIFY_FUNCTION_NAME: &'static str = "zk_verify_or_fail";
+    const VERIFICATION_KEY_NAME: &'static str = "verification_key";
+
     pub fn apply_modifications_to_src_for_token(
         aiken_src: String,
-        aiken_src_filename: String,
+        build_options: &BuildOptions,
         random_seeds: (&str, &str),
-    ) -> String {
-        let (offchain_token, offchain_token_span) = Self::detect_code_to_replace(&aiken_src);
-        match &offchain_token {
+    ) -> Result<String, AikenZkError> {
+        Self::compile(aiken_src, build_options, random_seeds)
+            .map(|compilation| compilation.aiken_src)
+    }
+
+    pub fn compile(
+        aiken_src: String,
+        build_options: &BuildOptions,
+        random_seeds: (&str, &str),
+    ) -> Result<ZkCompilation, AikenZkError> {
+        let offchain_tokens = Self::detect_code_to_replace(&aiken_src)?;
+        let proof_indices: Vec<Option<usize>> = if offchain_tokens.len() == 1 {
+            vec![None]
+        } else {
+            (0..offchain_tokens.len()).map(Some).collect()
+        };
+
+        let compiled_offchains: Vec<CompiledOffchain> = offchain_tokens
+            .into_iter()
+            .zip(proof_indices)
+            .map(|((offchain_token, offchain_token_span), proof_index)| {
+                Self::compile_offchain_token(
+                    build_options,
+                    random_seeds,
+                    &offchain_token,
+                    offchain_token_span,
+                    proof_index,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut aiken_zk_src = aiken_src;
+        for compiled_offchain in compiled_offchains.iter().rev() {
+            aiken_zk_src.replace_range(
+                compiled_offchain.span.start..compiled_offchain.span.end,
+                &compiled_offchain.verify_function_call,
+            );
+        }
+        aiken_zk_src = Self::prepend_imports(&aiken_zk_src);
+
+        Ok(ZkCompilation {
+            aiken_src: Self::append_verify_function_declarations(aiken_zk_src, &compiled_offchains),
+            circuits: compiled_offchains
+                .into_iter()
+                .map(|compiled_offchain| compiled_offchain.circuit)
+                .collect(),
+        })
+    }
+
+    fn compile_offchain_token(
+        build_options: &BuildOptions,
+        random_seeds: (&str, &str),
+        offchain_token: &TokenZK,
+        offchain_token_span: Span,
+        proof_index: Option<usize>,
+    ) -> Result<CompiledOffchain, AikenZkError> {
+        let verification_key_name =
+            Self::name_for_proof_index(Self::VERIFICATION_KEY_NAME, proof_index);
+        let verify_function_name =
+            Self::name_for_proof_index(Self::VERIFY_FUNCTION_NAME, proof_index);
+
+        let (circuit_path, public_inputs) = match offchain_token {
             TokenZK::Offchain {
                 example:
                     ZkExample::CustomCircom {
                         path,
                         public_inputs: public_input,
                     },
-            } => Self::apply_modifications_to_src_for_custom_token(
-                &aiken_src,
-                random_seeds,
-                offchain_token_span,
-                path,
-                public_input,
-            ),
-            TokenZK::Offchain { example } => Self::apply_modifications_to_src_for_example_token(
-                &aiken_src,
-                aiken_src_filename,
-                random_seeds,
-                &offchain_token,
-                offchain_token_span,
-                example,
+            } => {
+                let circuit_path = Self::path_in_project(build_options, path);
+                let public_inputs = Self::apply_modifications_to_src_for_custom_token(
+                    build_options,
+                    random_seeds,
+                    &circuit_path,
+                    public_input,
+                    &verification_key_name,
+                    offchain_token_span,
+                )?;
+                (circuit_path, public_inputs)
+            }
+            TokenZK::Offchain { example } => {
+                // Each validator has its own build directory, so circuits with the same name
+                // don't overwrite each other.
+                let circuit_path = build_options.artifact_path(
+                    &(Self::name_for_proof_index(build_options.circuit_name(), proof_index)
+                        + ".circom"),
+                );
+                let public_inputs = Self::apply_modifications_to_src_for_example_token(
+                    build_options,
+                    &circuit_path,
+                    random_seeds,
+                    offchain_token,
+                    example,
+                    &verification_key_name,
+                )
+                .map_err(|error| error.located_at(offchain_token_span))?;
+                (circuit_path, public_inputs)
+            }
+            _ => {
+                return Err(AikenZkError::UnexpectedToken {
+                    expected: "an offchain statement",
+                    found: offchain_token.to_string(),
+                    span: Some(offchain_token_span),
+                });
+            }
+        };
+
+        let vk_compressed_data =
+            Self::extract_vk_compressed_data(build_options, &verification_key_name)?;
+
+        Ok(CompiledOffchain {
+            span: offchain_token_span,
+            verify_function_call: Self::verification_function_call(
+                &verify_function_name,
+                &public_inputs,
             ),
-            _ => panic!(""),
+            vk_compressed_data,
+            circuit: CompiledCircuit {
+                proof_index,
+                circuit_path,
+                verification_key_name,
+                public_inputs,
+            },
+        })
+    }
+
+    fn path_in_project(build_options: &BuildOptions, path: &str) -> String {
+        build_options.in_project(path).to_string_lossy().to_string()
+    }
+
+    fn name_for_proof_index(name: &str, proof_index: Option<usize>) -> String {
+        match proof_index {
+            None => name.to_string(),
+            Some(index) => format!("{}_{}", name, index),
         }
     }
 
     fn apply_modifications_to_src_for_custom_token(
-        aiken_src: &str,
+        build_options: &BuildOptions,
         random_seeds: (&str, &str),
-        offchain_token_span: Span,
         path: &str,
         public_inputs: &Vec<Box<TokenWithCardinality>>,
-    ) -> String {
-        let output_path = BUILD_DIR;
-        let circom_circuit = CircomCircuit::from(path.to_string());
+        verification_key_name: &str,
+        offchain_token_span: Span,
+    ) -> Result<Vec<PublicInput>, AikenZkError> {
+        let circom_circuit = CircomCircuit::from(path.to_string())?;
         let circuit_name = circom_circuit.filename();
 
-        circom_circuit
-            .generate_verification_key(random_seeds)
-            .unwrap();
-
-        let vk_compressed_data = Self::extract_vk_compressed_data().unwrap();
+        circom_circuit.generate_verification_key_with_name(
+            random_seeds,
+            build_options,
+            verification_key_name,
+        )?;
 
         Self::assert_equal_number_of_public_inputs(
-            output_path,
+            build_options,
             &circuit_name,
             circom_circuit,
-            &public_inputs,
-        );
+            public_inputs,
+            offchain_token_span,
+        )?;
 
-        let mut aiken_zk_src =
-            Self::replace_range_of_offchain_keyword_by_verification_function_call_using_cardinality_parameters(
-                aiken_src,
-                offchain_token_span,
-                &public_inputs,
-            );
-        let public_input_count = public_inputs.len();
-        aiken_zk_src = Self::prepend_imports(&aiken_zk_src);
+        Self::public_inputs_from_cardinality_parameters(public_inputs)
+            .map_err(|error| error.located_at(offchain_token_span))
+    }
 
-        Self::append_verify_function_declaration(
-            aiken_zk_src,
-            &vk_compressed_data,
-            public_input_count,
+    fn verification_function_call(
+        verify_function_name: &str,
+        public_inputs: &[PublicInput],
+    ) -> String {
+        let public_inputs_wrapped_with_list_characteristics: Vec<String> = public_inputs
+            .iter()
+            .map(|public_input| public_input.to_string())
+            .collect();
+
+        format!(
+            "{}(redeemer, [{}])",
+            verify_function_name,
+            public_inputs_wrapped_with_list_characteristics.join(", ")
         )
     }
 
-    fn replace_range_of_offchain_keyword_by_verification_function_call(
-        aiken_src: &str,
-        offchain_token_span: Span,
-        public_input_identifiers: &Vec<String>,
+    // The signals of the built-in functions that take arrays are passed as lists.
+    fn public_inputs_for_example(
+        offchain_token: &TokenZK,
         example: &ZkExample,
-    ) -> String {
-        let mut aiken_zk_src = aiken_src.to_string();
-        let public_input_identifiers_wrapped_with_list_characteristics: Vec<String> = match example
-        {
-            ZkExample::Sha256 {
-                n_bits: _,
-                r#in: _,
-                out: _,
-            } => public_input_identifiers
-                .iter()
-                .map(|identifier| "Many(".to_string() + identifier + ")")
-                .collect(),
-            ZkExample::Poseidon {
-                n_inputs: _,
-                r#in: _,
-                out: _,
-            } => public_input_identifiers
-                .iter()
-                .enumerate()
-                .map(|(i, identifier)| match i {
-                    0 => "Many(".to_string() + identifier + ")",
-                    1 => "Single(".to_string() + identifier + ")",
-                    _ => panic!("Invalid MerkleTreeChecker identifier"),
-                })
-                .collect(),
+    ) -> Result<Vec<PublicInput>, AikenZkError> {
+        match example {
+            ZkExample::Sha256 { r#in, out, .. } => {
+                Self::public_inputs_of(&[(r#in, Cardinality::Many), (out, Cardinality::Many)])
+            }
+            ZkExample::Poseidon { r#in, out, .. } => {
+                Self::public_inputs_of(&[(r#in, Cardinality::Many), (out, Cardinality::Single)])
+            }
             ZkExample::MerkleTreeChecker {
-                levels: _,
-                leaf: _,
-                root: _,
-                path_elements: _,
-                path_indices: _,
-            } => public_input_identifiers
-                .iter()
-                .enumerate()
-                .map(|(i, identifier)| match i {
-                    0..=1 => "Single(".to_string() + identifier + ")",
-                    2..=3 => "Many(".to_string() + identifier + ")",
-                    _ => panic!("Invalid MerkleTreeChecker identifier"),
-                })
-                .collect(),
-            ZkExample::PolynomialEvaluations { .. } => public_input_identifiers
-                .iter()
-                .map(|identifier| "Many(".to_string() + identifier + ")")
-                .collect(),
-            _ => public_input_identifiers
-                .iter()
-                .map(|identifier| "Single(".to_string() + identifier + ")")
-                .collect(),
-        };
+                leaf,
+                root,
+                path_elements,
+                path_indices,
+                ..
+            } => Self::public_inputs_of(&[
+                (leaf, Cardinality::Single),
+                (root, Cardinality::Single),
+                (path_elements, Cardinality::Many),
+                (path_indices, Cardinality::Many),
+            ]),
+            ZkExample::PolynomialEvaluations {
+                coefficients,
+                domain,
+                evaluations,
+                ..
+            } => Self::public_inputs_of(&[
+                (coefficients, Cardinality::Many),
+                (domain, Cardinality::Many),
+                (evaluations, Cardinality::Many),
+            ]),
+            _ => Ok(Self::extract_public_identifiers_from_token(offchain_token)?
+                .into_iter()
+                .map(PublicInput::single)
+                .collect()),
+        }
+    }
 
-        let replacement = format!(
-            "zk_verify_or_fail(redeemer, [{}])",
-            public_input_identifiers_wrapped_with_list_characteristics.join(", ")
-        );
-        aiken_zk_src.replace_range(
-            offchain_token_span.start..offchain_token_span.end,
-            &replacement,
-        );
-        aiken_zk_src
+    fn public_inputs_of(
+        inputs: &[(&InputZK, Cardinality)],
+    ) -> Result<Vec<PublicInput>, AikenZkError> {
+        inputs
+            .iter()
+            .filter_map(|(input, cardinality)| {
+                Self::extract_visibility_from_input(input).map(|identifier| {
+                    identifier.map(|identifier| PublicInput {
+                        identifier,
+                        cardinality: *cardinality,
+                    })
+                })
+            })
+            .collect()
     }
 
-    fn replace_range_of_offchain_keyword_by_verification_function_call_using_cardinality_parameters(
-        aiken_src: &str,
-        offchain_token_span: Span,
-        public_inputs: &Vec<Box<TokenWithCardinality>>,
-    ) -> String {
-        let mut aiken_zk_src = aiken_src.to_string();
-        let public_input_identifiers_wrapped_with_list_characteristics: Vec<String> = public_inputs
+    fn public_inputs_from_cardinality_parameters(
+        public_inputs: &[Box<TokenWithCardinality>],
+    ) -> Result<Vec<PublicInput>, AikenZkError> {
+        public_inputs
             .iter()
             .map(
                 |token_with_cardinality| match *token_with_cardinality.clone() {
-                    TokenWithCardinality::Single(token) => {
-                        let identifier = Self::extract_identifier_from_token(&token);
-                        "Single(".to_string() + &identifier + ")"
-                    }
-                    TokenWithCardinality::Multiple(token) => {
-                        let identifier = Self::extract_identifier_from_token(&token);
-                        "Many(".to_string() + &identifier + ")"
-                    }
+                    TokenWithCardinality::Single(token) => Ok(PublicInput::single(
+                        Self::extract_identifier_from_token(&token)?,
+                    )),
+                    TokenWithCardinality::Multiple(token) => Ok(PublicInput::many(
+                        Self::extract_identifier_from_token(&token)?,
+                    )),
                 },
             )
-            .collect();
-
-        let replacement = format!(
-            "zk_verify_or_fail(redeemer, [{}])",
-            public_input_identifiers_wrapped_with_list_characteristics.join(", ")
-        );
-        aiken_zk_src.replace_range(
-            offchain_token_span.start..offchain_token_span.end,
-            &replacement,
-        );
-        aiken_zk_src
+            .collect()
     }
 
     fn assert_equal_number_of_public_inputs(
-        output_path: &str,
+        build_options: &BuildOptions,
         circuit_name: &str,
         circom_circuit: CircomCircuit,
         public_inputs: &Vec<Box<TokenWithCardinality>>,
-    ) {
+        offchain_token_span: Span,
+    ) -> Result<(), AikenZkError> {
         let bind = public_inputs.clone();
         let has_list_variable =
             bind.iter()
@@ -210,116 +302,111 @@ impl AikenZkCompiler {
                 });
         if has_list_variable.is_none() {
             // We anulate the validation in case there are list variables
-            let r1cs_path = format!("{}{}.r1cs", output_path, circuit_name);
+            let r1cs_path = build_options.artifact_path(&format!("{}.r1cs", circuit_name));
             let r1cs_json_path = format!("{}.json", r1cs_path);
-            circom_circuit.export_r1cs_to_json(&r1cs_path, &r1cs_json_path);
-            let r1cs_json_str = fs::read_to_string(&r1cs_json_path).unwrap();
-            let json: Value = serde_json::from_str(&r1cs_json_str).unwrap();
-            let public_inputs_amount = json["nPubInputs"].as_u64().unwrap();
-            assert_eq!(
-                public_inputs.len(),
-                public_inputs_amount as usize,
-                "Amount of public inputs doesnt match"
-            );
+            circom_circuit.export_r1cs_to_json(&r1cs_path, &r1cs_json_path)?;
+            let r1cs_json_str = fs::read_to_string(&r1cs_json_path)
+                .map_err(|error| AikenZkError::io(&r1cs_json_path, error))?;
+            let public_inputs_amount = serde_json::from_str::<Value>(&r1cs_json_str)
+                .ok()
+                .and_then(|json| json["nPubInputs"].as_u64())
+                .ok_or_else(|| AikenZkError::ToolchainFailed {
+                    command: "export r1cs".to_string(),
+                    exit_code: None,
+                    output: format!("{} has no nPubInputs", r1cs_json_path),
+                })?;
+            if public_inputs.len() != public_inputs_amount as usize {
+                return Err(AikenZkError::PublicInputCountMismatch {
+                    expected: public_inputs_amount as usize,
+                    actual: public_inputs.len(),
+                    span: offchain_token_span,
+                });
+            }
         }
+        Ok(())
     }
 
     fn apply_modifications_to_src_for_example_token(
-        aiken_src: &String,
-        aiken_src_filename: String,
+        build_options: &BuildOptions,
+        circuit_path: &str,
         random_seeds: (&str, &str),
         offchain_token: &TokenZK,
-        offchain_token_span: Span,
         example: &ZkExample,
-    ) -> String {
+        verification_key_name: &str,
+    ) -> Result<Vec<PublicInput>, AikenZkError> {
         Self::output_offchain_circuit_and_reference(
-            aiken_src_filename,
+            build_options,
+            circuit_path,
             random_seeds,
-            &offchain_token,
-        );
-        Self::output_aiken_code(&aiken_src, &offchain_token, offchain_token_span, example)
+            offchain_token,
+            verification_key_name,
+        )?;
+        Self::public_inputs_for_example(offchain_token, example)
     }
 
-    fn detect_code_to_replace(aiken_src: &String) -> (TokenZK, Span) {
-        // Detect offchain token
-        let LexInfo { tokens, .. } = Lexer::new().run(&aiken_src).unwrap();
-        let (offchain_token, offchain_token_span) = Self::find_offchain_token(tokens);
-        (offchain_token, offchain_token_span)
+    pub fn detect_code_to_replace(aiken_src: &str) -> Result<Vec<(TokenZK, Span)>, AikenZkError> {
+        // Detect offchain tokens
+        let LexInfo { tokens, .. } = Lexer::new().run(aiken_src)?;
+        Self::find_offchain_tokens(tokens)
     }
 
     fn output_offchain_circuit_and_reference(
-        aiken_src_filename: String,
+        build_options: &BuildOptions,
+        circuit_path: &str,
         random_seeds: (&str, &str),
         offchain_token: &TokenZK,
-    ) {
-        // Create circom circuit source code for offchain token
-        let circom_component_src = ComponentCreator::from_token(offchain_token.clone()).create();
-        let circom_src_filename_with_extension = aiken_src_filename + ".circom";
-        fs::write(&circom_src_filename_with_extension, circom_component_src).unwrap();
+        verification_key_name: &str,
+    ) -> Result<(), AikenZkError> {
+        // Create circom circuit source code for offchain token next to its template, in the build dir
+        let build_dir = build_options.build_dir();
+        fs::create_dir_all(&build_dir)
+            .map_err(|error| AikenZkError::io(build_dir.to_string_lossy(), error))?;
+        let component_creator = ComponentCreator::from_token(offchain_token.clone());
+        if let Some(template_file_name) = component_creator.template_file_name()? {
+            CircomTemplates::write_to_build_dir(template_file_name, &build_dir)?;
+        }
+        let circom_component_src = component_creator.create()?;
+        fs::write(circuit_path, circom_component_src)
+            .map_err(|error| AikenZkError::io(circuit_path, error))?;
 
         // Create verification key for circom circuit
-        let circom_circuit = CircomCircuit::from(circom_src_filename_with_extension.clone());
-        circom_circuit
-            .generate_verification_key(random_seeds)
-            .unwrap();
-    }
-
-    fn output_aiken_code(
-        aiken_src: &String,
-        offchain_token: &TokenZK,
-        offchain_token_span: Span,
-        example: &ZkExample,
-    ) -> String {
-        // Replace offchain with groth16 verifier
-        let vk_compressed_data = Self::extract_vk_compressed_data().unwrap();
-        let mut aiken_zk_src = Self::replace_keyword_with_function_call(
-            &aiken_src,
-            &offchain_token,
-            offchain_token_span,
-            example,
-        );
-        let public_input_count = Self::extract_public_identifiers_from_token(offchain_token).len();
-        aiken_zk_src = Self::prepend_imports(&aiken_zk_src);
-        aiken_zk_src = Self::append_verify_function_declaration(
-            aiken_zk_src,
-            &vk_compressed_data,
-            public_input_count,
-        );
-        aiken_zk_src
-    }
-
-    fn find_offchain_token(tokens: Vec<(Token, Span)>) -> (Token, Span) {
-        tokens
-            .iter()
-            .find(|(token, _span)| matches!(token, Token::Offchain { .. }))
-            .unwrap_or_else(||panic!("Offchain function not present, or present but it is not one of the supported functions"))
-            .clone()
+        let circom_circuit = CircomCircuit::from(circuit_path.to_string())?;
+        circom_circuit.generate_verification_key_with_name(
+            random_seeds,
+            build_options,
+            verification_key_name,
+        )
     }
 
-    fn replace_keyword_with_function_call(
-        aiken_src: &str,
-        token: &Token,
-        offchain_token_span: Span,
-        example: &ZkExample,
-    ) -> String {
-        let public_input_identifiers = Self::extract_public_identifiers_from_token(token);
-        Self::replace_range_of_offchain_keyword_by_verification_function_call(
-            aiken_src,
-            offchain_token_span,
-            &public_input_identifiers,
-            example,
-        )
+    // Circom resolves includes from the directory of the including file, so the templates are
+    // referenced relative to the circuit unless the build directory is outside the project.
+    fn find_offchain_tokens(
+        tokens: Vec<(Token, Span)>,
+    ) -> Result<Vec<(Token, Span)>, AikenZkError> {
+        let offchain_tokens: Vec<(Token, Span)> = tokens
+            .into_iter()
+            .filter(|(token, _span)| matches!(token, Token::Offchain { .. }))
+            .collect();
+        if offchain_tokens.is_empty() {
+            return Err(AikenZkError::MissingOffchain);
+        }
+        Ok(offchain_tokens)
     }
 
-    fn extract_identifier_from_token(token: &TokenZK) -> String {
+    fn extract_identifier_from_token(token: &TokenZK) -> Result<String, AikenZkError> {
         match token {
-            Token::Name { name } => name.clone(),
-            Token::Int { value, .. } => value.clone(),
-            _ => panic!("Not the expected kind of token"),
+            Token::Name { name } => Ok(name.clone()),
+            Token::Int { value, .. } => Ok(value.clone()),
+            Token::Expression { text } => Ok(text.clone()),
+            _ => Err(AikenZkError::UnexpectedToken {
+                expected: "an identifier, an integer or an expression",
+                found: token.to_string(),
+                span: None,
+            }),
         }
     }
 
-    fn extract_public_identifiers_from_token(token: &TokenZK) -> Vec<String> {
+    fn extract_public_identifiers_from_token(token: &TokenZK) -> Result<Vec<String>, AikenZkError> {
         match token {
             Token::Offchain {
                 example: ZkExample::Addition { lhs, rhs, res },
@@ -373,86 +460,133 @@ impl AikenZkCompiler {
                 .collect(),
 
             Token::Offchain {
-                example: ZkExample::Sha256 { r#in, out, .. },
-            } => [r#in, out]
-                .iter()
-                .filter_map(|input| Self::extract_visibility_from_input(&input))
+                example:
+                    ZkExample::RangeCheck {
+                        value,
+                        lower_bound,
+                        upper_bound,
+                        ..
+                    },
+            } => [value, lower_bound, upper_bound]
+                .into_iter()
+                .filter_map(Self::extract_visibility_from_input)
+                .collect(),
+
+            Token::Offchain {
+                example:
+                    ZkExample::LessThan { lhs, rhs, out, .. }
+                    | ZkExample::GreaterEqual { lhs, rhs, out, .. },
+            } => [lhs, rhs, out]
+                .into_iter()
+                .filter_map(Self::extract_visibility_from_input)
                 .collect(),
 
             Token::Offchain {
-                example: ZkExample::Poseidon { r#in, out, .. },
+                example: ZkExample::IsZero { r#in, out },
             } => [r#in, out]
-                .iter()
-                .filter_map(|input| Self::extract_visibility_from_input(&input))
+                .into_iter()
+                .filter_map(Self::extract_visibility_from_input)
                 .collect(),
 
             Token::Offchain {
                 example:
-                    ZkExample::MerkleTreeChecker {
-                        leaf,
-                        root,
-                        path_elements,
-                        path_indices,
-                        ..
+                    ZkExample::EddsaVerify {
+                        pub_key_x,
+                        pub_key_y,
+                        msg,
+                        r_x,
+                        r_y,
+                        s,
                     },
-            } => [leaf, root, path_elements, path_indices]
-                .iter()
-                .filter_map(|input| Self::extract_visibility_from_input(&input))
+            } => [pub_key_x, pub_key_y, msg, r_x, r_y, s]
+                .into_iter()
+                .filter_map(Self::extract_visibility_from_input)
                 .collect(),
 
             Token::Offchain {
                 example:
-                    ZkExample::PolynomialEvaluations {
-                        grade: _,
-                        coefficients,
-                        amount_of_evaluations: _,
-                        domain,
-                        evaluations,
+                    ZkExample::MembershipWithNullifier {
+                        root,
+                        nullifier,
+                        external_nullifier,
+                        ..
                     },
-            } => [coefficients, domain, evaluations]
+            } => [root, nullifier, external_nullifier]
+                .into_iter()
+                .filter_map(Self::extract_visibility_from_input)
+                .collect(),
+
+            Token::Offchain {
+                example: ZkExample::ExpressionBlock { block },
+            } => block
+                .inputs
                 .iter()
-                .filter_map(|input| Self::extract_visibility_from_input(&input))
+                .filter_map(|block_input| Self::extract_visibility_from_input(&block_input.input))
                 .collect(),
 
-            _ => panic!("Not implemented"),
+            _ => Err(AikenZkError::UnexpectedToken {
+                expected: "one of the built-in offchain functions",
+                found: token.to_string(),
+                span: None,
+            }),
         }
     }
 
-    fn extract_visibility_from_input(input: &InputZK) -> Option<String> {
+    fn extract_visibility_from_input(input: &InputZK) -> Option<Result<String, AikenZkError>> {
         match input.visibility.clone() {
             InputVisibility::Private => None,
             _ => {
-                let token = TokenWithCardinality::extract_single(&input.token.clone().unwrap());
-                Some(Self::extract_identifier_from_token(&token.unwrap()))
+                let token = input
+                    .token
+                    .as_deref()
+                    .and_then(TokenWithCardinality::extract_single)?;
+                Some(Self::extract_identifier_from_token(&token))
             }
         }
     }
 
-    fn append_verify_function_declaration(
+    fn append_verify_function_declarations(
         aiken_zk_src: String,
-        vk_compressed_data: &Groth16CompressedData,
-        public_input_count: usize,
+        compiled_offchains: &[CompiledOffchain],
     ) -> String {
-        let full_verify_function_declaration =
-            Self::create_verify_function_declaration_from(vk_compressed_data, public_input_count);
-        aiken_zk_src + &full_verify_function_declaration
+        let verify_function_declarations: String = compiled_offchains
+            .iter()
+            .map(|compiled_offchain| {
+                Self::create_verify_function_declaration_from(
+                    &compiled_offchain.vk_compressed_data,
+                    compiled_offchain.circuit.public_inputs.len(),
+                    compiled_offchain.circuit.proof_index,
+                )
+            })
+            .collect();
+        aiken_zk_src + &Self::create_zk_input_type_declaration() + &verify_function_declarations
     }
 
-    fn extract_vk_compressed_data() -> Result<Groth16CompressedData, Error> {
-        let output = Command::new("node")
-            .arg("curve_compress/compressedVerificationKey.js")
-            .arg(BUILD_DIR.to_string() + "verification_key.json")
-            .output()?;
-
-        let stdout = String::from_utf8(output.stdout).unwrap();
-        let compressed_vk: Groth16CompressedData = serde_json::from_str(&stdout)?;
+    fn extract_vk_compressed_data(
+        build_options: &BuildOptions,
+        verification_key_name: &str,
+    ) -> Result<Groth16CompressedData, AikenZkError> {
+        Ok(Groth16CompressedData::from_json(
+            &build_options.artifact_path(&format!("{}.json", verification_key_name)),
+        )?)
+    }
 
-        Ok(compressed_vk)
+    fn create_zk_input_type_declaration() -> String {
+        r#"
+    type ZKInputType {
+        Single(Int)
+        Many(List<Int>)
+    }
+"#
+        .to_string()
     }
 
+    // A lone offchain statement consumes the head of the proofs list and hands back the rest,
+    // while each of several statements reads the proof at its own index.
     fn create_verify_function_declaration_from(
         vk_compressed_data: &Groth16CompressedData,
         public_input_count: usize,
+        proof_index: Option<usize>,
     ) -> String {
         let formatted_ic = vk_compressed_data
             .IC
@@ -461,14 +595,22 @@ impl AikenZkCompiler {
             .collect::<Vec<_>>()
             .join(",\n");
 
+        let verify_function_name =
+            Self::name_for_proof_index(Self::VERIFY_FUNCTION_NAME, proof_index);
+        let (proof_lookup, returned_redeemer) = match proof_index {
+            None => (
+                "list.head(zk_redeemer.proofs)".to_string(),
+                "expect Some(proofs) = list.tail(zk_redeemer.proofs)\n        ZK { redeemer: zk_redeemer.redeemer, proofs }".to_string(),
+            ),
+            Some(index) => (
+                format!("list.at(zk_redeemer.proofs, {})", index),
+                "zk_redeemer".to_string(),
+            ),
+        };
+
         format!(
             r#"
-    type ZKInputType {{
-        Single(Int)
-        Many(List<Int>)
-    }}
-
-    fn zk_verify_or_fail(
+    fn {verify_function_name}(
         zk_redeemer: ZK<Redeemer>,
         public_inputs: List<ZKInputType>
     ) -> ZK<Redeemer> {{
@@ -486,7 +628,7 @@ impl AikenZkCompiler {
                 ],
             }}
 
-        expect Some(proof) = list.head(zk_redeemer.proofs)
+        expect Some(proof) = {proof_lookup}
 
         let flattened_public_inputs: List<Int> = list.flat_map(public_inputs, fn(item) {{
             when item is {{
@@ -502,8 +644,7 @@ impl AikenZkCompiler {
           Void
         }}
 
-        expect Some(proofs) = list.tail(zk_redeemer.proofs)
-        ZK {{ redeemer: zk_redeemer.redeemer, proofs }}
+        {returned_redeemer}
     }}"#,
             public_input_count,
             vkAlpha = vk_compressed_data.vk_alpha_1,
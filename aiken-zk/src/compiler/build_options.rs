@@ -0,0 +1,96 @@
+use crate::compiler::BUILD_DIR;
+use crate::filename_without_extension_nor_path;
+use std::path;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOptions {
+    project_root: PathBuf,
+    build_dir: PathBuf,
+    ptau_path: PathBuf,
+    circuit_name: String,
+    reproducible_setup: bool,
+}
+
+impl BuildOptions {
+    pub const DEFAULT_PTAU_PATH: &'static str = "ceremony.ptau";
+    const DEFAULT_CIRCUIT_NAME: &'static str = "output";
+
+    // Relative paths, including the circuits written by the compiler, are anchored to the project
+    // root instead of the current directory. A relative root is made absolute here, so the paths
+    // recorded in build manifests can be used from any directory.
+    pub fn for_circuit(project_root: PathBuf, circuit_name: &str) -> Self {
+        Self {
+            project_root: path::absolute(&project_root).unwrap_or(project_root),
+            build_dir: PathBuf::from(BUILD_DIR),
+            ptau_path: PathBuf::from(Self::DEFAULT_PTAU_PATH),
+            circuit_name: circuit_name.to_string(),
+            reproducible_setup: false,
+        }
+    }
+
+    // Every validator gets its own artifact set: `foo.ak` builds the `foo` circuit into `build/foo/`.
+    pub fn for_source(project_root: PathBuf, source_path: &Path) -> Self {
+        let source_name =
+            filename_without_extension_nor_path(source_path.to_string_lossy().to_string())
+                .unwrap_or_else(|| Self::DEFAULT_CIRCUIT_NAME.to_string());
+        Self::for_circuit(project_root, &source_name)
+            .with_build_dir(Path::new(BUILD_DIR).join(&source_name))
+    }
+
+    pub fn with_build_dir(self, build_dir: PathBuf) -> Self {
+        Self { build_dir, ..self }
+    }
+
+    pub fn with_ptau_path(self, ptau_path: PathBuf) -> Self {
+        Self { ptau_path, ..self }
+    }
+
+    pub fn with_circuit_name(self, circuit_name: String) -> Self {
+        Self {
+            circuit_name,
+            ..self
+        }
+    }
+
+    // Replaces the snarkjs contributions, which mix in system randomness, by beacons derived from
+    // the random seeds, so the same seeds always produce the same keys.
+    pub fn with_reproducible_setup(self, reproducible_setup: bool) -> Self {
+        Self {
+            reproducible_setup,
+            ..self
+        }
+    }
+
+    pub fn project_root(&self) -> &Path {
+        &self.project_root
+    }
+
+    pub fn build_dir(&self) -> PathBuf {
+        self.in_project(&self.build_dir)
+    }
+
+    pub fn ptau_path(&self) -> PathBuf {
+        self.in_project(&self.ptau_path)
+    }
+
+    pub fn circuit_name(&self) -> &str {
+        &self.circuit_name
+    }
+
+    pub fn has_reproducible_setup(&self) -> bool {
+        self.reproducible_setup
+    }
+
+    pub fn artifact_path(&self, artifact_filename: &str) -> String {
+        self.build_dir()
+            .join(artifact_filename)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    // Absolute paths are kept as they are.
+    pub fn in_project(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.project_root.join(path)
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Cardinality {
+    Single,
+    Many,
+}
+
+// A public input of the circuit as it is passed to the verifier, in circuit order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicInput {
+    pub identifier: String,
+    pub cardinality: Cardinality,
+}
+
+impl PublicInput {
+    pub fn single(identifier: String) -> Self {
+        Self {
+            identifier,
+            cardinality: Cardinality::Single,
+        }
+    }
+
+    pub fn many(identifier: String) -> Self {
+        Self {
+            identifier,
+            cardinality: Cardinality::Many,
+        }
+    }
+}
+
+impl fmt::Display for PublicInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cardinality {
+            Cardinality::Single => write!(f, "Single({})", self.identifier),
+            Cardinality::Many => write!(f, "Many({})", self.identifier),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledCircuit {
+    pub proof_index: Option<usize>,
+    pub circuit_path: String,
+    pub verification_key_name: String,
+    pub public_inputs: Vec<PublicInput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZkCompilation {
+    pub aiken_src: String,
+    pub circuits: Vec<CompiledCircuit>,
+}
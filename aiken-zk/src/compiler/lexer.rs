@@ -1,3 +1,4 @@
+use crate::aiken_zk_error::AikenZkError;
 use crate::compiler::parsers::int_parser;
 use crate::compiler::token_zk::TokenZK as Token;
 use crate::zk_examples::ZkExample;
@@ -178,18 +179,31 @@ impl Lexer {
         }
     }
 
-    pub fn run(&mut self, src: &str) -> Result<LexInfo, Vec<ParseError>> {
+    pub fn run(&mut self, src: &str) -> Result<LexInfo, AikenZkError> {
         let len = src.len();
 
-        let tokens = self.lexer.parse(chumsky::Stream::from_iter(
-            Span::create(len, 1),
-            src.chars().scan(0, |i, c| {
-                let start = *i;
-                let offset = c.len_utf8();
-                *i = start + offset;
-                Some((c, Span::create(start, offset)))
-            }),
-        ))?;
+        let tokens = self
+            .lexer
+            .parse(chumsky::Stream::from_iter(
+                Span::create(len, 1),
+                src.chars().scan(0, |i, c| {
+                    let start = *i;
+                    let offset = c.len_utf8();
+                    *i = start + offset;
+                    Some((c, Span::create(start, offset)))
+                }),
+            ))
+            .map_err(|errors| AikenZkError::Parse { errors })?;
+
+        if let Some((Token::InvalidOffchain { error }, span)) = tokens
+            .iter()
+            .find(|(token, _)| matches!(token, Token::InvalidOffchain { .. }))
+        {
+            return Err(AikenZkError::InvalidOffchainArgument {
+                reason: error.clone(),
+                span: *span,
+            });
+        }
 
         let mut extra = ModuleExtra::new();
 
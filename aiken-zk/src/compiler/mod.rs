@@ -1,5 +1,8 @@
 pub mod aiken_zk_compiler;
+pub mod build_options;
+pub mod compiled_circuit;
 pub mod lexer;
+pub mod offchain_block;
 pub mod parsers;
 pub mod token_zk;
 
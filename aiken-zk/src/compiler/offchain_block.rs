@@ -0,0 +1,517 @@
+use crate::compiler::parsers::int_parser;
+use crate::compiler::token_zk::TokenZK as Token;
+use crate::zk_examples::{InputVisibility, InputZK, OffchainArgumentError, TokenWithCardinality};
+use aiken_lang::parser::error::ParseError;
+use chumsky::prelude::*;
+
+// An `offchain { ... }` block: let bindings and assertions over the Int variables of the validator.
+// The variables it reads become the input signals of a circom template synthesized for the block.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OffchainBlock {
+    pub inputs: Vec<BlockInput>,
+    pub statements: Vec<BlockStatement>,
+}
+
+// Inputs are public unless marked with priv, in any of the places the block reads them.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlockInput {
+    pub name: String,
+    pub input: InputZK,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BlockStatement {
+    Let {
+        name: String,
+        value: BlockExpression,
+    },
+    Assert {
+        condition: BlockExpression,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BlockExpression {
+    Int {
+        value: String,
+    },
+    Variable {
+        name: String,
+        visibility: Option<InputVisibility>,
+    },
+    Negation {
+        operand: Box<BlockExpression>,
+    },
+    Addition {
+        lhs: Box<BlockExpression>,
+        rhs: Box<BlockExpression>,
+    },
+    Subtraction {
+        lhs: Box<BlockExpression>,
+        rhs: Box<BlockExpression>,
+    },
+    Multiplication {
+        lhs: Box<BlockExpression>,
+        rhs: Box<BlockExpression>,
+    },
+    Equality {
+        lhs: Box<BlockExpression>,
+        rhs: Box<BlockExpression>,
+    },
+}
+
+impl OffchainBlock {
+    const KEYWORDS: [&'static str; 4] = ["let", "assert", "pub", "priv"];
+
+    // Names the synthesized template could clash with. Auxiliary signals start with an underscore.
+    const CIRCOM_KEYWORDS: [&'static str; 22] = [
+        "signal",
+        "input",
+        "output",
+        "public",
+        "template",
+        "component",
+        "main",
+        "var",
+        "function",
+        "return",
+        "if",
+        "else",
+        "for",
+        "while",
+        "do",
+        "log",
+        "assert",
+        "include",
+        "pragma",
+        "circom",
+        "custom",
+        "parallel",
+    ];
+
+    pub fn from_statements(statements: Vec<BlockStatement>) -> Result<Self, OffchainArgumentError> {
+        let mut scope = BlockScope::default();
+        for statement in &statements {
+            match statement {
+                BlockStatement::Let { name, value } => {
+                    scope.read(value)?;
+                    scope.bind(name)?;
+                }
+                BlockStatement::Assert { condition } => scope.read(condition)?,
+            }
+        }
+        if !statements
+            .iter()
+            .any(|statement| matches!(statement, BlockStatement::Assert { .. }))
+        {
+            return Err(OffchainArgumentError::BlockWithoutAssertion);
+        }
+
+        Ok(Self {
+            inputs: scope.into_inputs(),
+            statements,
+        })
+    }
+
+    // The inputs are declared in the order the block first reads them, which is also the order of
+    // the public inputs of the proof.
+    pub fn circom_template(&self, template_name: &str) -> String {
+        let mut synthesizer = CircomSynthesizer::default();
+        for statement in &self.statements {
+            synthesizer.statement(statement);
+        }
+
+        let lines: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| format!("signal input {};", input.name))
+            .chain(synthesizer.lines)
+            .map(|line| format!("    {}", line))
+            .collect();
+        format!("template {}() {{\n{}\n}}", template_name, lines.join("\n"))
+    }
+
+    pub fn parser() -> impl Parser<char, Vec<BlockStatement>, Error = ParseError> {
+        let let_statement = text::keyword("let")
+            .ignore_then(Self::name_parser().padded_by(Self::padding()))
+            .then_ignore(just('='))
+            .then(Self::expression_parser())
+            .map(|(name, value)| BlockStatement::Let { name, value });
+        let assert_statement = text::keyword("assert")
+            .ignore_then(Self::expression_parser())
+            .map(|condition| BlockStatement::Assert { condition });
+
+        choice((let_statement, assert_statement))
+            .padded_by(Self::padding())
+            .repeated()
+            .delimited_by(just('{'), just('}'))
+    }
+
+    fn expression_parser() -> impl Parser<char, BlockExpression, Error = ParseError> + Clone {
+        recursive(|expression| {
+            let variable = choice((
+                text::keyword("pub").to(InputVisibility::Public),
+                text::keyword("priv").to(InputVisibility::Private),
+            ))
+            .then_ignore(Self::padding())
+            .or_not()
+            .then(Self::name_parser())
+            .map(|(visibility, name)| BlockExpression::Variable { name, visibility });
+            let atom = choice((
+                int_parser().map(|token| BlockExpression::Int {
+                    value: token.to_string(),
+                }),
+                variable,
+                expression.delimited_by(just('('), just(')')),
+            ))
+            .padded_by(Self::padding())
+            .boxed();
+
+            let negation = just('-')
+                .padded_by(Self::padding())
+                .repeated()
+                .then(atom)
+                .foldr(|_, operand| BlockExpression::Negation {
+                    operand: Box::new(operand),
+                });
+            let product = negation
+                .clone()
+                .then(just('*').ignore_then(negation).repeated())
+                .foldl(|lhs, rhs| BlockExpression::Multiplication {
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(rhs),
+                });
+            let sum = product
+                .clone()
+                .then(choice((just('+'), just('-'))).then(product).repeated())
+                .foldl(|lhs, (operator, rhs)| {
+                    let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+                    match operator {
+                        '+' => BlockExpression::Addition { lhs, rhs },
+                        _ => BlockExpression::Subtraction { lhs, rhs },
+                    }
+                });
+
+            sum.clone()
+                .then(just("==").ignore_then(sum).or_not())
+                .map(|(lhs, rhs)| match rhs {
+                    Some(rhs) => BlockExpression::Equality {
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    },
+                    None => lhs,
+                })
+        })
+    }
+
+    fn name_parser() -> impl Parser<char, String, Error = ParseError> + Clone {
+        text::ident().validate(|name: String, span, emit| {
+            if Self::KEYWORDS.contains(&name.as_str()) {
+                emit(ParseError::expected_input_found(span, None, None::<char>));
+            }
+            name
+        })
+    }
+
+    // Blocks span several lines, and may have comments.
+    fn padding() -> impl Parser<char, (), Error = ParseError> + Clone {
+        choice((
+            filter(|c: &char| c.is_whitespace()).ignored(),
+            just("//").then(take_until(text::newline())).ignored(),
+        ))
+        .repeated()
+        .ignored()
+    }
+
+    fn is_reserved(name: &str) -> bool {
+        name.starts_with('_') || Self::CIRCOM_KEYWORDS.contains(&name)
+    }
+}
+
+// The names a block has read or bound so far, to check each one means the same all along.
+#[derive(Default)]
+struct BlockScope {
+    inputs: Vec<(String, Option<InputVisibility>)>,
+    bound: Vec<String>,
+}
+
+impl BlockScope {
+    fn read(&mut self, expression: &BlockExpression) -> Result<(), OffchainArgumentError> {
+        match expression {
+            BlockExpression::Int { .. } => Ok(()),
+            BlockExpression::Variable { name, visibility } => self.read_variable(name, visibility),
+            BlockExpression::Negation { operand } => self.read(operand),
+            BlockExpression::Addition { lhs, rhs }
+            | BlockExpression::Subtraction { lhs, rhs }
+            | BlockExpression::Multiplication { lhs, rhs }
+            | BlockExpression::Equality { lhs, rhs } => {
+                self.read(lhs)?;
+                self.read(rhs)
+            }
+        }
+    }
+
+    fn read_variable(
+        &mut self,
+        name: &str,
+        visibility: &Option<InputVisibility>,
+    ) -> Result<(), OffchainArgumentError> {
+        if OffchainBlock::is_reserved(name) {
+            return Err(OffchainArgumentError::ReservedName {
+                name: name.to_string(),
+            });
+        }
+        if self.bound.iter().any(|bound| bound == name) {
+            return match visibility {
+                Some(_) => Err(OffchainArgumentError::LetBindingWithVisibility {
+                    name: name.to_string(),
+                }),
+                None => Ok(()),
+            };
+        }
+
+        match self.inputs.iter_mut().find(|(input, _)| input == name) {
+            Some((_, known_visibility)) => match (known_visibility.clone(), visibility) {
+                (Some(known), Some(marked)) if known != *marked => {
+                    Err(OffchainArgumentError::ConflictingVisibility {
+                        name: name.to_string(),
+                    })
+                }
+                (None, Some(marked)) => {
+                    *known_visibility = Some(marked.clone());
+                    Ok(())
+                }
+                _ => Ok(()),
+            },
+            None => {
+                self.inputs.push((name.to_string(), visibility.clone()));
+                Ok(())
+            }
+        }
+    }
+
+    fn bind(&mut self, name: &str) -> Result<(), OffchainArgumentError> {
+        if OffchainBlock::is_reserved(name) {
+            return Err(OffchainArgumentError::ReservedName {
+                name: name.to_string(),
+            });
+        }
+        let is_known = self.inputs.iter().any(|(input, _)| input == name)
+            || self.bound.iter().any(|bound| bound == name);
+        if is_known {
+            return Err(OffchainArgumentError::RedefinedName {
+                name: name.to_string(),
+            });
+        }
+        self.bound.push(name.to_string());
+        Ok(())
+    }
+
+    fn into_inputs(self) -> Vec<BlockInput> {
+        self.inputs
+            .into_iter()
+            .map(|(name, visibility)| {
+                let input = match visibility.unwrap_or(InputVisibility::Public) {
+                    InputVisibility::Public => InputZK {
+                        visibility: InputVisibility::Public,
+                        token: Some(Box::new(TokenWithCardinality::new_single(Token::Name {
+                            name: name.clone(),
+                        }))),
+                    },
+                    InputVisibility::Private => InputZK {
+                        visibility: InputVisibility::Private,
+                        token: None,
+                    },
+                };
+                BlockInput { name, input }
+            })
+            .collect()
+    }
+}
+
+// Circom constraints are at most quadratic, so products of non constant terms that would go
+// beyond that are kept in auxiliary signals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Degree {
+    Constant,
+    Linear,
+    Quadratic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Sum,
+    Product,
+    Atom,
+}
+
+struct CircomExpression {
+    text: String,
+    degree: Degree,
+    precedence: Precedence,
+}
+
+impl CircomExpression {
+    fn atom(text: String, degree: Degree) -> Self {
+        Self {
+            text,
+            degree,
+            precedence: Precedence::Atom,
+        }
+    }
+
+    fn wrapped(&self, precedence: Precedence) -> String {
+        if self.precedence >= precedence {
+            self.text.clone()
+        } else {
+            format!("({})", self.text)
+        }
+    }
+}
+
+#[derive(Default)]
+struct CircomSynthesizer {
+    lines: Vec<String>,
+    auxiliary_signals: usize,
+}
+
+impl CircomSynthesizer {
+    fn statement(&mut self, statement: &BlockStatement) {
+        match statement {
+            BlockStatement::Let { name, value } => {
+                let value = self.expression(value);
+                self.lines
+                    .push(format!("signal {} <== {};", name, value.text));
+            }
+            BlockStatement::Assert {
+                condition: BlockExpression::Equality { lhs, rhs },
+            } => {
+                let lhs = self.expression(lhs);
+                let rhs = self.expression(rhs);
+                let rhs = if lhs.degree == Degree::Quadratic {
+                    self.linear(rhs)
+                } else {
+                    rhs
+                };
+                self.lines.push(format!("{} === {};", lhs.text, rhs.text));
+            }
+            // Any other condition is a number that must be 1, like the result of an equality.
+            BlockStatement::Assert { condition } => {
+                let condition = self.expression(condition);
+                self.lines.push(format!("{} === 1;", condition.text));
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &BlockExpression) -> CircomExpression {
+        match expression {
+            BlockExpression::Int { value } => {
+                CircomExpression::atom(value.clone(), Degree::Constant)
+            }
+            BlockExpression::Variable { name, .. } => {
+                CircomExpression::atom(name.clone(), Degree::Linear)
+            }
+            BlockExpression::Negation { operand } => {
+                let operand = self.expression(operand);
+                CircomExpression {
+                    text: format!("-{}", operand.wrapped(Precedence::Atom)),
+                    degree: operand.degree,
+                    precedence: Precedence::Sum,
+                }
+            }
+            BlockExpression::Addition { lhs, rhs } => {
+                let (lhs, rhs) = (self.expression(lhs), self.expression(rhs));
+                self.sum(lhs, "+", rhs)
+            }
+            BlockExpression::Subtraction { lhs, rhs } => {
+                let (lhs, rhs) = (self.expression(lhs), self.expression(rhs));
+                self.sum(lhs, "-", rhs)
+            }
+            BlockExpression::Multiplication { lhs, rhs } => {
+                let (lhs, rhs) = (self.expression(lhs), self.expression(rhs));
+                self.product(lhs, rhs)
+            }
+            BlockExpression::Equality { lhs, rhs } => {
+                let (lhs, rhs) = (self.expression(lhs), self.expression(rhs));
+                self.equality(lhs, rhs)
+            }
+        }
+    }
+
+    fn sum(
+        &mut self,
+        lhs: CircomExpression,
+        operator: &str,
+        rhs: CircomExpression,
+    ) -> CircomExpression {
+        let rhs = if lhs.degree == Degree::Quadratic {
+            self.linear(rhs)
+        } else {
+            rhs
+        };
+        CircomExpression {
+            text: format!(
+                "{} {} {}",
+                lhs.text,
+                operator,
+                rhs.wrapped(Precedence::Product)
+            ),
+            degree: lhs.degree.max(rhs.degree),
+            precedence: Precedence::Sum,
+        }
+    }
+
+    fn product(&mut self, lhs: CircomExpression, rhs: CircomExpression) -> CircomExpression {
+        let (lhs, rhs, degree) = if lhs.degree == Degree::Constant || rhs.degree == Degree::Constant
+        {
+            let degree = lhs.degree.max(rhs.degree);
+            (lhs, rhs, degree)
+        } else {
+            (self.linear(lhs), self.linear(rhs), Degree::Quadratic)
+        };
+        CircomExpression {
+            text: format!(
+                "{} * {}",
+                lhs.wrapped(Precedence::Product),
+                rhs.wrapped(Precedence::Product)
+            ),
+            degree,
+            precedence: Precedence::Product,
+        }
+    }
+
+    // An equality used as a number is 1 when both sides are equal and 0 otherwise, computed like
+    // the IsZero template does with the inverse of their difference.
+    fn equality(&mut self, lhs: CircomExpression, rhs: CircomExpression) -> CircomExpression {
+        let difference = self.sum(lhs, "-", rhs);
+        let difference = self.linear(difference).wrapped(Precedence::Atom);
+        let equal = self.auxiliary_signal_name();
+        self.lines.push(format!(
+            "signal {}_inverse <-- {} != 0 ? 1 / {} : 0;",
+            equal, difference, difference
+        ));
+        self.lines.push(format!(
+            "signal {} <== 1 - {} * {}_inverse;",
+            equal, difference, equal
+        ));
+        self.lines
+            .push(format!("{} * {} === 0;", difference, equal));
+        CircomExpression::atom(equal, Degree::Linear)
+    }
+
+    fn linear(&mut self, expression: CircomExpression) -> CircomExpression {
+        if expression.degree < Degree::Quadratic {
+            return expression;
+        }
+        let signal = self.auxiliary_signal_name();
+        self.lines
+            .push(format!("signal {} <== {};", signal, expression.text));
+        CircomExpression::atom(signal, Degree::Linear)
+    }
+
+    fn auxiliary_signal_name(&mut self) -> String {
+        let name = format!("_aux{}", self.auxiliary_signals);
+        self.auxiliary_signals += 1;
+        name
+    }
+}
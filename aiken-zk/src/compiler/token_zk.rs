@@ -1,6 +1,6 @@
 use std::fmt;
 
-use crate::zk_examples::ZkExample;
+use crate::zk_examples::{OffchainArgumentError, ZkExample};
 use aiken_lang::parser::error::Pattern;
 use aiken_lang::parser::token::Base;
 use aiken_lang::parser::token::Token;
@@ -8,6 +8,9 @@ use aiken_lang::parser::token::Token;
 #[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Hash, Eq)]
 pub enum TokenZK {
     Offchain { example: ZkExample },
+    InvalidOffchain { error: OffchainArgumentError },
+    // An argument of an offchain statement like `datum.amount`, copied verbatim to the verify call.
+    Expression { text: String },
     Error(char),
     Name { name: String },
     Ordinal { index: u32 },
@@ -100,10 +103,12 @@ impl fmt::Display for TokenZK {
         let index_str;
         let s = match self {
             TokenZK::Offchain { example } => &format!("[offchain] -> {:?}", example),
+            TokenZK::InvalidOffchain { error } => &format!("[offchain] -> {}", error),
             TokenZK::Error(c) => {
                 write!(f, "\"{c}\"")?;
                 return Ok(());
             }
+            TokenZK::Expression { text } => text,
             TokenZK::Name { name } => name,
             TokenZK::Ordinal { index } => {
                 index_str = index.to_string();
@@ -1,43 +1,94 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::circom_templates::CircomTemplates;
 use crate::compiler::token_zk::TokenZK as Token;
-use crate::zk_examples::{InputVisibility, InputZK, ZkExample};
+use crate::inputs_template::CircuitSignal;
+use crate::zk_examples::{CircuitTemplateParameter, InputVisibility, InputZK, ZkExample};
 
 pub struct ComponentCreator {
     pub token: Token,
+    templates_dir: String,
+}
+
+// The template an offchain statement is compiled to, with the signal each argument feeds.
+struct ComponentDescription<'a> {
+    template: ComponentTemplate,
+    template_name: &'static str,
+    inputs: Vec<ComponentInput<'a>>,
+    template_parameters: Vec<String>,
+}
+
+enum ComponentTemplate {
+    // One of the templates shipped in the binary, included from the templates dir.
+    Embedded(&'static str),
+    // Written in the circuit itself, like the templates of offchain blocks.
+    Synthesized(String),
+}
+
+// Signals like the secrets of a proof only come from the inputs file, not from the validator.
+static PRIVATE_INPUT: InputZK = InputZK {
+    visibility: InputVisibility::Private,
+    token: None,
+};
+
+struct ComponentInput<'a> {
+    input: &'a InputZK,
+    signal_name: String,
+    dimensions: Vec<usize>,
+}
+
+impl<'a> ComponentInput<'a> {
+    fn scalar(input: &'a InputZK, signal_name: &str) -> Self {
+        Self::array(input, signal_name, vec![])
+    }
+
+    fn private(signal_name: &str, dimensions: Vec<usize>) -> Self {
+        Self::array(&PRIVATE_INPUT, signal_name, dimensions)
+    }
+
+    fn array(input: &'a InputZK, signal_name: &str, dimensions: Vec<usize>) -> Self {
+        Self {
+            input,
+            signal_name: signal_name.to_string(),
+            dimensions,
+        }
+    }
 }
 
 impl ComponentCreator {
+    const USED_CIRCOM_VERSION: &'static str = "2.1.9";
+    const OFFCHAIN_BLOCK_TEMPLATE_NAME: &'static str = "OffchainBlock";
+
     pub fn from_token(token: Token) -> Self {
-        Self { token }
+        Self {
+            token,
+            templates_dir: CircomTemplates::DIRECTORY.to_string(),
+        }
     }
-    const USED_CIRCOM_VERSION: &'static str = "2.1.9";
 
-    fn process_component_inputs_and_template_parameters<const N: usize>(
-        template_file_name: &str,
-        template_name: &str,
-        input_to_identifiers: [(&InputZK, &str); N],
-        template_parameters: &[&str],
-    ) -> String {
-        let public_inputs_identifiers = Self::process_inputs_visibility(input_to_identifiers);
+    // Where the generated circuit includes the template from, relative to the circuit file.
+    pub fn with_templates_dir(self, templates_dir: String) -> Self {
+        Self {
+            templates_dir,
+            ..self
+        }
+    }
 
-        Self::generate_circom_component(
-            Self::USED_CIRCOM_VERSION,
-            template_file_name,
-            template_name,
-            public_inputs_identifiers,
-            template_parameters,
-        )
+    // Synthesized templates have no file: they are part of the circuit.
+    pub fn template_file_name(&self) -> Result<Option<&'static str>, AikenZkError> {
+        match self.describe()?.template {
+            ComponentTemplate::Embedded(template_file_name) => Ok(Some(template_file_name)),
+            ComponentTemplate::Synthesized(_) => Ok(None),
+        }
     }
 
     fn generate_circom_component(
         circom_version: &str,
-        template_file_name: &str,
+        template_source: &str,
         template_name: &str,
         public_inputs_identifiers: Vec<String>,
-        circuit_template_parameters: &[&str],
+        circuit_template_parameters: &[String],
     ) -> String {
         let circom_version_line = format!("pragma circom {};", circom_version);
-        let import_line = format!("include \"templates/{}.circom\";", template_file_name);
-
         let visibility_line = Self::generate_inputs_visibility(public_inputs_identifiers);
 
         let component_parameters =
@@ -50,11 +101,11 @@ impl ComponentCreator {
 
         format!(
             "{}\n{}\n{}",
-            circom_version_line, import_line, instantiation
+            circom_version_line, template_source, instantiation
         )
     }
 
-    fn process_and_generate_template_arguments(parameters: &[&str]) -> String {
+    fn process_and_generate_template_arguments(parameters: &[String]) -> String {
         if parameters.is_empty() {
             "()".to_string()
         } else {
@@ -70,161 +121,156 @@ impl ComponentCreator {
         }
     }
 
-    pub fn create(&self) -> String {
+    pub fn create(&self) -> Result<String, AikenZkError> {
+        let description = self.describe()?;
+        let public_inputs_identifiers = Self::process_inputs_visibility(&description.inputs);
+        let template_source = match &description.template {
+            ComponentTemplate::Embedded(template_file_name) => format!(
+                "include \"{}/{}\";",
+                self.templates_dir,
+                CircomTemplates::file_name(template_file_name)
+            ),
+            ComponentTemplate::Synthesized(source) => source.clone(),
+        };
+
+        Ok(Self::generate_circom_component(
+            Self::USED_CIRCOM_VERSION,
+            &template_source,
+            description.template_name,
+            public_inputs_identifiers,
+            &description.template_parameters,
+        ))
+    }
+
+    // The input signals of the generated component, as they must appear in the inputs json.
+    pub fn signals(&self) -> Result<Vec<CircuitSignal>, AikenZkError> {
+        let description = self.describe()?;
+        Ok(description
+            .inputs
+            .into_iter()
+            .map(|component_input| CircuitSignal {
+                name: component_input.signal_name,
+                dimensions: component_input.dimensions,
+                visibility: component_input.input.visibility.clone(),
+            })
+            .collect())
+    }
+
+    fn describe(&self) -> Result<ComponentDescription<'_>, AikenZkError> {
         let Token::Offchain { example } = &self.token else {
-            panic!("Not expected kind of token")
+            return Err(Self::unexpected_token("an offchain statement", &self.token));
         };
-        match example {
-            ZkExample::Addition { lhs, rhs, res } => {
-                let template_file_name = "addition";
-                let template_name = "Addition";
-
-                let input_to_identifiers =
-                    [(lhs, "first_addend"), (rhs, "second_addend"), (res, "sum")];
-                let template_parameters = &[];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    input_to_identifiers,
-                    template_parameters,
-                )
-            }
-            ZkExample::Subtraction { lhs, rhs, res } => {
-                let template_file_name = "subtraction";
-                let template_name = "Subtraction";
-
-                let input_to_identifiers =
-                    [(lhs, "minuend"), (rhs, "subtrahend"), (res, "difference")];
-                let template_parameters = &[];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    input_to_identifiers,
-                    template_parameters,
-                )
-            }
-            ZkExample::Multiplication { lhs, rhs, res } => {
-                let template_file_name = "multiplication";
-                let template_name = "Multiplication";
-
-                let input_to_identifiers =
-                    [(lhs, "multiplicand"), (rhs, "multiplier"), (res, "product")];
-                let template_parameters = &[];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    input_to_identifiers,
-                    template_parameters,
-                )
-            }
+        let description = match example {
+            ZkExample::Addition { lhs, rhs, res } => ComponentDescription {
+                template: ComponentTemplate::Embedded("addition"),
+                template_name: "Addition",
+                inputs: vec![
+                    ComponentInput::scalar(lhs, "first_addend"),
+                    ComponentInput::scalar(rhs, "second_addend"),
+                    ComponentInput::scalar(res, "sum"),
+                ],
+                template_parameters: vec![],
+            },
+            ZkExample::Subtraction { lhs, rhs, res } => ComponentDescription {
+                template: ComponentTemplate::Embedded("subtraction"),
+                template_name: "Subtraction",
+                inputs: vec![
+                    ComponentInput::scalar(lhs, "minuend"),
+                    ComponentInput::scalar(rhs, "subtrahend"),
+                    ComponentInput::scalar(res, "difference"),
+                ],
+                template_parameters: vec![],
+            },
+            ZkExample::Multiplication { lhs, rhs, res } => ComponentDescription {
+                template: ComponentTemplate::Embedded("multiplication"),
+                template_name: "Multiplication",
+                inputs: vec![
+                    ComponentInput::scalar(lhs, "multiplicand"),
+                    ComponentInput::scalar(rhs, "multiplier"),
+                    ComponentInput::scalar(res, "product"),
+                ],
+                template_parameters: vec![],
+            },
             ZkExample::Fibonacci {
                 fib_0,
                 fib_1,
                 n,
                 res,
             } => {
-                let Token::Int { value, base: _ } = *n.token.clone() else {
-                    panic!("Not expected kind of token")
-                };
-
-                let template_file_name = "fibonacci";
-                let template_name = "Fibonacci";
-
-                let inputs_to_identifiers = [
-                    (fib_0, "first_fibonacci"),
-                    (fib_1, "second_fibonacci"),
-                    (res, "nth_fibonacci"),
-                ];
-                let template_parameters = &[value.as_str()];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    inputs_to_identifiers,
-                    template_parameters,
-                )
+                let (value, _) = Self::template_parameter_value(n)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("fibonacci"),
+                    template_name: "Fibonacci",
+                    inputs: vec![
+                        ComponentInput::scalar(fib_0, "first_fibonacci"),
+                        ComponentInput::scalar(fib_1, "second_fibonacci"),
+                        ComponentInput::scalar(res, "nth_fibonacci"),
+                    ],
+                    template_parameters: vec![value],
+                }
             }
             ZkExample::If {
                 condition,
                 assigned,
                 true_branch,
                 false_branch,
-            } => {
-                let template_file_name = "if";
-                let template_name = "If";
-
-                let inputs_to_identifiers = [
-                    (condition, "condition"),
-                    (assigned, "assigned"),
-                    (true_branch, "true_branch"),
-                    (false_branch, "false_branch"),
-                ];
-                let template_parameters = &[];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    inputs_to_identifiers,
-                    template_parameters,
-                )
-            }
-            ZkExample::AssertEq { lhs, rhs } => {
-                let template_file_name = "assert_eq";
-                let template_name = "AssertEq";
-
-                let inputs_to_identifiers = [(lhs, "lhs"), (rhs, "rhs")];
-                let template_parameters = &[];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    inputs_to_identifiers,
-                    template_parameters,
-                )
-            }
+            } => ComponentDescription {
+                template: ComponentTemplate::Embedded("if"),
+                template_name: "If",
+                inputs: vec![
+                    ComponentInput::scalar(condition, "condition"),
+                    ComponentInput::scalar(assigned, "assigned"),
+                    ComponentInput::scalar(true_branch, "true_branch"),
+                    ComponentInput::scalar(false_branch, "false_branch"),
+                ],
+                template_parameters: vec![],
+            },
+            ZkExample::AssertEq { lhs, rhs } => ComponentDescription {
+                template: ComponentTemplate::Embedded("assert_eq"),
+                template_name: "AssertEq",
+                inputs: vec![
+                    ComponentInput::scalar(lhs, "lhs"),
+                    ComponentInput::scalar(rhs, "rhs"),
+                ],
+                template_parameters: vec![],
+            },
             ZkExample::CustomCircom { .. } => {
-                panic!("You shouldn't be here")
+                return Err(Self::unexpected_token(
+                    "one of the built-in offchain functions",
+                    &self.token,
+                ));
             }
             ZkExample::Sha256 { n_bits, r#in, out } => {
-                let Token::Int { value, base: _ } = *n_bits.token.clone() else {
-                    panic!("Not expected kind of token")
-                };
-                let template_file_name = "hash";
-                let template_name = "Sha256";
-
-                let inputs_to_identifiers = [(r#in, "in"), (out, "out")];
-                let template_parameters = &[value.as_str()];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    inputs_to_identifiers,
-                    template_parameters,
-                )
+                let (value, size) = Self::template_parameter_value(n_bits)?;
+
+                // The digest is split in two field elements: 256 bits don't fit in one.
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("hash"),
+                    template_name: "Sha256",
+                    inputs: vec![
+                        ComponentInput::array(r#in, "in", vec![size]),
+                        ComponentInput::array(out, "out", vec![2]),
+                    ],
+                    template_parameters: vec![value],
+                }
             }
             ZkExample::Poseidon {
                 n_inputs,
                 r#in,
                 out,
             } => {
-                let Token::Int { value, base: _ } = *n_inputs.token.clone() else {
-                    panic!("Not expected kind of token")
-                };
-                let template_file_name = "hash";
-                let template_name = "Poseidon";
-
-                let inputs_to_identifiers = [(r#in, "in"), (out, "out")];
-                let template_parametrs = &[value.as_str()];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    inputs_to_identifiers,
-                    template_parametrs,
-                )
+                let (value, size) = Self::template_parameter_value(n_inputs)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("hash"),
+                    template_name: "Poseidon",
+                    inputs: vec![
+                        ComponentInput::array(r#in, "in", vec![size]),
+                        ComponentInput::scalar(out, "out"),
+                    ],
+                    template_parameters: vec![value],
+                }
             }
             ZkExample::MerkleTreeChecker {
                 levels,
@@ -233,26 +279,19 @@ impl ComponentCreator {
                 path_elements,
                 path_indices,
             } => {
-                let Token::Int { value, base: _ } = *levels.token.clone() else {
-                    panic!("Not expected kind of token")
-                };
-                let template_file_name = "merkle_tree_checker";
-                let template_name = "MerkleTreeChecker";
-
-                let inputs_to_identifiers = [
-                    (leaf, "leaf"),
-                    (root, "root"),
-                    (path_elements, "pathElements"),
-                    (path_indices, "pathIndices"),
-                ];
-                let template_parameters = &[value.as_str()];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    inputs_to_identifiers,
-                    template_parameters,
-                )
+                let (value, size) = Self::template_parameter_value(levels)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("merkle_tree_checker"),
+                    template_name: "MerkleTreeChecker",
+                    inputs: vec![
+                        ComponentInput::scalar(leaf, "leaf"),
+                        ComponentInput::scalar(root, "root"),
+                        ComponentInput::array(path_elements, "pathElements", vec![size]),
+                        ComponentInput::array(path_indices, "pathIndices", vec![size]),
+                    ],
+                    template_parameters: vec![value],
+                }
             }
             ZkExample::PolynomialEvaluations {
                 grade,
@@ -261,54 +300,180 @@ impl ComponentCreator {
                 domain,
                 evaluations,
             } => {
-                let Token::Int {
-                    value: polynomial_grade,
-                    base: _,
-                } = *grade.token.clone()
-                else {
-                    panic!("Not expected kind of token")
-                };
-                let Token::Int {
-                    value: amount_of_evaluations,
-                    base: _,
-                } = *amount_of_evaluations.token.clone()
-                else {
-                    panic!("Not expected kind of token")
-                };
-                let template_file_name = "polynomials";
-                let template_name = "PolynomialEvaluations";
-
-                let inputs_to_identifiers = [
-                    (coefficients, "coefficients"),
-                    (domain, "domain"),
-                    (evaluations, "evaluations"),
-                ];
-                let template_parameters =
-                    &[polynomial_grade.as_str(), amount_of_evaluations.as_str()];
-
-                Self::process_component_inputs_and_template_parameters(
-                    template_file_name,
-                    template_name,
-                    inputs_to_identifiers,
-                    template_parameters,
-                )
+                let (polynomial_grade, grade_size) = Self::template_parameter_value(grade)?;
+                let (amount_of_evaluations, evaluations_size) =
+                    Self::template_parameter_value(amount_of_evaluations)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("polynomials"),
+                    template_name: "PolynomialEvaluations",
+                    inputs: vec![
+                        ComponentInput::array(coefficients, "coefficients", vec![grade_size + 1]),
+                        ComponentInput::array(domain, "domain", vec![evaluations_size]),
+                        ComponentInput::array(evaluations, "evaluations", vec![evaluations_size]),
+                    ],
+                    template_parameters: vec![polynomial_grade, amount_of_evaluations],
+                }
             }
-        }
+            ZkExample::RangeCheck {
+                n_bits,
+                value,
+                lower_bound,
+                upper_bound,
+            } => {
+                let (value_bits, _) = Self::template_parameter_value(n_bits)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("range_check"),
+                    template_name: "RangeCheck",
+                    inputs: vec![
+                        ComponentInput::scalar(value, "value"),
+                        ComponentInput::scalar(lower_bound, "lower_bound"),
+                        ComponentInput::scalar(upper_bound, "upper_bound"),
+                    ],
+                    template_parameters: vec![value_bits],
+                }
+            }
+            ZkExample::LessThan {
+                n_bits,
+                lhs,
+                rhs,
+                out,
+            } => {
+                let (value_bits, _) = Self::template_parameter_value(n_bits)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("comparators"),
+                    template_name: "LessThan",
+                    inputs: vec![
+                        ComponentInput::scalar(lhs, "lhs"),
+                        ComponentInput::scalar(rhs, "rhs"),
+                        ComponentInput::scalar(out, "out"),
+                    ],
+                    template_parameters: vec![value_bits],
+                }
+            }
+            ZkExample::GreaterEqual {
+                n_bits,
+                lhs,
+                rhs,
+                out,
+            } => {
+                let (value_bits, _) = Self::template_parameter_value(n_bits)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("comparators"),
+                    template_name: "GreaterEqual",
+                    inputs: vec![
+                        ComponentInput::scalar(lhs, "lhs"),
+                        ComponentInput::scalar(rhs, "rhs"),
+                        ComponentInput::scalar(out, "out"),
+                    ],
+                    template_parameters: vec![value_bits],
+                }
+            }
+            ZkExample::IsZero { r#in, out } => ComponentDescription {
+                template: ComponentTemplate::Embedded("comparators"),
+                template_name: "IsZero",
+                inputs: vec![
+                    ComponentInput::scalar(r#in, "in"),
+                    ComponentInput::scalar(out, "out"),
+                ],
+                template_parameters: vec![],
+            },
+            ZkExample::EddsaVerify {
+                pub_key_x,
+                pub_key_y,
+                msg,
+                r_x,
+                r_y,
+                s,
+            } => ComponentDescription {
+                template: ComponentTemplate::Embedded("eddsa"),
+                template_name: "EddsaVerify",
+                inputs: vec![
+                    ComponentInput::scalar(pub_key_x, "pub_key_x"),
+                    ComponentInput::scalar(pub_key_y, "pub_key_y"),
+                    ComponentInput::scalar(msg, "msg"),
+                    ComponentInput::scalar(r_x, "r_x"),
+                    ComponentInput::scalar(r_y, "r_y"),
+                    ComponentInput::scalar(s, "s"),
+                ],
+                template_parameters: vec![],
+            },
+            ZkExample::MembershipWithNullifier {
+                levels,
+                root,
+                nullifier,
+                external_nullifier,
+            } => {
+                let (value, size) = Self::template_parameter_value(levels)?;
+
+                ComponentDescription {
+                    template: ComponentTemplate::Embedded("membership_with_nullifier"),
+                    template_name: "MembershipWithNullifier",
+                    inputs: vec![
+                        ComponentInput::scalar(root, "root"),
+                        ComponentInput::scalar(nullifier, "nullifier"),
+                        ComponentInput::scalar(external_nullifier, "external_nullifier"),
+                        ComponentInput::private("secret", vec![]),
+                        ComponentInput::private("trapdoor", vec![]),
+                        ComponentInput::private("pathElements", vec![size]),
+                        ComponentInput::private("pathIndices", vec![size]),
+                    ],
+                    template_parameters: vec![value],
+                }
+            }
+            ZkExample::ExpressionBlock { block } => ComponentDescription {
+                template: ComponentTemplate::Synthesized(
+                    block.circom_template(Self::OFFCHAIN_BLOCK_TEMPLATE_NAME),
+                ),
+                template_name: Self::OFFCHAIN_BLOCK_TEMPLATE_NAME,
+                inputs: block
+                    .inputs
+                    .iter()
+                    .map(|block_input| {
+                        ComponentInput::scalar(&block_input.input, &block_input.name)
+                    })
+                    .collect(),
+                template_parameters: vec![],
+            },
+        };
+        Ok(description)
     }
 
-    fn process_inputs_visibility<const N: usize>(
-        public_inputs_identifiers: [(&InputZK, &str); N],
-    ) -> Vec<String> {
-        public_inputs_identifiers
+    fn process_inputs_visibility(component_inputs: &[ComponentInput]) -> Vec<String> {
+        component_inputs
             .iter()
-            .fold(vec![], |mut acc, (input, var_name)| {
-                match input.visibility {
+            .fold(vec![], |mut acc, component_input| {
+                match component_input.input.visibility {
                     InputVisibility::Private => acc,
                     InputVisibility::Public => {
-                        acc.push(var_name.to_string());
+                        acc.push(component_input.signal_name.clone());
                         acc
                     }
                 }
             })
     }
+
+    // Template parameters are passed verbatim to circom, and also size the array signals.
+    fn template_parameter_value(
+        parameter: &CircuitTemplateParameter,
+    ) -> Result<(String, usize), AikenZkError> {
+        let Token::Int { value, base: _ } = *parameter.token.clone() else {
+            return Err(Self::unexpected_token("a constant", &parameter.token));
+        };
+        let size = value
+            .parse()
+            .map_err(|_| Self::unexpected_token("a constant", &parameter.token))?;
+        Ok((value, size))
+    }
+
+    fn unexpected_token(expected: &'static str, found: &Token) -> AikenZkError {
+        AikenZkError::UnexpectedToken {
+            expected,
+            found: found.to_string(),
+            span: None,
+        }
+    }
 }
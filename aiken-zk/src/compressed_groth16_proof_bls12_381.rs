@@ -1,4 +1,23 @@
-use std::process::{Command, Output};
+use crate::point_compressor_bls12_381::{PointCompressionError, PointCompressorBls12_381};
+use serde_json::Value;
+use std::{fs, io};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProofCompressionError {
+    #[error("couldn't read the proof: {0}")]
+    Io(#[from] io::Error),
+    #[error("the proof is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the proof has no '{0}' field")]
+    MissingField(&'static str),
+    #[error("invalid '{field}' in the proof: {source}")]
+    InvalidPoint {
+        field: &'static str,
+        source: PointCompressionError,
+    },
+}
+
 #[derive(Clone)]
 pub struct CompressedGroth16ProofBls12_381 {
     pi_a: String,
@@ -15,24 +34,28 @@ impl CompressedGroth16ProofBls12_381 {
         }
     }
 
-    pub fn from_json(build_path: &str) -> Self {
-        let command_output = Self::execute_curve_compress_command(&build_path);
+    pub fn from_json(build_path: &str) -> Result<Self, ProofCompressionError> {
+        let proof_json = fs::read_to_string(build_path.to_string() + "proof.json")?;
+        Self::from_snarkjs_proof(&serde_json::from_str(&proof_json)?)
+    }
 
-        let standard_output = String::from_utf8(command_output.stdout).unwrap();
+    pub fn from_snarkjs_proof(proof: &Value) -> Result<Self, ProofCompressionError> {
+        let pi_a = Self::compress_field(proof, "pi_a", PointCompressorBls12_381::compress_g1)?;
+        let pi_b = Self::compress_field(proof, "pi_b", PointCompressorBls12_381::compress_g2)?;
+        let pi_c = Self::compress_field(proof, "pi_c", PointCompressorBls12_381::compress_g1)?;
 
-        Self::new(
-            &standard_output[..96],
-            &standard_output[96..288],
-            &standard_output[288..384],
-        )
+        Ok(Self::new(&pi_a, &pi_b, &pi_c))
     }
 
-    fn execute_curve_compress_command(build_path: &str) -> Output {
-        Command::new("node")
-            .arg("curve_compress/compressedProof.js")
-            .arg(build_path.to_string() + "proof.json")
-            .output()
-            .expect("failed to finish proof compression")
+    fn compress_field(
+        proof: &Value,
+        field: &'static str,
+        compress: fn(&Value) -> Result<String, PointCompressionError>,
+    ) -> Result<String, ProofCompressionError> {
+        let point = proof
+            .get(field)
+            .ok_or(ProofCompressionError::MissingField(field))?;
+        compress(point).map_err(|source| ProofCompressionError::InvalidPoint { field, source })
     }
 
     pub fn pi_a_as_byte_string(&self) -> &str {
@@ -0,0 +1,101 @@
+use crate::point_compressor_bls12_381::{PointCompressionError, PointCompressorBls12_381};
+use serde_json::Value;
+use std::{fs, io};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum VerificationKeyCompressionError {
+    #[error("couldn't read the verification key: {0}")]
+    Io(#[from] io::Error),
+    #[error("the verification key is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the verification key has no '{0}' field")]
+    MissingField(&'static str),
+    #[error("invalid '{field}' in the verification key: {source}")]
+    InvalidPoint {
+        field: String,
+        source: PointCompressionError,
+    },
+}
+
+#[derive(Debug)]
+#[allow(non_snake_case)]
+pub struct Groth16CompressedData {
+    pub vk_alpha_1: String,
+    pub vk_beta_2: String,
+    pub vk_gamma_2: String,
+    pub vk_delta_2: String,
+    pub IC: Vec<String>,
+}
+
+impl Groth16CompressedData {
+    pub fn from_json(verification_key_path: &str) -> Result<Self, VerificationKeyCompressionError> {
+        let verification_key_json = fs::read_to_string(verification_key_path)?;
+        Self::from_snarkjs_verification_key(&serde_json::from_str(&verification_key_json)?)
+    }
+
+    pub fn from_snarkjs_verification_key(
+        verification_key: &Value,
+    ) -> Result<Self, VerificationKeyCompressionError> {
+        let ic = Self::field(verification_key, "IC")?
+            .as_array()
+            .ok_or(VerificationKeyCompressionError::MissingField("IC"))?
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                PointCompressorBls12_381::compress_g1(point).map_err(|source| {
+                    VerificationKeyCompressionError::InvalidPoint {
+                        field: format!("IC[{}]", index),
+                        source,
+                    }
+                })
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+
+        Ok(Self {
+            vk_alpha_1: Self::compress_field(
+                verification_key,
+                "vk_alpha_1",
+                PointCompressorBls12_381::compress_g1,
+            )?,
+            vk_beta_2: Self::compress_field(
+                verification_key,
+                "vk_beta_2",
+                PointCompressorBls12_381::compress_g2,
+            )?,
+            vk_gamma_2: Self::compress_field(
+                verification_key,
+                "vk_gamma_2",
+                PointCompressorBls12_381::compress_g2,
+            )?,
+            vk_delta_2: Self::compress_field(
+                verification_key,
+                "vk_delta_2",
+                PointCompressorBls12_381::compress_g2,
+            )?,
+            IC: ic,
+        })
+    }
+
+    fn field<'a>(
+        verification_key: &'a Value,
+        field: &'static str,
+    ) -> Result<&'a Value, VerificationKeyCompressionError> {
+        verification_key
+            .get(field)
+            .ok_or(VerificationKeyCompressionError::MissingField(field))
+    }
+
+    fn compress_field(
+        verification_key: &Value,
+        field: &'static str,
+        compress: fn(&Value) -> Result<String, PointCompressionError>,
+    ) -> Result<String, VerificationKeyCompressionError> {
+        compress(Self::field(verification_key, field)?).map_err(|source| {
+            VerificationKeyCompressionError::InvalidPoint {
+                field: field.to_string(),
+                source,
+            }
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::inputs_validator::InputsValidator;
+use crate::jubjub::JubjubPoint;
+use crate::poseidon_bls12_381::PoseidonBls12381;
+use ark_bls12_381::Fr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use miette::Diagnostic;
+use num_bigint::{BigUint, Sign};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha512};
+use std::fs;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Diagnostic)]
+pub enum EddsaError {
+    // The key is never echoed back.
+    #[error("the private key must be a number between 1 and the order of the Jubjub subgroup")]
+    InvalidPrivateKey,
+    #[error("the message {value} is not a number of the BLS12-381 scalar field")]
+    InvalidMessage { value: String },
+}
+
+// An EdDSA signature over Jubjub, as the eddsa_verify template checks it: s * B = R + h * A,
+// multiplied by the cofactor, where A is the public key and the challenge h is the Poseidon hash
+// of R, A and the message, hashed in pairs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EddsaSignature {
+    pub public_key: JubjubPoint,
+    pub msg: Fr,
+    pub r: JubjubPoint,
+    pub s: BigUint,
+}
+
+impl EddsaSignature {
+    const PRIVATE_KEY_BYTES: usize = 32;
+
+    pub fn sign(private_key: &BigUint, msg: Fr) -> Result<Self, EddsaError> {
+        let public_key = Self::public_key(private_key)?;
+        let subgroup_order = JubjubPoint::subgroup_order();
+
+        // Deterministic like RFC 8032: the nonce hashes a secret prefix, kept apart from the
+        // scalar, with the message. The 512 bit hash makes its reduction modulo the order unbiased.
+        let nonce = BigUint::from_bytes_be(
+            &Sha512::new()
+                .chain_update(Self::nonce_prefix(private_key))
+                .chain_update(msg.into_bigint().to_bytes_be())
+                .finalize(),
+        ) % &subgroup_order;
+        let r = JubjubPoint::generator().mul(&nonce);
+        let s = (nonce + Self::challenge(&r, &public_key, msg) * private_key) % subgroup_order;
+
+        Ok(Self {
+            public_key,
+            msg,
+            r,
+            s,
+        })
+    }
+
+    pub fn public_key(private_key: &BigUint) -> Result<JubjubPoint, EddsaError> {
+        if private_key.is_zero() || *private_key >= JubjubPoint::subgroup_order() {
+            return Err(EddsaError::InvalidPrivateKey);
+        }
+        Ok(JubjubPoint::generator().mul(private_key))
+    }
+
+    pub fn challenge(r: &JubjubPoint, public_key: &JubjubPoint, msg: Fr) -> BigUint {
+        let hash = PoseidonBls12381::hash_left_right(
+            PoseidonBls12381::hash_left_right(
+                PoseidonBls12381::hash_left_right(r.x, r.y),
+                PoseidonBls12381::hash_left_right(public_key.x, public_key.y),
+            ),
+            msg,
+        );
+        BigUint::from(hash.into_bigint())
+    }
+
+    // The same checks as the circuit. Public keys of small order would accept any signature.
+    pub fn verify(&self) -> bool {
+        let challenge = Self::challenge(&self.r, &self.public_key, self.msg);
+        let signed = JubjubPoint::generator().mul(&self.s);
+        let expected = self.r.add(&self.public_key.mul(&challenge));
+
+        self.public_key.is_on_curve()
+            && self.r.is_on_curve()
+            && self.public_key.mul_by_cofactor() != JubjubPoint::identity()
+            && self.s < JubjubPoint::subgroup_order()
+            && signed.mul_by_cofactor() == expected.mul_by_cofactor()
+    }
+
+    // A file with the key as a decimal or hexadecimal number, so it stays out of the shell history.
+    pub fn private_key_from_file(path: &str) -> Result<BigUint, AikenZkError> {
+        let private_key =
+            fs::read_to_string(path).map_err(|error| AikenZkError::io(path, error))?;
+        Ok(InputsValidator::parse_integer(private_key.trim())
+            .filter(|private_key| private_key.sign() != Sign::Minus)
+            .and_then(|private_key| private_key.to_biguint())
+            .ok_or(EddsaError::InvalidPrivateKey)?)
+    }
+
+    pub fn msg_from_text(text: &str) -> Result<Fr, EddsaError> {
+        InputsValidator::parse_field_element(text).ok_or_else(|| EddsaError::InvalidMessage {
+            value: text.to_string(),
+        })
+    }
+
+    pub fn to_inputs_json(&self) -> Value {
+        let decimal = InputsValidator::field_element_to_decimal;
+        json!({
+            "pub_key_x": decimal(&self.public_key.x),
+            "pub_key_y": decimal(&self.public_key.y),
+            "msg": decimal(&self.msg),
+            "r_x": decimal(&self.r.x),
+            "r_y": decimal(&self.r.y),
+            "s": self.s.to_string(),
+        })
+    }
+
+    // The upper half of the hash of the key, as RFC 8032 does. Unlike there, the key is the
+    // scalar itself, so the lower half is not used.
+    fn nonce_prefix(private_key: &BigUint) -> Vec<u8> {
+        Sha512::digest(Self::private_key_bytes(private_key))[Self::PRIVATE_KEY_BYTES..].to_vec()
+    }
+
+    fn private_key_bytes(private_key: &BigUint) -> Vec<u8> {
+        let bytes = private_key.to_bytes_be();
+        let mut padded = vec![0; Self::PRIVATE_KEY_BYTES.saturating_sub(bytes.len())];
+        padded.extend(bytes);
+        padded
+    }
+}
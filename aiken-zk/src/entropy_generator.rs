@@ -1,17 +1,115 @@
 use rand::distr::{Alphanumeric, SampleString};
 use rand::rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::Cell;
 
-pub struct EntropyGenerator {}
+pub struct EntropyGenerator {
+    seed: Option<Vec<u8>>,
+    generated: Cell<u32>,
+}
 
 impl EntropyGenerator {
     const ENTROPY_LENGTH: usize = 200_usize;
 
     pub fn new() -> Self {
-        Self {}
+        Self {
+            seed: None,
+            generated: Cell::new(0),
+        }
+    }
+
+    // Seeded generators derive every value from the seed, so the whole setup can be replayed.
+    // Anyone holding the seed can recompute the setup trapdoor: it must be kept secret.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        Self {
+            seed: Some(seed.to_vec()),
+            generated: Cell::new(0),
+        }
+    }
+
+    pub fn is_seeded(&self) -> bool {
+        self.seed.is_some()
+    }
+
+    pub fn seed_commitment(&self) -> Option<SeedCommitment> {
+        self.seed.as_deref().map(SeedCommitment::for_seed)
     }
 
     pub fn generate(&self) -> String {
-        let mut random_generator = rng();
-        Alphanumeric.sample_string(&mut random_generator, Self::ENTROPY_LENGTH)
+        match &self.seed {
+            None => {
+                let mut random_generator = rng();
+                Alphanumeric.sample_string(&mut random_generator, Self::ENTROPY_LENGTH)
+            }
+            Some(seed) => {
+                let index = self.generated.get();
+                self.generated.set(index + 1);
+                hex::encode(
+                    Sha256::new()
+                        .chain_update(seed)
+                        .chain_update(index.to_be_bytes())
+                        .finalize(),
+                )
+            }
+        }
+    }
+}
+
+impl Default for EntropyGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Lets an auditor that holds a seed check it was the one used, without giving it away to anyone
+// else. The random salt rules out precomputed tables and the iterated hash makes every guess of a
+// weak seed slow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedCommitment {
+    pub salt: String,
+    pub iterations: u32,
+    pub hash: String,
+}
+
+impl SeedCommitment {
+    pub const ITERATIONS: u32 = 100_000;
+    const SALT_LENGTH: usize = 16;
+
+    pub fn for_seed(seed: &[u8]) -> Self {
+        Self::with_salt(
+            seed,
+            &rand::random::<[u8; Self::SALT_LENGTH]>(),
+            Self::ITERATIONS,
+        )
+    }
+
+    pub fn with_salt(seed: &[u8], salt: &[u8], iterations: u32) -> Self {
+        Self {
+            salt: hex::encode(salt),
+            iterations,
+            hash: hex::encode(Self::stretch(seed, salt, iterations)),
+        }
+    }
+
+    pub fn matches(&self, seed: &[u8]) -> bool {
+        hex::decode(&self.salt)
+            .is_ok_and(|salt| Self::with_salt(seed, &salt, self.iterations) == *self)
+    }
+
+    fn stretch(seed: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+        let mut hash: [u8; 32] = Sha256::new()
+            .chain_update(salt)
+            .chain_update(seed)
+            .finalize()
+            .into();
+        for _ in 1..iterations {
+            hash = Sha256::new()
+                .chain_update(hash)
+                .chain_update(seed)
+                .finalize()
+                .into();
+        }
+        hash
     }
 }
@@ -0,0 +1,362 @@
+use crate::point_compressor_bls12_381::PointCompressorBls12_381;
+use crate::r1cs_header::ByteReader;
+use ark_bls12_381::{Bls12_381, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInt, PrimeField, Zero};
+use ark_groth16::r1cs_to_qap::{LibsnarkReduction, R1CSToQAP, evaluate_constraint};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_poly::EvaluationDomain;
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSystemRef, SynthesisError};
+use num_bigint::BigUint;
+use rayon::prelude::*;
+use serde_json::{Value, json};
+use std::{fs, io};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProvingError {
+    #[error("couldn't access {path}: {source}")]
+    Io { path: String, source: io::Error },
+    #[error("{path} is not a BLS12-381 Groth16 proving key: {reason}")]
+    InvalidProvingKey { path: String, reason: &'static str },
+    #[error("the proving key expects a witness of {expected} values, got {actual}")]
+    WitnessSizeMismatch { expected: usize, actual: usize },
+    #[error("couldn't compute the proof: {0}")]
+    Synthesis(SynthesisError),
+    #[error("the witness doesn't satisfy the constraints of the proving key")]
+    UnsatisfiedConstraints,
+}
+
+// Proves with the proving keys written by snarkjs (`.zkey`) from the witnesses computed by the
+// circom witness generator, producing the same proof.json and public.json as
+// `snarkjs groth16 prove`. The proving key is read once and can be reused for many proofs.
+pub struct Groth16ProverBls12_381 {
+    proving_key: ProvingKey<Bls12_381>,
+    matrices: ConstraintMatrices<Fr>,
+}
+
+impl Groth16ProverBls12_381 {
+    const ZKEY_MAGIC: &'static [u8] = b"zkey";
+    const GROTH16_PROTOCOL: u32 = 1;
+    const BASE_FIELD_BYTES: usize = 48;
+    const SCALAR_FIELD_BYTES: usize = 32;
+
+    pub fn from_zkey(zkey_path: &str) -> Result<Self, ProvingError> {
+        let zkey = fs::read(zkey_path).map_err(|source| ProvingError::Io {
+            path: zkey_path.to_string(),
+            source,
+        })?;
+        Self::from_zkey_bytes(&zkey).map_err(|reason| ProvingError::InvalidProvingKey {
+            path: zkey_path.to_string(),
+            reason,
+        })
+    }
+
+    pub fn from_zkey_bytes(zkey: &[u8]) -> Result<Self, &'static str> {
+        let mut reader = ByteReader::new(zkey);
+        if reader.take(4)? != Self::ZKEY_MAGIC {
+            return Err("wrong magic number");
+        }
+        let _version = reader.u32()?;
+        let sections = reader.sections()?;
+        let section = |section_type: u32| {
+            sections
+                .get(&section_type)
+                .map(|section| ByteReader::new(section))
+                .ok_or("missing section")
+        };
+
+        if section(1)?.u32()? != Self::GROTH16_PROTOCOL {
+            return Err("not a Groth16 proving key");
+        }
+        let mut header = section(2)?;
+        Self::expect_field(
+            &mut header,
+            BigUint::from(Fq::MODULUS),
+            Self::BASE_FIELD_BYTES,
+        )?;
+        Self::expect_field(
+            &mut header,
+            BigUint::from(Fr::MODULUS),
+            Self::SCALAR_FIELD_BYTES,
+        )?;
+        let n_vars = header.u32()? as usize;
+        let n_public = header.u32()? as usize;
+        let domain_size = header.u32()? as usize;
+        let alpha_g1 = Self::g1(&mut header)?;
+        let beta_g1 = Self::g1(&mut header)?;
+        let beta_g2 = Self::g2(&mut header)?;
+        let gamma_g2 = Self::g2(&mut header)?;
+        let delta_g1 = Self::g1(&mut header)?;
+        let delta_g2 = Self::g2(&mut header)?;
+        let n_private = n_vars
+            .checked_sub(n_public + 1)
+            .ok_or("more public signals than variables")?;
+
+        let proving_key = ProvingKey {
+            vk: VerifyingKey {
+                alpha_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g2,
+                gamma_abc_g1: Self::g1_points(&mut section(3)?, n_public + 1)?,
+            },
+            beta_g1,
+            delta_g1,
+            a_query: Self::g1_points(&mut section(5)?, n_vars)?,
+            b_g1_query: Self::g1_points(&mut section(6)?, n_vars)?,
+            b_g2_query: Self::g2_points(&mut section(7)?, n_vars)?,
+            l_query: Self::g1_points(&mut section(8)?, n_private)?,
+            h_query: Self::g1_points(&mut section(9)?, domain_size)?,
+        };
+        let matrices = Self::matrices(&mut section(4)?, n_vars, n_public, domain_size)?;
+        Ok(Self {
+            proving_key,
+            matrices,
+        })
+    }
+
+    // Public signals are the witness values right after the constant one: outputs first, then
+    // public inputs.
+    pub fn prove(&self, witness: &[Fr]) -> Result<(Proof<Bls12_381>, Vec<Fr>), ProvingError> {
+        let expected = self.proving_key.a_query.len();
+        if witness.len() != expected {
+            return Err(ProvingError::WitnessSizeMismatch {
+                expected,
+                actual: witness.len(),
+            });
+        }
+        let proof =
+            Groth16::<Bls12_381, SnarkjsReduction>::create_proof_with_reduction_and_matrices(
+                &self.proving_key,
+                Self::random_scalar(),
+                Self::random_scalar(),
+                &self.matrices,
+                self.matrices.num_instance_variables,
+                self.matrices.num_constraints,
+                witness,
+            )
+            .map_err(ProvingError::Synthesis)?;
+
+        // Unsatisfied constraints still produce a proof: checking it is far cheaper than proving.
+        let public_signals = witness[1..self.matrices.num_instance_variables].to_vec();
+        let prepared_verifying_key = prepare_verifying_key(&self.proving_key.vk);
+        match Groth16::<Bls12_381>::verify_proof(&prepared_verifying_key, &proof, &public_signals) {
+            Ok(true) => Ok((proof, public_signals)),
+            Ok(false) => Err(ProvingError::UnsatisfiedConstraints),
+            Err(error) => Err(ProvingError::Synthesis(error)),
+        }
+    }
+
+    pub fn to_snarkjs_proof(proof: &Proof<Bls12_381>) -> Value {
+        json!({
+            "pi_a": PointCompressorBls12_381::g1_to_snarkjs(&proof.a),
+            "pi_b": PointCompressorBls12_381::g2_to_snarkjs(&proof.b),
+            "pi_c": PointCompressorBls12_381::g1_to_snarkjs(&proof.c),
+            "protocol": "groth16",
+            "curve": "bls12381",
+        })
+    }
+
+    pub fn to_snarkjs_public_signals(public_signals: &[Fr]) -> Value {
+        public_signals
+            .iter()
+            .map(|value| Value::String(BigUint::from(value.into_bigint()).to_string()))
+            .collect()
+    }
+
+    // private - proving key sections
+
+    fn expect_field(
+        reader: &mut ByteReader,
+        modulus: BigUint,
+        field_bytes: usize,
+    ) -> Result<(), &'static str> {
+        let n8 = reader.u32()? as usize;
+        if n8 != field_bytes || BigUint::from_bytes_le(reader.take(n8)?) != modulus {
+            return Err("not over the BLS12-381 curve");
+        }
+        Ok(())
+    }
+
+    // The A and B coefficients of each constraint, followed by one constraint per public signal
+    // that snarkjs adds so the public signals are bound to the proof. Those are added again by
+    // the reduction, so they are dropped here.
+    fn matrices(
+        reader: &mut ByteReader,
+        n_vars: usize,
+        n_public: usize,
+        domain_size: usize,
+    ) -> Result<ConstraintMatrices<Fr>, &'static str> {
+        let n_coefficients = reader.u32()?;
+        let mut matrices = [vec![vec![]; domain_size], vec![vec![]; domain_size]];
+        let mut n_rows = 0;
+        for _ in 0..n_coefficients {
+            let matrix = reader.u32()? as usize;
+            let constraint = reader.u32()? as usize;
+            let signal = reader.u32()? as usize;
+            let value = Self::coefficient(reader)?;
+            if matrix > 1 || constraint >= domain_size || signal >= n_vars {
+                return Err("coefficient out of the constraint system");
+            }
+            matrices[matrix][constraint].push((value, signal));
+            n_rows = n_rows.max(constraint + 1);
+        }
+        let num_constraints = n_rows
+            .checked_sub(n_public + 1)
+            .ok_or("missing the public signal constraints")?;
+        let [mut a, mut b] = matrices;
+        a.truncate(num_constraints);
+        b.truncate(num_constraints);
+        Ok(ConstraintMatrices {
+            num_instance_variables: n_public + 1,
+            num_witness_variables: n_vars - n_public - 1,
+            num_constraints,
+            a_num_non_zero: a.iter().map(Vec::len).sum(),
+            b_num_non_zero: b.iter().map(Vec::len).sum(),
+            c_num_non_zero: 0,
+            a,
+            b,
+            c: vec![],
+        })
+    }
+
+    fn g1_points(reader: &mut ByteReader, amount: usize) -> Result<Vec<G1Affine>, &'static str> {
+        (0..amount).map(|_| Self::g1(reader)).collect()
+    }
+
+    fn g2_points(reader: &mut ByteReader, amount: usize) -> Result<Vec<G2Affine>, &'static str> {
+        (0..amount).map(|_| Self::g2(reader)).collect()
+    }
+
+    // Points are stored as affine coordinates in Montgomery form, (0, 0) being the infinity.
+    fn g1(reader: &mut ByteReader) -> Result<G1Affine, &'static str> {
+        let x = Self::base_field_element(reader)?;
+        let y = Self::base_field_element(reader)?;
+        if x.is_zero() && y.is_zero() {
+            return Ok(G1Affine::zero());
+        }
+        Ok(G1Affine::new_unchecked(x, y))
+    }
+
+    fn g2(reader: &mut ByteReader) -> Result<G2Affine, &'static str> {
+        let x = Fq2::new(
+            Self::base_field_element(reader)?,
+            Self::base_field_element(reader)?,
+        );
+        let y = Fq2::new(
+            Self::base_field_element(reader)?,
+            Self::base_field_element(reader)?,
+        );
+        if x.is_zero() && y.is_zero() {
+            return Ok(G2Affine::zero());
+        }
+        Ok(G2Affine::new_unchecked(x, y))
+    }
+
+    fn base_field_element(reader: &mut ByteReader) -> Result<Fq, &'static str> {
+        let montgomery_form = BigInt(Self::limbs(reader.take(Self::BASE_FIELD_BYTES)?));
+        if montgomery_form >= Fq::MODULUS {
+            return Err("coordinate exceeds the base field");
+        }
+        Ok(Fq::new_unchecked(montgomery_form))
+    }
+
+    // Coefficients are stored multiplied by R^2 instead of R, so the Montgomery reduction is
+    // applied twice.
+    fn coefficient(reader: &mut ByteReader) -> Result<Fr, &'static str> {
+        let stored = BigInt(Self::limbs(reader.take(Self::SCALAR_FIELD_BYTES)?));
+        if stored >= Fr::MODULUS {
+            return Err("coefficient exceeds the scalar field");
+        }
+        Ok(Fr::new_unchecked(Fr::new_unchecked(stored).into_bigint()))
+    }
+
+    fn limbs<const N: usize>(bytes: &[u8]) -> [u64; N] {
+        let mut limbs = [0u64; N];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        limbs
+    }
+
+    // private - proving
+
+    fn random_scalar() -> Fr {
+        let bytes = [rand::random::<[u8; 32]>(), rand::random::<[u8; 32]>()].concat();
+        Fr::from_le_bytes_mod_order(&bytes)
+    }
+}
+
+// The witness map of snarkjs. Its H query holds the odd powers of the Lagrange basis of a domain
+// twice as large, so H is evaluated there instead of being divided by the vanishing polynomial.
+struct SnarkjsReduction;
+
+impl R1CSToQAP for SnarkjsReduction {
+    #[allow(clippy::type_complexity)]
+    fn instance_map_with_evaluation<F: PrimeField, D: EvaluationDomain<F>>(
+        cs: ConstraintSystemRef<F>,
+        t: &F,
+    ) -> Result<(Vec<F>, Vec<F>, Vec<F>, F, usize, usize), SynthesisError> {
+        LibsnarkReduction::instance_map_with_evaluation::<F, D>(cs, t)
+    }
+
+    fn witness_map_from_matrices<F: PrimeField, D: EvaluationDomain<F>>(
+        matrices: &ConstraintMatrices<F>,
+        num_inputs: usize,
+        num_constraints: usize,
+        full_assignment: &[F],
+    ) -> Result<Vec<F>, SynthesisError> {
+        let domain =
+            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let domain_size = domain.size();
+
+        let mut a = vec![F::zero(); domain_size];
+        let mut b = vec![F::zero(); domain_size];
+        a[..num_constraints]
+            .par_iter_mut()
+            .zip(b[..num_constraints].par_iter_mut())
+            .zip(matrices.a.par_iter().zip(matrices.b.par_iter()))
+            .for_each(|((a, b), (a_row, b_row))| {
+                *a = evaluate_constraint(a_row, full_assignment);
+                *b = evaluate_constraint(b_row, full_assignment);
+            });
+        a[num_constraints..num_constraints + num_inputs]
+            .clone_from_slice(&full_assignment[..num_inputs]);
+        // B is zero past the constraints, and so is C.
+        let mut c: Vec<F> = a
+            .par_iter()
+            .zip(b.par_iter())
+            .map(|(a, b)| *a * b)
+            .collect();
+
+        let coset_generator = D::new(2 * domain_size)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?
+            .element(1);
+        for evaluations in [&mut a, &mut b, &mut c] {
+            domain.ifft_in_place(evaluations);
+            D::distribute_powers_and_mul_by_const(evaluations, coset_generator, F::one());
+            domain.fft_in_place(evaluations);
+        }
+
+        Ok(a.into_par_iter()
+            .zip(b)
+            .zip(c)
+            .map(|((a, b), c)| a * b - c)
+            .collect())
+    }
+
+    fn h_query_scalars<F: PrimeField, D: EvaluationDomain<F>>(
+        max_power: usize,
+        t: F,
+        _zt: F,
+        delta_inverse: F,
+    ) -> Result<Vec<F>, SynthesisError> {
+        let mut scalars: Vec<F> = (0..2 * max_power + 1)
+            .map(|power| delta_inverse * t.pow([power as u64]))
+            .collect();
+        let domain = D::new(scalars.len()).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        domain.ifft_in_place(&mut scalars);
+        Ok(scalars.into_iter().skip(1).step_by(2).collect())
+    }
+}
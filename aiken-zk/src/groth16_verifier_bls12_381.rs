@@ -0,0 +1,268 @@
+use crate::point_compressor_bls12_381::{PointCompressionError, PointCompressorBls12_381};
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, VerifyingKey, prepare_verifying_key};
+use num_bigint::BigUint;
+use serde_json::Value;
+use std::str::FromStr;
+use std::{fs, io};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum VerificationError {
+    #[error("couldn't read {path}: {source}")]
+    Io { path: String, source: io::Error },
+    #[error("{path} is not valid JSON: {source}")]
+    Json {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[error("the {document} has no '{field}' field")]
+    MissingField {
+        document: &'static str,
+        field: &'static str,
+    },
+    #[error("the {document} has an empty '{field}' field")]
+    EmptyField {
+        document: &'static str,
+        field: &'static str,
+    },
+    #[error("invalid '{field}' in the {document}: {source}")]
+    InvalidPoint {
+        document: &'static str,
+        field: String,
+        source: PointCompressionError,
+    },
+    #[error("public input '{0}' is not an element of the BLS12-381 scalar field")]
+    InvalidPublicInput(String),
+    #[error("the verification key expects {expected} public inputs, got {actual}")]
+    PublicInputCountMismatch { expected: usize, actual: usize },
+}
+
+// Checks snarkjs Groth16 proofs over BLS12-381. The proof may be either the raw snarkjs
+// proof.json or the compressed JSON written by `prove json`.
+pub struct Groth16VerifierBls12_381 {
+    verifying_key: VerifyingKey<Bls12_381>,
+}
+
+impl Groth16VerifierBls12_381 {
+    const VERIFICATION_KEY: &'static str = "verification key";
+    const PROOF: &'static str = "proof";
+
+    pub fn from_json(verification_key_path: &str) -> Result<Self, VerificationError> {
+        Self::from_snarkjs_verification_key(&Self::read_json(verification_key_path)?)
+    }
+
+    pub fn from_snarkjs_verification_key(
+        verification_key: &Value,
+    ) -> Result<Self, VerificationError> {
+        let gamma_abc_g1 = Self::field(verification_key, Self::VERIFICATION_KEY, "IC")?
+            .as_array()
+            .ok_or(VerificationError::MissingField {
+                document: Self::VERIFICATION_KEY,
+                field: "IC",
+            })?
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                PointCompressorBls12_381::g1_from_snarkjs(point).map_err(|source| {
+                    VerificationError::InvalidPoint {
+                        document: Self::VERIFICATION_KEY,
+                        field: format!("IC[{}]", index),
+                        source,
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // IC has a point for the constant one and one for each public input.
+        if gamma_abc_g1.is_empty() {
+            return Err(VerificationError::EmptyField {
+                document: Self::VERIFICATION_KEY,
+                field: "IC",
+            });
+        }
+
+        let verifying_key = VerifyingKey {
+            alpha_g1: Self::point_field(
+                verification_key,
+                "vk_alpha_1",
+                PointCompressorBls12_381::g1_from_snarkjs,
+            )?,
+            beta_g2: Self::point_field(
+                verification_key,
+                "vk_beta_2",
+                PointCompressorBls12_381::g2_from_snarkjs,
+            )?,
+            gamma_g2: Self::point_field(
+                verification_key,
+                "vk_gamma_2",
+                PointCompressorBls12_381::g2_from_snarkjs,
+            )?,
+            delta_g2: Self::point_field(
+                verification_key,
+                "vk_delta_2",
+                PointCompressorBls12_381::g2_from_snarkjs,
+            )?,
+            gamma_abc_g1,
+        };
+        Ok(Self { verifying_key })
+    }
+
+    pub fn verify_files(
+        &self,
+        public_inputs_path: &str,
+        proof_path: &str,
+    ) -> Result<bool, VerificationError> {
+        let public_inputs = Self::read_json(public_inputs_path)?;
+        let proof = Self::read_json(proof_path)?;
+        self.verify(&public_inputs, &proof)
+    }
+
+    pub fn verify(&self, public_inputs: &Value, proof: &Value) -> Result<bool, VerificationError> {
+        let public_inputs = Self::public_inputs(public_inputs)?;
+        let expected = self.verifying_key.gamma_abc_g1.len() - 1;
+        if public_inputs.len() != expected {
+            return Err(VerificationError::PublicInputCountMismatch {
+                expected,
+                actual: public_inputs.len(),
+            });
+        }
+
+        let proof = Self::proof(proof)?;
+        let prepared_verifying_key = prepare_verifying_key(&self.verifying_key);
+        Ok(
+            Groth16::<Bls12_381>::verify_proof(&prepared_verifying_key, &proof, &public_inputs)
+                .expect("the amount of public inputs was already checked"),
+        )
+    }
+
+    fn proof(proof: &Value) -> Result<Proof<Bls12_381>, VerificationError> {
+        if proof.get("piA").is_some() {
+            Ok(Proof {
+                a: Self::compressed_proof_field(
+                    proof,
+                    "piA",
+                    PointCompressorBls12_381::decompress_g1,
+                )?,
+                b: Self::compressed_proof_field(
+                    proof,
+                    "piB",
+                    PointCompressorBls12_381::decompress_g2,
+                )?,
+                c: Self::compressed_proof_field(
+                    proof,
+                    "piC",
+                    PointCompressorBls12_381::decompress_g1,
+                )?,
+            })
+        } else {
+            Ok(Proof {
+                a: Self::snarkjs_proof_field(
+                    proof,
+                    "pi_a",
+                    PointCompressorBls12_381::g1_from_snarkjs,
+                )?,
+                b: Self::snarkjs_proof_field(
+                    proof,
+                    "pi_b",
+                    PointCompressorBls12_381::g2_from_snarkjs,
+                )?,
+                c: Self::snarkjs_proof_field(
+                    proof,
+                    "pi_c",
+                    PointCompressorBls12_381::g1_from_snarkjs,
+                )?,
+            })
+        }
+    }
+
+    fn public_inputs(public_inputs: &Value) -> Result<Vec<Fr>, VerificationError> {
+        public_inputs
+            .as_array()
+            .ok_or_else(|| VerificationError::InvalidPublicInput(public_inputs.to_string()))?
+            .iter()
+            .map(Self::scalar)
+            .collect()
+    }
+
+    fn scalar(public_input: &Value) -> Result<Fr, VerificationError> {
+        let invalid = || VerificationError::InvalidPublicInput(public_input.to_string());
+        let value =
+            BigUint::from_str(public_input.as_str().ok_or_else(invalid)?).map_err(|_| invalid())?;
+        if value >= BigUint::from(Fr::MODULUS) {
+            return Err(invalid());
+        }
+        Ok(Fr::from(value))
+    }
+
+    fn point_field<Point>(
+        verification_key: &Value,
+        field: &'static str,
+        parse: fn(&Value) -> Result<Point, PointCompressionError>,
+    ) -> Result<Point, VerificationError> {
+        parse(Self::field(
+            verification_key,
+            Self::VERIFICATION_KEY,
+            field,
+        )?)
+        .map_err(|source| VerificationError::InvalidPoint {
+            document: Self::VERIFICATION_KEY,
+            field: field.to_string(),
+            source,
+        })
+    }
+
+    fn snarkjs_proof_field<Point>(
+        proof: &Value,
+        field: &'static str,
+        parse: fn(&Value) -> Result<Point, PointCompressionError>,
+    ) -> Result<Point, VerificationError> {
+        parse(Self::field(proof, Self::PROOF, field)?)
+            .map_err(|source| Self::invalid_proof_point(field, source))
+    }
+
+    fn compressed_proof_field<Point>(
+        proof: &Value,
+        field: &'static str,
+        decompress: fn(&str) -> Result<Point, PointCompressionError>,
+    ) -> Result<Point, VerificationError> {
+        let compressed_point = Self::field(proof, Self::PROOF, field)?.as_str().ok_or(
+            VerificationError::MissingField {
+                document: Self::PROOF,
+                field,
+            },
+        )?;
+        decompress(compressed_point).map_err(|source| Self::invalid_proof_point(field, source))
+    }
+
+    fn invalid_proof_point(
+        field: &'static str,
+        source: PointCompressionError,
+    ) -> VerificationError {
+        VerificationError::InvalidPoint {
+            document: Self::PROOF,
+            field: field.to_string(),
+            source,
+        }
+    }
+
+    fn field<'a>(
+        json: &'a Value,
+        document: &'static str,
+        field: &'static str,
+    ) -> Result<&'a Value, VerificationError> {
+        json.get(field)
+            .ok_or(VerificationError::MissingField { document, field })
+    }
+
+    fn read_json(path: &str) -> Result<Value, VerificationError> {
+        let contents = fs::read_to_string(path).map_err(|source| VerificationError::Io {
+            path: path.to_string(),
+            source,
+        })?;
+        serde_json::from_str(&contents).map_err(|source| VerificationError::Json {
+            path: path.to_string(),
+            source,
+        })
+    }
+}
@@ -0,0 +1,173 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::circom_circuit::CircomCircuit;
+use crate::compiler::build_options::BuildOptions;
+use crate::compiler::token_zk::TokenZK;
+use crate::component_creator::ComponentCreator;
+use crate::r1cs_header::{R1csError, R1csHeader};
+use crate::zk_examples::{InputVisibility, ZkExample};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::fs;
+
+// An input signal of the main component. Scalars have no dimensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitSignal {
+    pub name: String,
+    pub dimensions: Vec<usize>,
+    pub visibility: InputVisibility,
+}
+
+impl CircuitSignal {
+    fn placeholder(&self) -> Value {
+        let marker = match self.visibility {
+            InputVisibility::Public => InputsTemplate::PUBLIC_MARKER,
+            InputVisibility::Private => InputsTemplate::PRIVATE_MARKER,
+        };
+        self.dimensions
+            .iter()
+            .rev()
+            .fold(Value::from(marker), |element, length| {
+                Value::Array(vec![element; *length])
+            })
+    }
+}
+
+// Skeleton of the inputs json expected by the witness generator: every value is a marker telling
+// whether it will be public, to be replaced by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputsTemplate {
+    signals: Vec<CircuitSignal>,
+}
+
+impl InputsTemplate {
+    pub const PUBLIC_MARKER: &'static str = "<public>";
+    pub const PRIVATE_MARKER: &'static str = "<private>";
+    const MAIN_COMPONENT_PREFIX: &'static str = "main.";
+
+    pub fn new(signals: Vec<CircuitSignal>) -> Self {
+        Self { signals }
+    }
+
+    // Built-in functions know their signals; custom circuits are compiled to find them out.
+    pub fn for_offchain(
+        offchain_token: &TokenZK,
+        build_options: &BuildOptions,
+    ) -> Result<Self, AikenZkError> {
+        match offchain_token {
+            TokenZK::Offchain {
+                example: ZkExample::CustomCircom { path, .. },
+            } => {
+                let circuit = CircomCircuit::from(
+                    build_options.in_project(path).to_string_lossy().to_string(),
+                )?;
+                let (r1cs_path, symbols_path) = circuit.compile_with_symbols(build_options)?;
+                Ok(Self::from_circuit_files(&r1cs_path, &symbols_path)?)
+            }
+            _ => Ok(Self::new(
+                ComponentCreator::from_token(offchain_token.clone()).signals()?,
+            )),
+        }
+    }
+
+    pub fn from_circuit_files(r1cs_path: &str, symbols_path: &str) -> Result<Self, R1csError> {
+        let r1cs_header = R1csHeader::from_file(r1cs_path)?;
+        let symbols = fs::read_to_string(symbols_path).map_err(|source| R1csError::Io {
+            path: symbols_path.to_string(),
+            source,
+        })?;
+        Self::from_symbols(&r1cs_header, &symbols).map_err(|reason| R1csError::MalformedSymbols {
+            path: symbols_path.to_string(),
+            reason,
+        })
+    }
+
+    // Wires are laid out as the constant one, the outputs, the public inputs and then the private
+    // inputs, so the header tells which symbols are inputs of the main component.
+    pub fn from_symbols(r1cs_header: &R1csHeader, symbols: &str) -> Result<Self, &'static str> {
+        let first_input_wire = 1 + i64::from(r1cs_header.n_public_outputs);
+        let first_private_wire = first_input_wire + i64::from(r1cs_header.n_public_inputs);
+        let end_of_inputs = first_private_wire + i64::from(r1cs_header.n_private_inputs);
+
+        let mut signals: Vec<CircuitSignal> = vec![];
+        for line in symbols.lines().filter(|line| !line.trim().is_empty()) {
+            let mut fields = line.splitn(4, ',');
+            let _label = fields.next();
+            let wire: i64 = fields
+                .next()
+                .and_then(|wire| wire.parse().ok())
+                .ok_or("wire index is not a number")?;
+            let name = fields.nth(1).ok_or("missing symbol name")?;
+            if wire < first_input_wire || wire >= end_of_inputs {
+                continue;
+            }
+            let Some(signal) = name.strip_prefix(Self::MAIN_COMPONENT_PREFIX) else {
+                return Err("input outside of the main component");
+            };
+            let visibility = if wire < first_private_wire {
+                InputVisibility::Public
+            } else {
+                InputVisibility::Private
+            };
+            Self::add_symbol(&mut signals, signal, visibility)?;
+        }
+        Ok(Self::new(signals))
+    }
+
+    pub fn signals(&self) -> &[CircuitSignal] {
+        &self.signals
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing an inputs template cannot fail")
+    }
+
+    // `in[1][0]` belongs to the `in` signal and makes its dimensions at least [2, 1].
+    fn add_symbol(
+        signals: &mut Vec<CircuitSignal>,
+        symbol: &str,
+        visibility: InputVisibility,
+    ) -> Result<(), &'static str> {
+        let (name, indices) = symbol.split_once('[').unwrap_or((symbol, ""));
+        let indices = if indices.is_empty() {
+            vec![]
+        } else {
+            indices
+                .trim_end_matches(']')
+                .split("][")
+                .map(|index| index.parse::<usize>().map_err(|_| "index is not a number"))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let position = match signals.iter().position(|signal| signal.name == name) {
+            Some(position) => position,
+            None => {
+                signals.push(CircuitSignal {
+                    name: name.to_string(),
+                    dimensions: vec![0; indices.len()],
+                    visibility,
+                });
+                signals.len() - 1
+            }
+        };
+        let dimensions = &mut signals[position].dimensions;
+        if dimensions.len() != indices.len() {
+            return Err("signal used with different dimensions");
+        }
+        for (dimension, index) in dimensions.iter_mut().zip(indices) {
+            *dimension = (*dimension).max(index + 1);
+        }
+        Ok(())
+    }
+}
+
+// Serialized by hand to keep the signals in circuit order.
+impl Serialize for InputsTemplate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.signals.len()))?;
+        for signal in &self.signals {
+            map.serialize_entry(&signal.name, &signal.placeholder())?;
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,211 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::inputs_template::{CircuitSignal, InputsTemplate};
+use crate::r1cs_header::{R1csError, R1csHeader};
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use miette::Diagnostic;
+use num_bigint::{BigInt, BigUint, Sign};
+use serde_json::{Map, Value};
+use std::fs;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Diagnostic)]
+pub enum InputsError {
+    #[error("the inputs are not a json object: {reason}")]
+    NotAnObject { reason: String },
+    #[error("{signal} is missing")]
+    MissingSignal { signal: String },
+    #[error("{signal} is not an input of the circuit")]
+    UnknownSignal { signal: String },
+    #[error("{signal} expected {expected} elements, got {actual}")]
+    WrongLength {
+        signal: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("{signal} expected a list of {expected} elements")]
+    ExpectedList { signal: String, expected: usize },
+    #[error("{signal} expected a single value, got a list")]
+    ExpectedSingleValue { signal: String },
+    #[error("{signal} is not a number: {value}")]
+    NotANumber { signal: String, value: String },
+    #[error("{signal} value exceeds BLS12-381 scalar field: {value}")]
+    OutOfField { signal: String, value: String },
+}
+
+// Checks an inputs json against the input signals of a circuit, so mistakes are reported before
+// the witness generator fails with an assertion.
+pub struct InputsValidator {
+    signals: Vec<CircuitSignal>,
+    field_modulus: BigUint,
+}
+
+impl InputsValidator {
+    pub fn new(signals: Vec<CircuitSignal>, field_modulus: BigUint) -> Self {
+        Self {
+            signals,
+            field_modulus,
+        }
+    }
+
+    pub fn from_circuit_files(r1cs_path: &str, symbols_path: &str) -> Result<Self, R1csError> {
+        let field_modulus = R1csHeader::from_file(r1cs_path)?.prime;
+        let inputs_template = InputsTemplate::from_circuit_files(r1cs_path, symbols_path)?;
+        Ok(Self::new(inputs_template.signals().to_vec(), field_modulus))
+    }
+
+    pub fn validate_file(&self, inputs_path: &str) -> Result<(), AikenZkError> {
+        self.ensure_valid(&Self::read_inputs(inputs_path)?, inputs_path)
+    }
+
+    // Inputs that are not json are reported like any other invalid inputs.
+    pub fn read_inputs(inputs_path: &str) -> Result<Value, AikenZkError> {
+        let inputs = fs::read_to_string(inputs_path)
+            .map_err(|error| AikenZkError::io(inputs_path, error))?;
+        serde_json::from_str::<Value>(&inputs).map_err(|error| AikenZkError::InvalidInputs {
+            path: inputs_path.to_string(),
+            errors: vec![InputsError::NotAnObject {
+                reason: error.to_string(),
+            }],
+        })
+    }
+
+    // `inputs_name` is how the inputs are referred to in the error, usually their path.
+    pub fn ensure_valid(&self, inputs: &Value, inputs_name: &str) -> Result<(), AikenZkError> {
+        let errors = self.validate(inputs);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AikenZkError::InvalidInputs {
+                path: inputs_name.to_string(),
+                errors,
+            })
+        }
+    }
+
+    pub fn validate(&self, inputs: &Value) -> Vec<InputsError> {
+        let Value::Object(inputs) = inputs else {
+            return vec![InputsError::NotAnObject {
+                reason: format!("found {}", inputs),
+            }];
+        };
+        let mut errors = vec![];
+        for signal in &self.signals {
+            match inputs.get(&signal.name) {
+                Some(value) => {
+                    self.validate_value(&signal.name, &signal.dimensions, value, &mut errors)
+                }
+                None => errors.push(InputsError::MissingSignal {
+                    signal: signal.name.clone(),
+                }),
+            }
+        }
+        errors.extend(self.unknown_signals(inputs));
+        errors
+    }
+
+    fn unknown_signals(&self, inputs: &Map<String, Value>) -> Vec<InputsError> {
+        inputs
+            .keys()
+            .filter(|key| !self.signals.iter().any(|signal| &signal.name == *key))
+            .map(|key| InputsError::UnknownSignal {
+                signal: key.clone(),
+            })
+            .collect()
+    }
+
+    // Nested lists are checked level by level: `m[1]` names the second row of `m`.
+    fn validate_value(
+        &self,
+        signal: &str,
+        dimensions: &[usize],
+        value: &Value,
+        errors: &mut Vec<InputsError>,
+    ) {
+        match (dimensions.split_first(), value) {
+            (None, Value::Array(_)) => errors.push(InputsError::ExpectedSingleValue {
+                signal: signal.to_string(),
+            }),
+            (None, value) => {
+                if let Err(error) = self.validate_field_element(signal, value) {
+                    errors.push(error);
+                }
+            }
+            (Some((&expected, _)), Value::Array(elements)) if elements.len() != expected => errors
+                .push(InputsError::WrongLength {
+                    signal: signal.to_string(),
+                    expected,
+                    actual: elements.len(),
+                }),
+            (Some((_, inner_dimensions)), Value::Array(elements)) => {
+                for (index, element) in elements.iter().enumerate() {
+                    let element_signal = format!("{}[{}]", signal, index);
+                    self.validate_value(&element_signal, inner_dimensions, element, errors);
+                }
+            }
+            (Some((&expected, _)), _) => errors.push(InputsError::ExpectedList {
+                signal: signal.to_string(),
+                expected,
+            }),
+        }
+    }
+
+    // The witness generator reduces values modulo the field, which would silently prove
+    // something else: only values in (-p, p) are accepted.
+    fn validate_field_element(&self, signal: &str, value: &Value) -> Result<(), InputsError> {
+        let not_a_number = || InputsError::NotANumber {
+            signal: signal.to_string(),
+            value: value.to_string(),
+        };
+        let number = match value {
+            Value::Number(number) => {
+                BigInt::from_str(&number.to_string()).map_err(|_| not_a_number())?
+            }
+            Value::String(text) => Self::parse_integer(text).ok_or_else(not_a_number)?,
+            _ => return Err(not_a_number()),
+        };
+        if number.magnitude() >= &self.field_modulus {
+            return Err(InputsError::OutOfField {
+                signal: signal.to_string(),
+                value: value.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    // Decimal or `0x` hexadecimal, optionally negative, as accepted by the witness generator.
+    pub fn parse_integer(text: &str) -> Option<BigInt> {
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => (Sign::Minus, digits),
+            None => (Sign::Plus, text),
+        };
+        let magnitude = match digits.strip_prefix("0x") {
+            Some(hex_digits) => BigUint::parse_bytes(hex_digits.as_bytes(), 16),
+            None => BigUint::parse_bytes(digits.as_bytes(), 10),
+        }?;
+        Some(BigInt::from_biguint(sign, magnitude))
+    }
+
+    // A BLS12-381 scalar, where negative values and values over the field are rejected instead
+    // of being reduced.
+    pub fn parse_field_element(text: &str) -> Option<Fr> {
+        Self::parse_integer(text)
+            .filter(|number| number.sign() != Sign::Minus)
+            .and_then(|number| number.to_biguint())
+            .filter(|number| *number < BigUint::from(Fr::MODULUS))
+            .map(Fr::from)
+    }
+
+    // The inputs json writes numbers quoted or not.
+    pub fn number_text(value: &Value) -> String {
+        match value {
+            Value::String(text) => text.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    pub fn field_element_to_decimal(value: &Fr) -> String {
+        BigUint::from(value.into_bigint()).to_string()
+    }
+}
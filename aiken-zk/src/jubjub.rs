@@ -0,0 +1,80 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, MontFp, One, Zero};
+use num_bigint::BigUint;
+
+// A point of Jubjub, the twisted Edwards curve -x^2 + y^2 = 1 + d * x^2 * y^2 over the BLS12-381
+// scalar field, as the eddsa template computes it. Its coordinates are field elements of the
+// circuits, so its arithmetic takes a few constraints per addition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JubjubPoint {
+    pub x: Fr,
+    pub y: Fr,
+}
+
+impl JubjubPoint {
+    pub const COFACTOR: u64 = 8;
+    const SUBGROUP_ORDER: &'static str =
+        "6554484396890773809930967563523245729705921265872317281365359162392183254199";
+    const GENERATOR: Self = Self {
+        x: MontFp!("8076246640662884909881801758704306714034609987455869804520522091855516602923"),
+        y: MontFp!("13262374693698910701929044844600465831413122818447359594527400194675274060458"),
+    };
+
+    pub fn new(x: Fr, y: Fr) -> Option<Self> {
+        Some(Self { x, y }).filter(Self::is_on_curve)
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            x: Fr::zero(),
+            y: Fr::one(),
+        }
+    }
+
+    // Generates the subgroup of prime order, where the signatures live.
+    pub fn generator() -> Self {
+        Self::GENERATOR
+    }
+
+    pub fn subgroup_order() -> BigUint {
+        Self::SUBGROUP_ORDER.parse().expect("a decimal number")
+    }
+
+    // d = -(10240/10241)
+    pub fn d() -> Fr {
+        -Fr::from(10240u64) / Fr::from(10241u64)
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let (x2, y2) = (self.x.square(), self.y.square());
+        -x2 + y2 == Fr::one() + Self::d() * x2 * y2
+    }
+
+    // The formulas are complete: they hold for the identity and for doubling too.
+    pub fn add(&self, other: &Self) -> Self {
+        let t = Self::d() * self.x * other.x * self.y * other.y;
+        Self {
+            x: (self.x * other.y + self.y * other.x) / (Fr::one() + t),
+            y: (self.y * other.y + self.x * other.x) / (Fr::one() - t),
+        }
+    }
+
+    pub fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    pub fn mul(&self, scalar: &BigUint) -> Self {
+        (0..scalar.bits()).rev().fold(Self::identity(), |acc, bit| {
+            let doubled = acc.double();
+            if scalar.bit(bit) {
+                doubled.add(self)
+            } else {
+                doubled
+            }
+        })
+    }
+
+    pub fn mul_by_cofactor(&self) -> Self {
+        self.double().double().double()
+    }
+}
@@ -5,17 +5,42 @@ pub mod zk_examples;
 #[cfg(test)]
 mod tests;
 
+pub mod aiken_zk_error;
+pub mod build_manifest;
 pub mod circom_circuit;
+mod circom_templates;
 pub mod cli;
 pub mod compiler;
 pub mod component_creator;
-mod compressed_groth16_proof_bls12_381;
-mod entropy_generator;
-mod presenter;
+pub mod compressed_groth16_proof_bls12_381;
+pub mod compressed_verification_key_bls12_381;
+pub mod eddsa;
+pub mod entropy_generator;
+pub mod groth16_prover_bls12_381;
+pub mod groth16_verifier_bls12_381;
+pub mod inputs_template;
+pub mod inputs_validator;
+pub mod jubjub;
+pub mod membership;
+pub mod merkle_tree;
+mod phase2_ceremony;
+pub mod point_compressor_bls12_381;
+pub mod poseidon_bls12_381;
+pub mod presenter;
+pub mod project;
+pub mod prover;
+pub mod public_signals;
+pub mod r1cs_header;
+pub mod witness_calculator;
+
+pub use aiken_zk_error::AikenZkError;
+pub use compiler::build_options::BuildOptions;
+pub use project::{BuildOutput, BuiltCircuit, Project};
+pub use prover::{Proof, Prover};
 
 fn filename_without_extension_nor_path(path: String) -> Option<String> {
     Path::new(&path)
         .file_stem()
         .and_then(|s| s.to_str())
         .map(String::from)
-}
\ No newline at end of file
+}
@@ -0,0 +1,107 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::inputs_validator::InputsValidator;
+use crate::merkle_tree::MerkleTree;
+use crate::poseidon_bls12_381::PoseidonBls12381;
+use ark_bls12_381::Fr;
+use miette::Diagnostic;
+use serde_json::{Value, json};
+use std::fs;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Diagnostic)]
+pub enum MembershipError {
+    #[error("the identity must be a json object with a secret and a trapdoor: {reason}")]
+    InvalidIdentity { reason: String },
+    #[error("{name} is {value}, which is not a number of the BLS12-381 scalar field")]
+    NotAFieldElement { name: String, value: String },
+    #[error("leaf {index} of the tree is not the commitment of the identity")]
+    NotAMember { index: usize },
+}
+
+// A member of the set of `offchain membership_with_nullifier`: the tree has its commitment as a
+// leaf, and each claim reveals its nullifier for the external nullifier of the claim, which is
+// always the same for the same identity and claim.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipIdentity {
+    pub secret: Fr,
+    pub trapdoor: Fr,
+}
+
+impl MembershipIdentity {
+    // The commitment and the nullifiers hash keys derived from the secret for each of them, so
+    // that no nullifier is a commitment, not even under an external nullifier equal to a trapdoor.
+    pub const COMMITMENT_DOMAIN: u64 = 1;
+    pub const NULLIFIER_DOMAIN: u64 = 2;
+
+    pub fn commitment(&self) -> Fr {
+        PoseidonBls12381::hash_left_right(self.key(Self::COMMITMENT_DOMAIN), self.trapdoor)
+    }
+
+    pub fn nullifier(&self, external_nullifier: Fr) -> Fr {
+        PoseidonBls12381::hash_left_right(self.key(Self::NULLIFIER_DOMAIN), external_nullifier)
+    }
+
+    fn key(&self, domain: u64) -> Fr {
+        PoseidonBls12381::hash_left_right(self.secret, Fr::from(domain))
+    }
+
+    pub fn from_json(identity: &Value) -> Result<Self, MembershipError> {
+        let field = |name: &str| {
+            let value = identity
+                .get(name)
+                .ok_or_else(|| MembershipError::InvalidIdentity {
+                    reason: format!("{} is missing", name),
+                })?;
+            Self::field_element(name, value)
+        };
+        Ok(Self {
+            secret: field("secret")?,
+            trapdoor: field("trapdoor")?,
+        })
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, AikenZkError> {
+        let identity = fs::read_to_string(path).map_err(|error| AikenZkError::io(path, error))?;
+        let identity =
+            serde_json::from_str(&identity).map_err(|error| MembershipError::InvalidIdentity {
+                reason: error.to_string(),
+            })?;
+        Ok(Self::from_json(&identity)?)
+    }
+
+    // Numbers are written like in the inputs file: decimal or hexadecimal, quoted or not.
+    pub fn field_element(name: &str, value: &Value) -> Result<Fr, MembershipError> {
+        let text = InputsValidator::number_text(value);
+        InputsValidator::parse_field_element(&text).ok_or_else(|| {
+            MembershipError::NotAFieldElement {
+                name: name.to_string(),
+                value: text,
+            }
+        })
+    }
+
+    // The inputs of a claim of the member whose commitment is the leaf with the index.
+    pub fn to_inputs_json(
+        &self,
+        tree: &MerkleTree,
+        index: usize,
+        external_nullifier: Fr,
+    ) -> Result<Value, AikenZkError> {
+        let proof = tree.proof(index)?;
+        if proof.leaf != self.commitment() {
+            return Err(MembershipError::NotAMember { index }.into());
+        }
+
+        let merkle_inputs = proof.to_inputs_json();
+        let decimal = InputsValidator::field_element_to_decimal;
+        Ok(json!({
+            "root": merkle_inputs["root"],
+            "nullifier": decimal(&self.nullifier(external_nullifier)),
+            "external_nullifier": decimal(&external_nullifier),
+            "secret": decimal(&self.secret),
+            "trapdoor": decimal(&self.trapdoor),
+            "pathElements": merkle_inputs["pathElements"],
+            "pathIndices": merkle_inputs["pathIndices"],
+        }))
+    }
+}
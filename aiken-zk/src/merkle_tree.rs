@@ -0,0 +1,169 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::inputs_validator::InputsValidator;
+use crate::poseidon_bls12_381::PoseidonBls12381;
+use ark_bls12_381::Fr;
+use miette::Diagnostic;
+use serde_json::{Value, json};
+use std::fs;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Diagnostic)]
+pub enum MerkleTreeError {
+    #[error("the leaves must be a json list of numbers: {reason}")]
+    NotAList { reason: String },
+    #[error("leaf {index} is {value}, which is not a number of the BLS12-381 scalar field")]
+    InvalidLeaf { index: usize, value: String },
+    #[error("there are no leaves")]
+    NoLeaves,
+    #[error("the circuit needs a tree of depth 1 or more")]
+    ZeroDepth,
+    #[error("{leaves} leaves don't fit in a tree of depth {depth}")]
+    TooManyLeaves { leaves: usize, depth: usize },
+    #[error("there is no leaf with index {index}, the tree has {leaves} leaves")]
+    UnknownLeaf { index: usize, leaves: usize },
+}
+
+// A binary Merkle tree hashed with Poseidon over BLS12-381, like the merkle_tree_checker template
+// checks it. The leaves are padded with zeros up to a power of two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    leaf_count: usize,
+    levels: Vec<Vec<Fr>>,
+}
+
+// The inputs of `offchain merkle_tree_checker` proving that the leaf is in the tree. A path index
+// of 1 means the path element is the left node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub leaf: Fr,
+    pub root: Fr,
+    pub path_elements: Vec<Fr>,
+    pub path_indices: Vec<u8>,
+}
+
+impl MerkleTree {
+    // Without a depth, the tree is the shallowest that fits the leaves.
+    pub fn from_leaves(leaves: &[Fr], depth: Option<usize>) -> Result<Self, MerkleTreeError> {
+        if leaves.is_empty() {
+            return Err(MerkleTreeError::NoLeaves);
+        }
+        let depth = depth.unwrap_or_else(|| Self::depth_for(leaves.len()));
+        if depth == 0 {
+            return Err(MerkleTreeError::ZeroDepth);
+        }
+        if leaves.len() > 1usize.checked_shl(depth as u32).unwrap_or(usize::MAX) {
+            return Err(MerkleTreeError::TooManyLeaves {
+                leaves: leaves.len(),
+                depth,
+            });
+        }
+
+        // Only the nodes over some leaf are stored, with the root of a subtree of zeros as sibling.
+        let mut level = leaves.to_vec();
+        let mut levels = vec![];
+        let mut zero_subtree_root = Fr::from(0u64);
+        for _ in 0..depth {
+            if level.len() % 2 == 1 {
+                level.push(zero_subtree_root);
+            }
+            let parents = level
+                .chunks(2)
+                .map(|pair| PoseidonBls12381::hash_left_right(pair[0], pair[1]))
+                .collect();
+            levels.push(level);
+            level = parents;
+            zero_subtree_root =
+                PoseidonBls12381::hash_left_right(zero_subtree_root, zero_subtree_root);
+        }
+        levels.push(level);
+
+        Ok(Self {
+            leaf_count: leaves.len(),
+            levels,
+        })
+    }
+
+    // The leaves as the inputs json writes numbers: decimal or hexadecimal, quoted or not.
+    pub fn leaves_from_json(leaves: &Value) -> Result<Vec<Fr>, MerkleTreeError> {
+        leaves
+            .as_array()
+            .ok_or_else(|| MerkleTreeError::NotAList {
+                reason: format!("found {}", leaves),
+            })?
+            .iter()
+            .enumerate()
+            .map(|(index, leaf)| {
+                let text = InputsValidator::number_text(leaf);
+                InputsValidator::parse_field_element(&text)
+                    .ok_or(MerkleTreeError::InvalidLeaf { index, value: text })
+            })
+            .collect()
+    }
+
+    pub fn leaves_from_file(path: &str) -> Result<Vec<Fr>, AikenZkError> {
+        let leaves = fs::read_to_string(path).map_err(|error| AikenZkError::io(path, error))?;
+        let leaves = serde_json::from_str(&leaves).map_err(|error| MerkleTreeError::NotAList {
+            reason: error.to_string(),
+        })?;
+        Ok(Self::leaves_from_json(&leaves)?)
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn root(&self) -> Fr {
+        self.levels[self.depth()][0]
+    }
+
+    pub fn proof(&self, index: usize) -> Result<MerkleProof, MerkleTreeError> {
+        if index >= self.leaf_count {
+            return Err(MerkleTreeError::UnknownLeaf {
+                index,
+                leaves: self.leaf_count,
+            });
+        }
+
+        let (path_elements, path_indices) = self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| {
+                let node_index = index >> height;
+                (level[node_index ^ 1], (node_index & 1) as u8)
+            })
+            .unzip();
+        Ok(MerkleProof {
+            leaf: self.levels[0][index],
+            root: self.root(),
+            path_elements,
+            path_indices,
+        })
+    }
+
+    fn depth_for(leaf_count: usize) -> usize {
+        (leaf_count.next_power_of_two().trailing_zeros() as usize).max(1)
+    }
+}
+
+impl MerkleProof {
+    // Recomputes the root from the leaf, as the circuit does.
+    pub fn computed_root(&self) -> Fr {
+        self.path_elements.iter().zip(&self.path_indices).fold(
+            self.leaf,
+            |node, (path_element, path_index)| match path_index {
+                0 => PoseidonBls12381::hash_left_right(node, *path_element),
+                _ => PoseidonBls12381::hash_left_right(*path_element, node),
+            },
+        )
+    }
+
+    pub fn to_inputs_json(&self) -> Value {
+        let decimal = InputsValidator::field_element_to_decimal;
+        json!({
+            "leaf": decimal(&self.leaf),
+            "root": decimal(&self.root),
+            "pathElements": self.path_elements.iter().map(decimal).collect::<Vec<_>>(),
+            "pathIndices": self.path_indices.iter().map(|index| index.to_string()).collect::<Vec<_>>(),
+        })
+    }
+}
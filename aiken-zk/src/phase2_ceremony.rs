@@ -0,0 +1,82 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::circom_circuit::CircomCircuit;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Circuit specific (phase 2) operations over snarkjs zkeys. Every contribution and the final beacon
+// take the previous zkey as input, so the final key depends on all of them.
+pub struct Phase2Ceremony;
+
+impl Phase2Ceremony {
+    pub const DEFAULT_BEACON_HASH: &'static str =
+        "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    pub const DEFAULT_BEACON_ITERATIONS: u32 = 10;
+
+    pub fn contribute(
+        input_zkey: &str,
+        output_zkey: &str,
+        name: &str,
+        entropy: &str,
+    ) -> Result<(), AikenZkError> {
+        let label = format!("zkey contribute '{}'", name);
+        let new_entropy = &format!("{}\n", entropy);
+        let mut child = Command::new("snarkjs")
+            .args([
+                "zkey",
+                "contribute",
+                input_zkey,
+                output_zkey,
+                &format!("--name={}", name),
+                "-v",
+            ])
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|error| CircomCircuit::toolchain_unavailable(&label, error))?;
+
+        if let Some(stdin) = child.stdin.as_mut() {
+            stdin
+                .write_all(new_entropy.as_bytes())
+                .map_err(|error| CircomCircuit::toolchain_unavailable(&label, error))?;
+        }
+
+        let status = child
+            .wait()
+            .map_err(|error| CircomCircuit::toolchain_unavailable(&label, error))?;
+        if !status.success() {
+            return Err(AikenZkError::ToolchainFailed {
+                command: label,
+                exit_code: status.code(),
+                output: String::new(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn beacon(
+        input_zkey: &str,
+        output_zkey: &str,
+        beacon_hash: &str,
+        iterations: u32,
+        name: &str,
+    ) -> Result<(), AikenZkError> {
+        CircomCircuit::run_command_or_fail(
+            Command::new("snarkjs").args([
+                "zkey",
+                "beacon",
+                input_zkey,
+                output_zkey,
+                beacon_hash,
+                &iterations.to_string(),
+                &format!("-n={}", name),
+            ]),
+            "zkey beacon",
+        )
+    }
+
+    pub fn export_verification_key(zkey: &str, output_json: &str) -> Result<(), AikenZkError> {
+        CircomCircuit::run_command_or_fail(
+            Command::new("snarkjs").args(["zkey", "export", "verificationkey", zkey, output_json]),
+            "export verification key",
+        )
+    }
+}
@@ -0,0 +1,194 @@
+use ark_bls12_381::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
+use num_bigint::BigUint;
+use serde_json::{Value, json};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PointCompressionError {
+    #[error("malformed {curve} point: {reason}")]
+    MalformedPoint { curve: &'static str, reason: String },
+    #[error("{curve} point is at infinity")]
+    PointAtInfinity { curve: &'static str },
+    #[error("{curve} point is not on the curve")]
+    NotOnCurve { curve: &'static str },
+    #[error("{curve} point is not in the prime order subgroup")]
+    NotInSubgroup { curve: &'static str },
+}
+
+// Compresses the affine points snarkjs writes as decimal coordinates ([x, y, z] for G1 and
+// [[x0, x1], [y0, y1], [z0, z1]] for G2) into the zcash encoding expected by ak-381, and
+// decompresses that encoding back into curve points.
+pub struct PointCompressorBls12_381;
+
+impl PointCompressorBls12_381 {
+    const G1: &'static str = "G1";
+    const G2: &'static str = "G2";
+
+    pub fn compress_g1(point: &Value) -> Result<String, PointCompressionError> {
+        Ok(Self::serialize_compressed(Self::g1_from_snarkjs(point)?))
+    }
+
+    pub fn compress_g2(point: &Value) -> Result<String, PointCompressionError> {
+        Ok(Self::serialize_compressed(Self::g2_from_snarkjs(point)?))
+    }
+
+    pub fn g1_from_snarkjs(point: &Value) -> Result<G1Affine, PointCompressionError> {
+        let coordinates = Self::coordinates(point, Self::G1)?;
+        let [x, y, z] = coordinates
+            .iter()
+            .map(|coordinate| Self::field_element(coordinate, Self::G1))
+            .collect::<Result<Vec<Fq>, _>>()?
+            .try_into()
+            .map_err(|_| Self::malformed(Self::G1, "expected 3 coordinates"))?;
+        Self::assert_affine(z == Fq::from(1u64), z == Fq::from(0u64), Self::G1)?;
+
+        let affine_point = G1Affine::new_unchecked(x, y);
+        Self::assert_in_group(
+            affine_point.is_on_curve(),
+            affine_point.is_in_correct_subgroup_assuming_on_curve(),
+            Self::G1,
+        )?;
+        Ok(affine_point)
+    }
+
+    pub fn g2_from_snarkjs(point: &Value) -> Result<G2Affine, PointCompressionError> {
+        let coordinates = Self::coordinates(point, Self::G2)?;
+        let [x, y, z] = coordinates
+            .iter()
+            .map(Self::quadratic_extension_element)
+            .collect::<Result<Vec<Fq2>, _>>()?
+            .try_into()
+            .map_err(|_| Self::malformed(Self::G2, "expected 3 coordinates"))?;
+        Self::assert_affine(z == Fq2::from(1u64), z == Fq2::from(0u64), Self::G2)?;
+
+        let affine_point = G2Affine::new_unchecked(x, y);
+        Self::assert_in_group(
+            affine_point.is_on_curve(),
+            affine_point.is_in_correct_subgroup_assuming_on_curve(),
+            Self::G2,
+        )?;
+        Ok(affine_point)
+    }
+
+    pub fn g1_to_snarkjs(point: &G1Affine) -> Value {
+        match point.xy() {
+            Some((x, y)) => json!([Self::decimal(x), Self::decimal(y), "1"]),
+            None => json!(["0", "1", "0"]),
+        }
+    }
+
+    pub fn g2_to_snarkjs(point: &G2Affine) -> Value {
+        match point.xy() {
+            Some((x, y)) => json!([
+                [Self::decimal(&x.c0), Self::decimal(&x.c1)],
+                [Self::decimal(&y.c0), Self::decimal(&y.c1)],
+                ["1", "0"]
+            ]),
+            None => json!([["0", "0"], ["1", "0"], ["0", "0"]]),
+        }
+    }
+
+    pub fn decompress_g1(compressed_point: &str) -> Result<G1Affine, PointCompressionError> {
+        Self::deserialize_compressed(compressed_point, Self::G1)
+    }
+
+    pub fn decompress_g2(compressed_point: &str) -> Result<G2Affine, PointCompressionError> {
+        Self::deserialize_compressed(compressed_point, Self::G2)
+    }
+
+    fn coordinates<'a>(
+        point: &'a Value,
+        curve: &'static str,
+    ) -> Result<&'a Vec<Value>, PointCompressionError> {
+        point
+            .as_array()
+            .ok_or_else(|| Self::malformed(curve, "expected a list of coordinates"))
+    }
+
+    fn quadratic_extension_element(coordinate: &Value) -> Result<Fq2, PointCompressionError> {
+        let [c0, c1] = Self::coordinates(coordinate, Self::G2)?
+            .iter()
+            .map(|component| Self::field_element(component, Self::G2))
+            .collect::<Result<Vec<Fq>, _>>()?
+            .try_into()
+            .map_err(|_| Self::malformed(Self::G2, "expected 2 components per coordinate"))?;
+        Ok(Fq2::new(c0, c1))
+    }
+
+    fn field_element(coordinate: &Value, curve: &'static str) -> Result<Fq, PointCompressionError> {
+        let decimal = coordinate
+            .as_str()
+            .ok_or_else(|| Self::malformed(curve, "coordinates must be decimal strings"))?;
+        let value = BigUint::from_str(decimal).map_err(|_| {
+            Self::malformed(curve, &format!("'{}' is not a decimal number", decimal))
+        })?;
+        if value >= BigUint::from(Fq::MODULUS) {
+            return Err(Self::malformed(
+                curve,
+                &format!("'{}' exceeds the base field modulus", decimal),
+            ));
+        }
+        Ok(Fq::from(value))
+    }
+
+    fn decimal(coordinate: &Fq) -> String {
+        BigUint::from(coordinate.into_bigint()).to_string()
+    }
+
+    fn assert_affine(
+        is_affine: bool,
+        is_infinity: bool,
+        curve: &'static str,
+    ) -> Result<(), PointCompressionError> {
+        if is_infinity {
+            Err(PointCompressionError::PointAtInfinity { curve })
+        } else if !is_affine {
+            Err(Self::malformed(curve, "expected affine coordinates"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn assert_in_group(
+        is_on_curve: bool,
+        is_in_subgroup: bool,
+        curve: &'static str,
+    ) -> Result<(), PointCompressionError> {
+        if !is_on_curve {
+            Err(PointCompressionError::NotOnCurve { curve })
+        } else if !is_in_subgroup {
+            Err(PointCompressionError::NotInSubgroup { curve })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn serialize_compressed(point: impl AffineRepr) -> String {
+        let mut bytes = vec![];
+        point
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a vector cannot fail");
+        hex::encode(bytes)
+    }
+
+    fn deserialize_compressed<Point: CanonicalDeserialize>(
+        compressed_point: &str,
+        curve: &'static str,
+    ) -> Result<Point, PointCompressionError> {
+        let bytes = hex::decode(compressed_point)
+            .map_err(|_| Self::malformed(curve, "compressed points must be hexadecimal"))?;
+        Point::deserialize_compressed(bytes.as_slice())
+            .map_err(|error| Self::malformed(curve, &error.to_string()))
+    }
+
+    fn malformed(curve: &'static str, reason: &str) -> PointCompressionError {
+        PointCompressionError::MalformedPoint {
+            curve,
+            reason: reason.to_string(),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, PrimeField};
+use num_bigint::BigUint;
+use std::sync::OnceLock;
+
+// Poseidon over the BLS12-381 scalar field, as the merkle_tree_checker template computes it: width
+// 3, x^5 S-boxes, 8 full and 57 partial rounds. The round constants and the MDS matrix come from
+// the Grain LFSR of the reference parameter generation, so they don't need to be copied here.
+pub struct PoseidonBls12381 {
+    round_constants: Vec<Fr>,
+    mds: [[Fr; Self::WIDTH]; Self::WIDTH],
+}
+
+impl PoseidonBls12381 {
+    pub const WIDTH: usize = 3;
+    const ALPHA: u64 = 5;
+    const FULL_ROUNDS: usize = 8;
+    const PARTIAL_ROUNDS: usize = 57;
+
+    pub fn parameters() -> &'static Self {
+        static PARAMETERS: OnceLock<PoseidonBls12381> = OnceLock::new();
+        PARAMETERS.get_or_init(Self::generate)
+    }
+
+    // The permutation of [0, left, right], keeping the first element.
+    pub fn hash_left_right(left: Fr, right: Fr) -> Fr {
+        Self::parameters().permute([Fr::from(0u64), left, right])[0]
+    }
+
+    pub fn round_constants(&self) -> &[Fr] {
+        &self.round_constants
+    }
+
+    pub fn mds(&self) -> &[[Fr; Self::WIDTH]; Self::WIDTH] {
+        &self.mds
+    }
+
+    pub fn permute(&self, mut state: [Fr; Self::WIDTH]) -> [Fr; Self::WIDTH] {
+        let half_full_rounds = Self::FULL_ROUNDS / 2;
+        for round in 0..Self::FULL_ROUNDS + Self::PARTIAL_ROUNDS {
+            for (element, round_constant) in state
+                .iter_mut()
+                .zip(&self.round_constants[round * Self::WIDTH..])
+            {
+                *element += round_constant;
+            }
+            if round < half_full_rounds || round >= half_full_rounds + Self::PARTIAL_ROUNDS {
+                state
+                    .iter_mut()
+                    .for_each(|element| *element = Self::sbox(*element));
+            } else {
+                state[0] = Self::sbox(state[0]);
+            }
+            state = self.mds.map(|row| {
+                row.iter()
+                    .zip(&state)
+                    .map(|(coefficient, element)| *coefficient * element)
+                    .sum()
+            });
+        }
+        state
+    }
+
+    fn sbox(element: Fr) -> Fr {
+        element.pow([Self::ALPHA])
+    }
+
+    fn generate() -> Self {
+        let mut grain = GrainLfsr::new(Self::FULL_ROUNDS, Self::PARTIAL_ROUNDS);
+        let round_constants = (0..(Self::FULL_ROUNDS + Self::PARTIAL_ROUNDS) * Self::WIDTH)
+            .map(|_| grain.field_element())
+            .collect();
+
+        // A Cauchy matrix, from 2 * WIDTH distinct elements.
+        let cauchy_elements = loop {
+            let elements: Vec<Fr> = (0..2 * Self::WIDTH)
+                .map(|_| grain.reduced_field_element())
+                .collect();
+            if (1..elements.len()).all(|i| !elements[..i].contains(&elements[i])) {
+                break elements;
+            }
+        };
+        let (xs, ys) = cauchy_elements.split_at(Self::WIDTH);
+        let mds = std::array::from_fn(|i| {
+            std::array::from_fn(|j| {
+                (xs[i] + ys[j])
+                    .inverse()
+                    .expect("the Cauchy elements are distinct")
+            })
+        });
+
+        Self {
+            round_constants,
+            mds,
+        }
+    }
+}
+
+struct GrainLfsr {
+    state: [bool; 80],
+}
+
+impl GrainLfsr {
+    const PRIME_FIELD: u64 = 1;
+    const POWER_SBOX: u64 = 0;
+    const DISCARDED_BITS: usize = 160;
+
+    fn new(full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut state = [true; 80];
+        let fields = [
+            (Self::PRIME_FIELD, 2),
+            (Self::POWER_SBOX, 4),
+            (u64::from(Fr::MODULUS_BIT_SIZE), 12),
+            (PoseidonBls12381::WIDTH as u64, 12),
+            (full_rounds as u64, 10),
+            (partial_rounds as u64, 10),
+        ];
+        let mut position = 0;
+        for (value, bits) in fields {
+            for bit in (0..bits).rev() {
+                state[position] = (value >> bit) & 1 == 1;
+                position += 1;
+            }
+        }
+
+        let mut grain = Self { state };
+        for _ in 0..Self::DISCARDED_BITS {
+            grain.shift();
+        }
+        grain
+    }
+
+    fn shift(&mut self) -> bool {
+        let new_bit = [62, 51, 38, 23, 13, 0]
+            .iter()
+            .fold(false, |bit, tap| bit ^ self.state[*tap]);
+        self.state.rotate_left(1);
+        self.state[79] = new_bit;
+        new_bit
+    }
+
+    // Bits come in pairs, and the second one is kept only if the first one is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.shift();
+            let bit = self.shift();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    fn random_bits(&mut self) -> BigUint {
+        (0..Fr::MODULUS_BIT_SIZE).fold(BigUint::from(0u8), |bits, _| {
+            (bits << 1u8) + u8::from(self.next_bit())
+        })
+    }
+
+    fn field_element(&mut self) -> Fr {
+        let modulus = BigUint::from(Fr::MODULUS);
+        loop {
+            let bits = self.random_bits();
+            if bits < modulus {
+                return Fr::from(bits);
+            }
+        }
+    }
+
+    fn reduced_field_element(&mut self) -> Fr {
+        Fr::from(self.random_bits() % BigUint::from(Fr::MODULUS))
+    }
+}
@@ -12,7 +12,8 @@ impl CompressedGroth16ProofBls12_381ToJsonPresenter {
     }
 
     pub fn present(&self) -> String {
-        format!("{{
+        format!(
+            "{{
 \t\"piA\": \"{}\",
 \t\"piB\": \"{}\",
 \t\"piC\": \"{}\"
@@ -0,0 +1,150 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::build_manifest::{BuildManifest, CircuitManifest, EntropySource};
+use crate::compiler::aiken_zk_compiler::AikenZkCompiler;
+use crate::compiler::build_options::BuildOptions;
+use crate::compiler::compiled_circuit::CompiledCircuit;
+use crate::compressed_verification_key_bls12_381::VerificationKeyCompressionError;
+use crate::entropy_generator::{EntropyGenerator, SeedCommitment};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// An offchain statement of the validator with the circuit and verification key built for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltCircuit {
+    pub circuit: CompiledCircuit,
+    pub circom_src: String,
+    pub verification_key: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOutput {
+    pub aiken_src: String,
+    pub circuits: Vec<BuiltCircuit>,
+    pub manifest: BuildManifest,
+}
+
+impl BuildOutput {
+    // The validator and its manifest, where the manifest says they go.
+    pub fn write(&self) -> Result<(), AikenZkError> {
+        let output_path = &self.manifest.output_path;
+        fs::write(output_path, &self.aiken_src)
+            .map_err(|error| AikenZkError::io(output_path, error))?;
+        self.manifest
+            .write(&BuildManifest::path_for_validator(Path::new(output_path)).to_string_lossy())
+    }
+}
+
+// Builds validators with offchain statements. Building writes to disk: the circuits, keys and
+// witness generators go to the build directory under the project root of the build options, where
+// proving needs them. The validator and its manifest are returned, and only `BuildOutput::write`
+// writes them.
+pub struct Project {
+    build_options: BuildOptions,
+    entropy_generator: EntropyGenerator,
+    entropy_source: EntropySource,
+}
+
+impl Project {
+    pub fn new(build_options: BuildOptions) -> Self {
+        Self {
+            build_options,
+            entropy_generator: EntropyGenerator::new(),
+            entropy_source: EntropySource::Random,
+        }
+    }
+
+    // Seeded entropy makes the setup reproducible, see `BuildOptions::with_reproducible_setup`.
+    pub fn with_entropy(
+        self,
+        entropy_generator: EntropyGenerator,
+        entropy_source: EntropySource,
+    ) -> Self {
+        Self {
+            build_options: self
+                .build_options
+                .with_reproducible_setup(entropy_generator.is_seeded()),
+            entropy_generator,
+            entropy_source,
+        }
+    }
+
+    pub fn with_seed(self, seed: &[u8]) -> Self {
+        self.with_entropy(EntropyGenerator::from_seed(seed), EntropySource::Seed)
+    }
+
+    pub fn build_options(&self) -> &BuildOptions {
+        &self.build_options
+    }
+
+    pub fn build(
+        &self,
+        source_path: &Path,
+        source_offchain_aiken: &str,
+        output_path: &Path,
+    ) -> Result<BuildOutput, AikenZkError> {
+        let random_seeds = (
+            self.entropy_generator.generate(),
+            self.entropy_generator.generate(),
+        );
+        let compilation = AikenZkCompiler::compile(
+            source_offchain_aiken.to_string(),
+            &self.build_options,
+            (&random_seeds.0, &random_seeds.1),
+        )?;
+
+        let ptau_path = self.build_options.ptau_path().to_string_lossy().to_string();
+        let manifest = BuildManifest {
+            source_path: self.path_in_project(source_path),
+            source_hash: BuildManifest::hash(source_offchain_aiken.as_bytes()),
+            output_path: self.path_in_project(output_path),
+            output_hash: BuildManifest::hash(compilation.aiken_src.as_bytes()),
+            build_dir: self.build_options.build_dir().to_string_lossy().to_string(),
+            ptau_hash: BuildManifest::hash_file(&ptau_path)?,
+            ptau_path,
+            entropy_source: self.entropy_source.clone(),
+            seed_commitment: self.entropy_generator.seed_commitment(),
+            contribution_seed_commitments: vec![
+                SeedCommitment::for_seed(random_seeds.0.as_bytes()),
+                SeedCommitment::for_seed(random_seeds.1.as_bytes()),
+            ],
+            circuits: compilation
+                .circuits
+                .iter()
+                .map(|circuit| CircuitManifest::for_circuit(circuit, &self.build_options))
+                .collect::<Result<_, _>>()?,
+        };
+        let circuits = compilation
+            .circuits
+            .into_iter()
+            .zip(&manifest.circuits)
+            .map(|(circuit, circuit_manifest)| {
+                Ok(BuiltCircuit {
+                    circom_src: Self::read(&circuit_manifest.circuit_path)?,
+                    verification_key: serde_json::from_str(&Self::read(
+                        &circuit_manifest.verification_key_path,
+                    )?)
+                    .map_err(VerificationKeyCompressionError::Json)?,
+                    circuit,
+                })
+            })
+            .collect::<Result<_, AikenZkError>>()?;
+
+        Ok(BuildOutput {
+            aiken_src: compilation.aiken_src,
+            circuits,
+            manifest,
+        })
+    }
+
+    fn path_in_project(&self, path: &Path) -> String {
+        self.build_options
+            .in_project(path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn read(path: &str) -> Result<String, AikenZkError> {
+        fs::read_to_string(path).map_err(|error| AikenZkError::io(path, error))
+    }
+}
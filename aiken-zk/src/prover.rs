@@ -0,0 +1,140 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::build_manifest::BuildManifest;
+use crate::circom_circuit::CircomCircuit;
+use crate::compiler::BUILD_DIR;
+use crate::compiler::compiled_circuit::PublicInput;
+use crate::compressed_groth16_proof_bls12_381::CompressedGroth16ProofBls12_381;
+use crate::groth16_prover_bls12_381::Groth16ProverBls12_381;
+use crate::inputs_validator::InputsValidator;
+use crate::public_signals::PublicSignals;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// A Groth16 proof in the snarkjs layout, compressed for the validator, together with the public
+// signals it commits to.
+#[derive(Clone)]
+pub struct Proof {
+    pub compressed: CompressedGroth16ProofBls12_381,
+    pub snarkjs_proof: Value,
+    pub public_signals: PublicSignals,
+}
+
+impl Proof {
+    const PROOF_FILENAME: &'static str = "proof.json";
+    const PUBLIC_SIGNALS_FILENAME: &'static str = "public.json";
+
+    // The proof.json and public.json that `verify` and snarkjs read.
+    pub fn write_snarkjs_files(&self, build_dir: &str) -> Result<(), AikenZkError> {
+        let build_dir = Path::new(build_dir);
+        Self::write_json(&build_dir.join(Self::PROOF_FILENAME), &self.snarkjs_proof)?;
+        Self::write_json(
+            &build_dir.join(Self::PUBLIC_SIGNALS_FILENAME),
+            &self.public_signals.to_snarkjs(),
+        )
+    }
+
+    fn write_json(path: &Path, json: &Value) -> Result<(), AikenZkError> {
+        fs::write(path, json.to_string())
+            .map_err(|error| AikenZkError::io(path.to_string_lossy(), error))
+    }
+}
+
+// Proves a circom circuit with the zkey of its setup. The build directory caches the witness
+// generator of the circuit.
+pub struct Prover {
+    circuit: CircomCircuit,
+    zkey_path: String,
+    build_dir: String,
+    public_inputs: Option<Vec<PublicInput>>,
+}
+
+impl Prover {
+    // Relative paths are taken from the project root, whose build directory caches the witness
+    // generator unless another one is given.
+    pub fn for_circuit(
+        project_root: &Path,
+        circom_path: &str,
+        zkey_path: &str,
+    ) -> Result<Self, AikenZkError> {
+        let in_project = |path: &str| project_root.join(path).to_string_lossy().to_string();
+        Ok(Self {
+            circuit: CircomCircuit::from(in_project(circom_path))?,
+            zkey_path: in_project(zkey_path),
+            build_dir: in_project(BUILD_DIR),
+            public_inputs: None,
+        })
+    }
+
+    // The manifest written by the build locates the circuit and zkey, and names the public signals
+    // after the Aiken identifiers.
+    pub fn for_validator(
+        validator_path: &Path,
+        proof_index: Option<usize>,
+    ) -> Result<Self, AikenZkError> {
+        let manifest_path = BuildManifest::path_for_validator(validator_path)
+            .to_string_lossy()
+            .to_string();
+        let manifest = BuildManifest::from_file(&manifest_path)?;
+        let circuit = manifest.circuit(&manifest_path, proof_index)?;
+        circuit.ensure_circuit_is_unchanged(&manifest_path)?;
+
+        Ok(Self {
+            circuit: CircomCircuit::from(circuit.circuit_path.clone())?,
+            zkey_path: circuit.zkey_path.clone(),
+            build_dir: manifest.build_dir.clone(),
+            public_inputs: Some(circuit.public_inputs.clone()),
+        })
+    }
+
+    pub fn with_build_dir(self, build_dir: String) -> Self {
+        Self { build_dir, ..self }
+    }
+
+    pub fn with_public_inputs(self, public_inputs: Vec<PublicInput>) -> Self {
+        Self {
+            public_inputs: Some(public_inputs),
+            ..self
+        }
+    }
+
+    pub fn build_dir(&self) -> &str {
+        &self.build_dir
+    }
+
+    pub fn prove(&self, inputs: &Value) -> Result<Proof, AikenZkError> {
+        self.prove_named(inputs, "the inputs")
+    }
+
+    pub fn prove_file(&self, inputs_path: &str) -> Result<Proof, AikenZkError> {
+        self.prove_named(&InputsValidator::read_inputs(inputs_path)?, inputs_path)
+    }
+
+    fn prove_named(&self, inputs: &Value, inputs_name: &str) -> Result<Proof, AikenZkError> {
+        let (proof, public_signals) = self.circuit.prove_in_build_dir(
+            &self.build_dir,
+            &self.zkey_path,
+            inputs,
+            inputs_name,
+        )?;
+        let snarkjs_proof = Groth16ProverBls12_381::to_snarkjs_proof(&proof);
+        let public_signals = PublicSignals::label(
+            public_signals
+                .iter()
+                .map(|value| BigUint::from(value.into_bigint()).to_string())
+                .collect(),
+            self.circuit
+                .inputs_template_in_build_dir(&self.build_dir)?
+                .signals(),
+            self.public_inputs.as_deref(),
+        );
+
+        Ok(Proof {
+            compressed: CompressedGroth16ProofBls12_381::from_snarkjs_proof(&snarkjs_proof)?,
+            snarkjs_proof,
+            public_signals,
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::compiler::compiled_circuit::{Cardinality, PublicInput};
+use crate::inputs_template::CircuitSignal;
+use crate::inputs_validator::InputsValidator;
+use crate::zk_examples::InputVisibility;
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use miette::Diagnostic;
+use num_bigint::{BigInt, BigUint};
+use serde_json::Value;
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Diagnostic)]
+pub enum PublicSignalError {
+    #[error("there is no public signal named {name}")]
+    Unknown { name: String },
+    #[error("{name} is {actual} in the proof, expected {expected}")]
+    Mismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+// A `name=value` check of a public signal, named after the circuit signal or the Aiken identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expectation {
+    pub name: String,
+    pub value: String,
+}
+
+impl Expectation {
+    pub fn parse(expectation: &str) -> Result<Self, String> {
+        let (name, value) = expectation
+            .split_once('=')
+            .ok_or_else(|| format!("expected name=value, got {}", expectation))?;
+        if InputsValidator::parse_integer(value.trim()).is_none() {
+            return Err(format!("{} is not a number", value.trim()));
+        }
+        Ok(Self {
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelledPublicSignal {
+    pub signal: Option<String>,
+    pub identifier: Option<String>,
+    pub value: String,
+}
+
+impl LabelledPublicSignal {
+    fn is_named(&self, name: &str) -> bool {
+        self.signal.as_deref() == Some(name) || self.identifier.as_deref() == Some(name)
+    }
+}
+
+// The public signals a proof commits to, in the order the verifier receives them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicSignals {
+    signals: Vec<LabelledPublicSignal>,
+}
+
+impl PublicSignals {
+    // Circuit outputs come first and are not inputs: they are only labelled by position. Aiken
+    // identifiers are only known when the validator manifest lists one per public signal.
+    pub fn label(
+        values: Vec<String>,
+        circuit_signals: &[CircuitSignal],
+        public_inputs: Option<&[PublicInput]>,
+    ) -> Self {
+        let public_circuit_signals: Vec<&CircuitSignal> = circuit_signals
+            .iter()
+            .filter(|signal| signal.visibility == InputVisibility::Public)
+            .collect();
+        let public_inputs =
+            public_inputs.filter(|inputs| inputs.len() == public_circuit_signals.len());
+
+        let mut labels: Vec<(Option<String>, Option<String>)> = vec![];
+        for (position, signal) in public_circuit_signals.iter().enumerate() {
+            let public_input = public_inputs.map(|inputs| &inputs[position]);
+            for (element, suffix) in Self::element_suffixes(&signal.dimensions)
+                .into_iter()
+                .enumerate()
+            {
+                let identifier = public_input.map(|input| match input.cardinality {
+                    Cardinality::Single => input.identifier.clone(),
+                    Cardinality::Many => format!("{}[{}]", input.identifier, element),
+                });
+                labels.push((Some(signal.name.clone() + &suffix), identifier));
+            }
+        }
+
+        let unlabelled = values.len().saturating_sub(labels.len());
+        let signals = values
+            .into_iter()
+            .enumerate()
+            .map(|(position, value)| {
+                let (signal, identifier) = position
+                    .checked_sub(unlabelled)
+                    .and_then(|index| labels.get(index).cloned())
+                    .unwrap_or((None, None));
+                LabelledPublicSignal {
+                    signal,
+                    identifier,
+                    value,
+                }
+            })
+            .collect();
+        Self { signals }
+    }
+
+    pub fn signals(&self) -> &[LabelledPublicSignal] {
+        &self.signals
+    }
+
+    // The values alone, as the public.json written by snarkjs.
+    pub fn to_snarkjs(&self) -> Value {
+        self.signals
+            .iter()
+            .map(|signal| Value::String(signal.value.clone()))
+            .collect()
+    }
+
+    pub fn check(&self, expectations: &[Expectation]) -> Result<(), AikenZkError> {
+        let errors: Vec<PublicSignalError> = expectations
+            .iter()
+            .filter_map(|expectation| self.check_expectation(expectation).err())
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AikenZkError::UnexpectedPublicSignals { errors })
+        }
+    }
+
+    fn check_expectation(&self, expectation: &Expectation) -> Result<(), PublicSignalError> {
+        let signal = self
+            .signals
+            .iter()
+            .find(|signal| signal.is_named(&expectation.name))
+            .ok_or_else(|| PublicSignalError::Unknown {
+                name: expectation.name.clone(),
+            })?;
+        let expected = InputsValidator::parse_integer(&expectation.value).map(Self::reduce);
+        let actual = InputsValidator::parse_integer(&signal.value).map(Self::reduce);
+        if expected.is_none() || expected != actual {
+            return Err(PublicSignalError::Mismatch {
+                name: expectation.name.clone(),
+                expected: expectation.value.clone(),
+                actual: signal.value.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    // Negative values stand for their field opposite, as in the witness generator.
+    fn reduce(value: BigInt) -> BigUint {
+        let field_modulus = BigInt::from(BigUint::from(Fr::MODULUS));
+        let reduced = ((value % &field_modulus) + &field_modulus) % &field_modulus;
+        reduced
+            .to_biguint()
+            .expect("a value reduced modulo the field is not negative")
+    }
+
+    // `[2, 2]` is laid out as `[0][0]`, `[0][1]`, `[1][0]`, `[1][1]`; scalars have a single element.
+    fn element_suffixes(dimensions: &[usize]) -> Vec<String> {
+        dimensions
+            .iter()
+            .fold(vec![String::new()], |suffixes, length| {
+                suffixes
+                    .iter()
+                    .flat_map(|suffix| {
+                        (0..*length).map(move |index| format!("{}[{}]", suffix, index))
+                    })
+                    .collect()
+            })
+    }
+}
+
+impl fmt::Display for PublicSignals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Public signals of the proof:")?;
+        for (position, signal) in self.signals.iter().enumerate() {
+            let name = signal.signal.as_deref().unwrap_or("output");
+            match &signal.identifier {
+                Some(identifier) => writeln!(
+                    f,
+                    "  [{}] {} ({}) = {}",
+                    position, name, identifier, signal.value
+                )?,
+                None => writeln!(f, "  [{}] {} = {}", position, name, signal.value)?,
+            }
+        }
+        Ok(())
+    }
+}
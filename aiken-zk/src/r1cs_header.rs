@@ -0,0 +1,127 @@
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum R1csError {
+    #[error("couldn't read {path}: {source}")]
+    Io { path: String, source: io::Error },
+    #[error("{path} is not an r1cs file: {reason}")]
+    Malformed { path: String, reason: &'static str },
+    #[error("{path} is not a circom symbols file: {reason}")]
+    MalformedSymbols { path: String, reason: &'static str },
+}
+
+// Header section of the iden3 binary r1cs format written by circom.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct R1csHeader {
+    #[serde(skip)]
+    pub prime: BigUint,
+    pub n_wires: u32,
+    pub n_public_outputs: u32,
+    pub n_public_inputs: u32,
+    pub n_private_inputs: u32,
+    pub n_labels: u64,
+    pub n_constraints: u32,
+}
+
+impl R1csHeader {
+    const MAGIC: &'static [u8] = b"r1cs";
+    const HEADER_SECTION_TYPE: u32 = 1;
+
+    pub fn from_file(path: &str) -> Result<Self, R1csError> {
+        let bytes = fs::read(path).map_err(|source| R1csError::Io {
+            path: path.to_string(),
+            source,
+        })?;
+        Self::from_bytes(&bytes).map_err(|reason| R1csError::Malformed {
+            path: path.to_string(),
+            reason,
+        })
+    }
+
+    // Circuit outputs are public signals too: snarkjs counts both as nPublic.
+    pub fn n_public(&self) -> u32 {
+        self.n_public_outputs + self.n_public_inputs
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.take(4)? != Self::MAGIC {
+            return Err("wrong magic number");
+        }
+        let _version = reader.u32()?;
+        let n_sections = reader.u32()?;
+        for _ in 0..n_sections {
+            let section_type = reader.u32()?;
+            let section_size = reader.u64()?;
+            if section_type == Self::HEADER_SECTION_TYPE {
+                return Self::read_header_section(&mut reader);
+            }
+            reader.take(usize::try_from(section_size).map_err(|_| "section too large")?)?;
+        }
+        Err("missing header section")
+    }
+
+    fn read_header_section(reader: &mut ByteReader) -> Result<Self, &'static str> {
+        let field_size = reader.u32()? as usize;
+        let prime = BigUint::from_bytes_le(reader.take(field_size)?);
+        Ok(Self {
+            prime,
+            n_wires: reader.u32()?,
+            n_public_outputs: reader.u32()?,
+            n_public_inputs: reader.u32()?,
+            n_private_inputs: reader.u32()?,
+            n_labels: reader.u64()?,
+            n_constraints: reader.u32()?,
+        })
+    }
+}
+
+// Little endian reader of the iden3 binary formats written by circom and snarkjs.
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub fn take(&mut self, length: usize) -> Result<&'a [u8], &'static str> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("unexpected end of file")?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, &'static str> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    // Reads the sections that follow the magic number and version, by type.
+    pub fn sections(&mut self) -> Result<HashMap<u32, &'a [u8]>, &'static str> {
+        let n_sections = self.u32()?;
+        let mut sections = HashMap::new();
+        for _ in 0..n_sections {
+            let section_type = self.u32()?;
+            let section_size = usize::try_from(self.u64()?).map_err(|_| "section too large")?;
+            sections
+                .entry(section_type)
+                .or_insert(self.take(section_size)?);
+        }
+        Ok(sections)
+    }
+}
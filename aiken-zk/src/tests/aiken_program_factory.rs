@@ -95,3 +95,84 @@ pub fn verify_declaration(
         formatted_ic = formatted_ic,
     )
 }
+
+pub fn indexed_verify_declarations(
+    public_input_counts_and_compressed_vks: Vec<(usize, Groth16CompressedData)>,
+) -> String {
+    let indexed_verify_functions: String = public_input_counts_and_compressed_vks
+        .into_iter()
+        .enumerate()
+        .map(|(proof_index, (public_input_count, compressed_vk))| {
+            indexed_verify_function(proof_index, public_input_count, compressed_vk)
+        })
+        .collect();
+
+    format!(
+        r#"
+    type ZKInputType {{
+        Single(Int)
+        Many(List<Int>)
+    }}
+{}"#,
+        indexed_verify_functions
+    )
+}
+
+fn indexed_verify_function(
+    proof_index: usize,
+    public_input_count: usize,
+    compressed_vk: Groth16CompressedData,
+) -> String {
+    let formatted_ic = compressed_vk
+        .IC
+        .iter()
+        .map(|h| format!("                #\"{h}\""))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        r#"
+    fn zk_verify_or_fail_{proof_index}(
+        zk_redeemer: ZK<Redeemer>,
+        public_inputs: List<ZKInputType>
+    ) -> ZK<Redeemer> {{
+
+        let vk: SnarkVerificationKey =
+            SnarkVerificationKey {{
+                nPublic: {},
+                vkAlpha: #"{vkAlpha}",
+                vkBeta: #"{vkBeta}",
+                vkGamma: #"{vkGamma}",
+                vkDelta: #"{vkDelta}",
+                vkAlphaBeta: [],
+                vkIC: [
+{formatted_ic},
+                ],
+            }}
+
+        expect Some(proof) = list.at(zk_redeemer.proofs, {proof_index})
+
+        let flattened_public_inputs: List<Int> = list.flat_map(public_inputs, fn(item) {{
+            when item is {{
+              Single(x) -> [x]
+              Many(xs) -> xs
+            }}
+        }})
+
+        if !groth_verify(vk, proof, flattened_public_inputs) {{
+          fail
+          Void
+        }} else {{
+          Void
+        }}
+
+        zk_redeemer
+    }}"#,
+        public_input_count,
+        vkAlpha = compressed_vk.vk_alpha_1,
+        vkBeta = compressed_vk.vk_beta_2,
+        vkGamma = compressed_vk.vk_gamma_2,
+        vkDelta = compressed_vk.vk_delta_2,
+        formatted_ic = formatted_ic,
+    )
+}
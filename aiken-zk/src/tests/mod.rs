@@ -15,7 +15,27 @@ mod tests_interface;
 
 mod aiken_program_factory;
 mod circom_component_factory;
+mod tests_build_manifest;
+mod tests_build_options;
+mod tests_circom_templates;
+mod tests_eddsa;
+mod tests_groth16_prover;
+mod tests_groth16_verifier;
+mod tests_inputs_template;
+mod tests_inputs_validator;
+mod tests_jubjub;
+mod tests_membership;
+mod tests_merkle_tree;
+mod tests_offchain_block;
+mod tests_point_compression;
+mod tests_poseidon_bls12_381;
 mod tests_presenters;
+mod tests_project;
+mod tests_prover;
+mod tests_public_signals;
+mod tests_r1cs_header;
 mod tests_random_generator;
+mod tests_verification_key_compression;
+mod tests_witness_calculator;
 mod token_examples;
 mod utils;
@@ -1,16 +1,18 @@
+use crate::aiken_zk_error::AikenZkError;
 use crate::compiler::aiken_zk_compiler::{AikenZkCompiler, Groth16CompressedData};
+use crate::compiler::build_options::BuildOptions;
+use crate::compiler::compiled_circuit::{CompiledCircuit, PublicInput};
 use crate::tests::aiken_program_factory::{
-    aiken_template_with_body_and_verify_definition, verify_declaration,
+    aiken_template_with_body_and_verify_definition, indexed_verify_declarations, verify_declaration,
 };
 use crate::tests::circom_component_factory::{
     addition_custom_circom_template_and_component, indexing_custom_circom_template_and_component,
 };
-use crate::tests::utils::create_sandbox_and_set_as_current_directory;
-use serial_test::serial;
+use crate::tests::utils::create_sandbox;
 use std::fs;
+use tempfile::TempDir;
 
 #[test]
-#[serial]
 fn test_replaces_addition_of_public_variables_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain addition(pub a, pub b, pub c)",
@@ -21,7 +23,6 @@ fn test_replaces_addition_of_public_variables_by_the_corresponding_function_and_
 }
 
 #[test]
-#[serial]
 fn test_replaces_addition_of_private_variables_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain addition(priv, priv, priv)",
@@ -32,7 +33,6 @@ fn test_replaces_addition_of_private_variables_by_the_corresponding_function_and
 }
 
 #[test]
-#[serial]
 fn test_replaces_addition_of_mixed_variables_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain addition(priv, b, pub c)",
@@ -43,7 +43,6 @@ fn test_replaces_addition_of_mixed_variables_by_the_corresponding_function_and_c
 }
 
 #[test]
-#[serial]
 fn test_replaces_addition_of_mixed_variables_and_constants_by_the_corresponding_function_and_call()
 {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
@@ -55,7 +54,16 @@ fn test_replaces_addition_of_mixed_variables_and_constants_by_the_corresponding_
 }
 
 #[test]
-#[serial]
+fn test_replaces_addition_of_expressions_by_the_corresponding_function_and_call() {
+    assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
+        "offchain addition(priv, datum.amount, pub list.length(xs))",
+        "zk_verify_or_fail(redeemer, [Single(datum.amount), Single(list.length(xs))])",
+        addition_mixed_visibility_vk_compressed(),
+        2,
+    );
+}
+
+#[test]
 fn test_replaces_subtraction_of_mixed_variables_and_constants_by_the_corresponding_function_and_call()
  {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
@@ -67,7 +75,6 @@ fn test_replaces_subtraction_of_mixed_variables_and_constants_by_the_correspondi
 }
 
 #[test]
-#[serial]
 fn test_replaces_multiplication_of_mixed_variables_and_constants_by_the_corresponding_function_and_call()
  {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
@@ -79,7 +86,6 @@ fn test_replaces_multiplication_of_mixed_variables_and_constants_by_the_correspo
 }
 
 #[test]
-#[serial]
 fn test_replaces_fibonacci_of_mixed_variables_and_constants_by_the_corresponding_function_and_call()
 {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
@@ -91,7 +97,6 @@ fn test_replaces_fibonacci_of_mixed_variables_and_constants_by_the_corresponding
 }
 
 #[test]
-#[serial]
 fn test_replaces_if_of_mixed_variables_and_constants_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain if(a, b, priv, priv)",
@@ -102,7 +107,6 @@ fn test_replaces_if_of_mixed_variables_and_constants_by_the_corresponding_functi
 }
 
 #[test]
-#[serial]
 fn test_replaces_assert_eq_of_mixed_variables_and_constants_by_the_corresponding_function_and_call()
 {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
@@ -114,7 +118,6 @@ fn test_replaces_assert_eq_of_mixed_variables_and_constants_by_the_corresponding
 }
 
 #[test]
-#[serial]
 fn test_replaces_sha256_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain sha256(5, in, out)",
@@ -125,7 +128,6 @@ fn test_replaces_sha256_by_the_corresponding_function_and_call() {
 }
 
 #[test]
-#[serial]
 fn test_replaces_poseidon_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain poseidon(5, in, out)",
@@ -136,7 +138,22 @@ fn test_replaces_poseidon_by_the_corresponding_function_and_call() {
 }
 
 #[test]
-#[serial]
+fn test_a_private_poseidon_input_does_not_make_the_output_a_list() {
+    let temp_dir = create_sandbox();
+    let aiken_src =
+        aiken_template_with_body_and_verify_definition("", "offchain poseidon(5, priv, out)", "");
+
+    let aiken_zk_src = AikenZkCompiler::apply_modifications_to_src_for_token(
+        aiken_src,
+        &BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program"),
+        ("asdasd", "dsadsa"),
+    )
+    .unwrap();
+
+    assert!(aiken_zk_src.contains("zk_verify_or_fail(redeemer, [Single(out)])"));
+}
+
+#[test]
 fn test_replaces_merkle_tree_checker_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain merkle_tree_checker(3, leaf, root, path_elements, path_indices)",
@@ -147,7 +164,6 @@ fn test_replaces_merkle_tree_checker_by_the_corresponding_function_and_call() {
 }
 
 #[test]
-#[serial]
 fn test_replaces_polynomial_evaluations_by_the_corresponding_function_and_call() {
     assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
         "offchain polynomial_evaluations(6, coefficients, 9, pub domain, pub evaluations)",
@@ -158,11 +174,10 @@ fn test_replaces_polynomial_evaluations_by_the_corresponding_function_and_call()
 }
 
 #[test]
-#[serial]
 fn test_replaces_custom_circom_by_the_corresponding_function_and_call() {
-    let _temp_dir = create_sandbox_and_set_as_current_directory();
+    let temp_dir = create_sandbox();
     fs::write(
-        "./test.circom",
+        temp_dir.path().join("test.circom"),
         addition_custom_circom_template_and_component(),
     )
     .unwrap();
@@ -171,14 +186,15 @@ fn test_replaces_custom_circom_by_the_corresponding_function_and_call() {
         "offchain custom(\"test.circom\", [a, 5])",
         "",
     );
-    let output_filename = "my_program".to_string();
+    let build_options = BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program");
     let random_seeds = ("asdasd", "dsadsa");
 
     let aiken_zk_src = AikenZkCompiler::apply_modifications_to_src_for_token(
         aiken_src,
-        output_filename,
+        &build_options,
         random_seeds,
-    );
+    )
+    .unwrap();
 
     let expected_aiken_src = aiken_template_with_body_and_verify_definition(
         import_header(),
@@ -193,11 +209,10 @@ fn test_replaces_custom_circom_by_the_corresponding_function_and_call() {
 }
 
 #[test]
-#[serial]
 fn test_replaces_custom_circom_with_list_variable_by_the_corresponding_function_and_call() {
-    let _temp_dir = create_sandbox_and_set_as_current_directory();
+    let temp_dir = create_sandbox();
     fs::write(
-        "./test.circom",
+        temp_dir.path().join("test.circom"),
         indexing_custom_circom_template_and_component(),
     )
     .unwrap();
@@ -206,14 +221,15 @@ fn test_replaces_custom_circom_with_list_variable_by_the_corresponding_function_
         "offchain custom(\"test.circom\", [@l, val])",
         "",
     );
-    let output_filename = "my_program".to_string();
+    let build_options = BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program");
     let random_seeds = ("asdasd", "dsadsa");
 
     let aiken_zk_src = AikenZkCompiler::apply_modifications_to_src_for_token(
         aiken_src,
-        output_filename,
+        &build_options,
         random_seeds,
-    );
+    )
+    .unwrap();
 
     let expected_aiken_src = aiken_template_with_body_and_verify_definition(
         import_header(),
@@ -227,6 +243,56 @@ fn test_replaces_custom_circom_with_list_variable_by_the_corresponding_function_
     );
 }
 
+#[test]
+fn test_replaces_each_of_several_offchain_statements_by_a_call_using_its_own_proof_index() {
+    let temp_dir = create_sandbox();
+    let aiken_src = aiken_template_with_body_and_verify_definition(
+        "",
+        "offchain addition(pub a, pub b, pub c)\n    offchain assert_eq(priv, b)",
+        "",
+    );
+    let build_options = BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program");
+    let random_seeds = ("asdasd", "dsadsa");
+
+    let aiken_zk_src = AikenZkCompiler::apply_modifications_to_src_for_token(
+        aiken_src,
+        &build_options,
+        random_seeds,
+    )
+    .unwrap();
+
+    let expected_aiken_src = aiken_template_with_body_and_verify_definition(
+        import_header(),
+        "zk_verify_or_fail_0(redeemer, [Single(a), Single(b), Single(c)])\n    zk_verify_or_fail_1(redeemer, [Single(b)])",
+        &indexed_verify_declarations(vec![
+            (3, addition_all_public_vk_compressed()),
+            (
+                1,
+                get_compressed_verification_key_from_assert_eq_circuit_with_mixed_visibility(),
+            ),
+        ]),
+    );
+
+    assert_eq!(
+        without_deltas(expected_aiken_src),
+        without_deltas(aiken_zk_src)
+    );
+    assert!(temp_dir.path().join("build/my_program_0.circom").exists());
+    assert!(temp_dir.path().join("build/my_program_1.circom").exists());
+    assert!(
+        temp_dir
+            .path()
+            .join("build/verification_key_0.zkey")
+            .exists()
+    );
+    assert!(
+        temp_dir
+            .path()
+            .join("build/verification_key_1.zkey")
+            .exists()
+    );
+}
+
 fn indexing_custom_circom_vk_compressed() -> Groth16CompressedData {
     Groth16CompressedData {
         vk_alpha_1: "85e3f8a13a670514351a68677ea0e2fc51150daeea496b85a34d97751695e26b2ae4f1a5a3b60e17bb7bfd6d474154c5".to_string(),
@@ -243,12 +309,46 @@ fn indexing_custom_circom_vk_compressed() -> Groth16CompressedData {
 }
 
 #[test]
-#[serial]
-#[should_panic(expected = "Amount of public inputs doesnt match")]
+fn test_compilation_describes_the_circuit_of_each_offchain_statement() {
+    let temp_dir = create_sandbox();
+    let aiken_src = aiken_template_with_body_and_verify_definition(
+        "",
+        "offchain addition(pub a, pub b, pub c)\n    offchain sha256(256, priv, pub hash)",
+        "",
+    );
+    let build_options = BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program");
+    let random_seeds = ("asdasd", "dsadsa");
+
+    let compilation = AikenZkCompiler::compile(aiken_src, &build_options, random_seeds).unwrap();
+
+    assert_eq!(
+        vec![
+            CompiledCircuit {
+                proof_index: Some(0),
+                circuit_path: in_sandbox(&temp_dir, "build/my_program_0.circom"),
+                verification_key_name: "verification_key_0".to_string(),
+                public_inputs: vec![
+                    PublicInput::single("a".to_string()),
+                    PublicInput::single("b".to_string()),
+                    PublicInput::single("c".to_string()),
+                ],
+            },
+            CompiledCircuit {
+                proof_index: Some(1),
+                circuit_path: in_sandbox(&temp_dir, "build/my_program_1.circom"),
+                verification_key_name: "verification_key_1".to_string(),
+                public_inputs: vec![PublicInput::many("hash".to_string())],
+            },
+        ],
+        compilation.circuits
+    );
+}
+
+#[test]
 fn test_custom_circom_should_fail_if_amount_of_public_inputs_doesnt_match() {
-    let _temp_dir = create_sandbox_and_set_as_current_directory();
+    let temp_dir = create_sandbox();
     fs::write(
-        "./test.circom",
+        temp_dir.path().join("test.circom"),
         addition_custom_circom_template_and_component(),
     )
     .unwrap();
@@ -257,10 +357,44 @@ fn test_custom_circom_should_fail_if_amount_of_public_inputs_doesnt_match() {
         "offchain custom(\"test.circom\", [a, 5, b])",
         "",
     );
-    let output_filename = "my_program".to_string();
+    let build_options = BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program");
+    let random_seeds = ("asdasd", "dsadsa");
+
+    let result = AikenZkCompiler::apply_modifications_to_src_for_token(
+        aiken_src,
+        &build_options,
+        random_seeds,
+    );
+
+    assert!(matches!(
+        result,
+        Err(AikenZkError::PublicInputCountMismatch {
+            expected: 2,
+            actual: 3,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_compiler_fails_when_the_ptau_file_does_not_exist() {
+    let temp_dir = create_sandbox();
+    let aiken_src =
+        aiken_template_with_body_and_verify_definition("", "offchain addition(priv, pub a, 5)", "");
+    let build_options = BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program")
+        .with_ptau_path("missing.ptau".into());
     let random_seeds = ("asdasd", "dsadsa");
 
-    AikenZkCompiler::apply_modifications_to_src_for_token(aiken_src, output_filename, random_seeds);
+    let result = AikenZkCompiler::apply_modifications_to_src_for_token(
+        aiken_src,
+        &build_options,
+        random_seeds,
+    );
+
+    assert!(matches!(
+        result,
+        Err(AikenZkError::Io { path, .. }) if path == in_sandbox(&temp_dir, "missing.ptau")
+    ));
 }
 
 fn assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
@@ -269,16 +403,17 @@ fn assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
     vk_compressed_data: Groth16CompressedData,
     n_public_inputs: usize,
 ) {
-    let _temp_dir = create_sandbox_and_set_as_current_directory();
+    let temp_dir = create_sandbox();
     let aiken_src = aiken_template_with_body_and_verify_definition("", original, "");
-    let output_filename = "my_program".to_string();
+    let build_options = BuildOptions::for_circuit(temp_dir.path().to_path_buf(), "my_program");
     let random_seeds = ("asdasd", "dsadsa");
 
     let aiken_zk_src = AikenZkCompiler::apply_modifications_to_src_for_token(
         aiken_src,
-        output_filename,
+        &build_options,
         random_seeds,
-    );
+    )
+    .unwrap();
 
     let verify_declaration = verify_declaration(n_public_inputs, vk_compressed_data);
     let expected_aiken_src = aiken_template_with_body_and_verify_definition(
@@ -293,6 +428,10 @@ fn assert_compiler_can_replace_keyword_by_the_corresponding_function_and_call(
     );
 }
 
+fn in_sandbox(temp_dir: &TempDir, path: &str) -> String {
+    temp_dir.path().join(path).to_string_lossy().to_string()
+}
+
 fn import_header() -> &'static str {
     "use aiken/collection/list\nuse ak_381/groth16.{Proof, SnarkVerificationKey, groth_verify}\n"
 }
@@ -519,3 +658,11 @@ fn without_delta(final_program: String) -> String {
     result.push_str(remainder);
     result
 }
+
+fn without_deltas(final_program: String) -> String {
+    let mut result = final_program;
+    while result.contains(r#"vkDelta: #""#) {
+        result = without_delta(result);
+    }
+    result
+}
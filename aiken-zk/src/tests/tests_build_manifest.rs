@@ -0,0 +1,301 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::build_manifest::{BuildManifest, CircuitManifest, EntropySource};
+use crate::compiler::compiled_circuit::PublicInput;
+use crate::entropy_generator::SeedCommitment;
+use crate::r1cs_header::R1csHeader;
+use num_bigint::BigUint;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_manifest_hashes_are_sha256() {
+    assert_eq!(
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        BuildManifest::hash(b"abc")
+    );
+}
+
+#[test]
+fn test_manifest_hashes_files_by_their_contents() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("ceremony.ptau");
+    fs::write(&path, b"abc").unwrap();
+
+    let hash = BuildManifest::hash_file(path.to_str().unwrap()).unwrap();
+
+    assert_eq!(BuildManifest::hash(b"abc"), hash);
+}
+
+#[test]
+fn test_manifest_lives_next_to_the_validator() {
+    assert_eq!(
+        Path::new("validators/vote.zk-manifest.json"),
+        BuildManifest::path_for_validator(Path::new("validators/vote.ak"))
+    );
+}
+
+#[test]
+fn test_manifest_is_written_as_json() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("vote.zk-manifest.json");
+    let manifest = manifest_example();
+
+    manifest.write(path.to_str().unwrap()).unwrap();
+
+    let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+        json!({
+            "source_path": "validators_with_offchain/vote.ak",
+            "source_hash": "aa",
+            "output_path": "validators/vote.ak",
+            "output_hash": "bb",
+            "build_dir": "build/vote",
+            "ptau_path": "ceremony.ptau",
+            "ptau_hash": "cc",
+            "entropy_source": "entropy_file",
+            "seed_commitment": {"salt": "dd", "iterations": 100000, "hash": "ee"},
+            "contribution_seed_commitments": [],
+            "circuits": [{
+                "proof_index": null,
+                "circuit_path": "vote.circom",
+                "circuit_hash": "11",
+                "r1cs_path": "build/vote/vote.r1cs",
+                "r1cs": {
+                    "n_wires": 5,
+                    "n_public_outputs": 0,
+                    "n_public_inputs": 2,
+                    "n_private_inputs": 1,
+                    "n_labels": 6,
+                    "n_constraints": 1
+                },
+                "n_public": 2,
+                "zkey_path": "build/vote/verification_key.zkey",
+                "verification_key_path": "build/vote/verification_key.json",
+                "verification_key_hash": "22",
+                "public_inputs": [
+                    {"identifier": "b", "cardinality": "single"},
+                    {"identifier": "votes", "cardinality": "many"}
+                ]
+            }]
+        }),
+        written
+    );
+}
+
+#[test]
+fn test_manifest_can_be_read_back() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("vote.zk-manifest.json");
+    let manifest = manifest_example();
+    manifest.write(path.to_str().unwrap()).unwrap();
+
+    let read_manifest = BuildManifest::from_file(path.to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        manifest.circuits[0].r1cs.n_wires,
+        read_manifest.circuits[0].r1cs.n_wires
+    );
+    assert_eq!(
+        manifest.circuits[0].public_inputs,
+        read_manifest.circuits[0].public_inputs
+    );
+    assert_eq!(manifest.entropy_source, read_manifest.entropy_source);
+}
+
+#[test]
+fn test_relative_manifest_paths_are_taken_from_its_directory() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("validators/vote.zk-manifest.json");
+    fs::create_dir(directory.path().join("validators")).unwrap();
+    let manifest = BuildManifest {
+        build_dir: directory
+            .path()
+            .join("build/vote")
+            .to_string_lossy()
+            .to_string(),
+        ..manifest_example()
+    };
+    manifest.write(path.to_str().unwrap()).unwrap();
+
+    let read_manifest = BuildManifest::from_file(path.to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        directory
+            .path()
+            .join("validators/vote.circom")
+            .to_str()
+            .unwrap(),
+        read_manifest.circuits[0].circuit_path
+    );
+    assert_eq!(manifest.build_dir, read_manifest.build_dir);
+}
+
+#[test]
+fn test_reading_a_file_that_is_not_a_manifest_fails() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("vote.zk-manifest.json");
+    fs::write(&path, "{\"circuits\": 3}").unwrap();
+
+    let result = BuildManifest::from_file(path.to_str().unwrap());
+
+    assert!(matches!(result, Err(AikenZkError::InvalidManifest { .. })));
+}
+
+#[test]
+fn test_the_only_circuit_is_selected_without_a_proof_index() {
+    let manifest = manifest_example();
+
+    let circuit = manifest.circuit("vote.zk-manifest.json", None).unwrap();
+
+    assert_eq!("vote.circom", circuit.circuit_path);
+}
+
+#[test]
+fn test_a_proof_index_is_needed_to_select_one_of_several_circuits() {
+    let manifest = manifest_with_two_circuits();
+
+    let result = manifest.circuit("vote.zk-manifest.json", None);
+
+    assert!(matches!(result, Err(AikenZkError::CircuitSelection { .. })));
+}
+
+#[test]
+fn test_circuits_are_selected_by_proof_index() {
+    let manifest = manifest_with_two_circuits();
+
+    let circuit = manifest.circuit("vote.zk-manifest.json", Some(1)).unwrap();
+
+    assert_eq!("vote_1.circom", circuit.circuit_path);
+}
+
+#[test]
+fn test_selecting_an_unknown_proof_index_fails() {
+    let manifest = manifest_with_two_circuits();
+
+    let result = manifest.circuit("vote.zk-manifest.json", Some(2));
+
+    assert!(matches!(result, Err(AikenZkError::CircuitSelection { .. })));
+}
+
+#[test]
+fn test_a_circuit_edited_after_the_build_is_stale() {
+    let directory = tempdir().unwrap();
+    let circuit_path = directory.path().join("vote.circom");
+    fs::write(&circuit_path, "template Vote() {}").unwrap();
+    let mut circuit = manifest_example().circuits.remove(0);
+    circuit.circuit_path = circuit_path.to_string_lossy().to_string();
+    circuit.circuit_hash = BuildManifest::hash(b"template Vote() {}");
+
+    assert!(
+        circuit
+            .ensure_circuit_is_unchanged("vote.zk-manifest.json")
+            .is_ok()
+    );
+    fs::write(&circuit_path, "template Vote() { signal input a; }").unwrap();
+    assert!(matches!(
+        circuit.ensure_circuit_is_unchanged("vote.zk-manifest.json"),
+        Err(AikenZkError::StaleCircuit { .. })
+    ));
+}
+
+#[test]
+fn test_a_circuit_without_includes_hashes_as_its_file() {
+    let directory = tempdir().unwrap();
+    let circuit_path = directory.path().join("vote.circom");
+    fs::write(&circuit_path, "template Vote() {}").unwrap();
+
+    let hash = BuildManifest::hash_circuit(circuit_path.to_str().unwrap()).unwrap();
+
+    assert_eq!(BuildManifest::hash(b"template Vote() {}"), hash);
+}
+
+#[test]
+fn test_a_circuit_whose_include_changed_is_stale() {
+    let directory = tempdir().unwrap();
+    let templates_dir = directory.path().join("build/templates");
+    fs::create_dir_all(&templates_dir).unwrap();
+    fs::write(templates_dir.join("vote.circom"), "template Vote() {}").unwrap();
+    let circuit_path = directory.path().join("vote.circom");
+    fs::write(
+        &circuit_path,
+        "include \"build/templates/vote.circom\";\ninclude \"circomlib/poseidon.circom\";",
+    )
+    .unwrap();
+    let mut circuit = manifest_example().circuits.remove(0);
+    circuit.circuit_path = circuit_path.to_string_lossy().to_string();
+    circuit.circuit_hash = BuildManifest::hash_circuit(&circuit.circuit_path).unwrap();
+
+    assert!(
+        circuit
+            .ensure_circuit_is_unchanged("vote.zk-manifest.json")
+            .is_ok()
+    );
+    fs::write(
+        templates_dir.join("vote.circom"),
+        "template Vote() { signal input a; }",
+    )
+    .unwrap();
+    assert!(matches!(
+        circuit.ensure_circuit_is_unchanged("vote.zk-manifest.json"),
+        Err(AikenZkError::StaleCircuit { .. })
+    ));
+}
+
+// ---------- AUX ---------- //
+
+fn manifest_with_two_circuits() -> BuildManifest {
+    let mut manifest = manifest_example();
+    let first_circuit = manifest.circuits.remove(0);
+    manifest.circuits = (0..2)
+        .map(|index| CircuitManifest {
+            proof_index: Some(index),
+            circuit_path: format!("vote_{}.circom", index),
+            ..first_circuit.clone()
+        })
+        .collect();
+    manifest
+}
+
+fn manifest_example() -> BuildManifest {
+    BuildManifest {
+        source_path: "validators_with_offchain/vote.ak".to_string(),
+        source_hash: "aa".to_string(),
+        output_path: "validators/vote.ak".to_string(),
+        output_hash: "bb".to_string(),
+        build_dir: "build/vote".to_string(),
+        ptau_path: "ceremony.ptau".to_string(),
+        ptau_hash: "cc".to_string(),
+        entropy_source: EntropySource::EntropyFile,
+        seed_commitment: Some(SeedCommitment {
+            salt: "dd".to_string(),
+            iterations: 100_000,
+            hash: "ee".to_string(),
+        }),
+        contribution_seed_commitments: vec![],
+        circuits: vec![CircuitManifest {
+            proof_index: None,
+            circuit_path: "vote.circom".to_string(),
+            circuit_hash: "11".to_string(),
+            r1cs_path: "build/vote/vote.r1cs".to_string(),
+            r1cs: R1csHeader {
+                prime: BigUint::from(7u32),
+                n_wires: 5,
+                n_public_outputs: 0,
+                n_public_inputs: 2,
+                n_private_inputs: 1,
+                n_labels: 6,
+                n_constraints: 1,
+            },
+            n_public: 2,
+            zkey_path: "build/vote/verification_key.zkey".to_string(),
+            verification_key_path: "build/vote/verification_key.json".to_string(),
+            verification_key_hash: "22".to_string(),
+            public_inputs: vec![
+                PublicInput::single("b".to_string()),
+                PublicInput::many("votes".to_string()),
+            ],
+        }],
+    }
+}
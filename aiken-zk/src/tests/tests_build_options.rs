@@ -0,0 +1,81 @@
+use crate::compiler::build_options::BuildOptions;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_build_options_for_a_circuit_use_the_shared_build_directory() {
+    let build_options = BuildOptions::for_circuit(PathBuf::from("/projects/ballot"), "output");
+
+    assert_eq!(
+        Path::new("/projects/ballot/build/"),
+        build_options.build_dir()
+    );
+    assert_eq!(
+        Path::new("/projects/ballot/ceremony.ptau"),
+        build_options.ptau_path()
+    );
+    assert_eq!("output", build_options.circuit_name());
+}
+
+#[test]
+fn test_build_options_for_a_source_are_derived_from_its_filename() {
+    let build_options = BuildOptions::for_source(
+        PathBuf::from("/projects/ballot"),
+        Path::new("validators_with_offchain/vote.ak"),
+    );
+
+    assert_eq!(
+        Path::new("/projects/ballot/build/vote"),
+        build_options.build_dir()
+    );
+    assert_eq!("vote", build_options.circuit_name());
+}
+
+#[test]
+fn test_build_options_for_different_sources_do_not_share_artifacts() {
+    let vote_options = BuildOptions::for_source(PathBuf::from("/projects"), Path::new("vote.ak"));
+    let tally_options = BuildOptions::for_source(PathBuf::from("/projects"), Path::new("tally.ak"));
+
+    assert_ne!(
+        vote_options.artifact_path("verification_key.zkey"),
+        tally_options.artifact_path("verification_key.zkey")
+    );
+}
+
+#[test]
+fn test_build_options_can_be_overridden() {
+    let build_options = BuildOptions::for_source(PathBuf::from("/projects"), Path::new("vote.ak"))
+        .with_build_dir(PathBuf::from("artifacts"))
+        .with_ptau_path(PathBuf::from("ceremonies/pot16.ptau"))
+        .with_circuit_name("ballot".to_string());
+
+    assert_eq!(Path::new("/projects/artifacts"), build_options.build_dir());
+    assert_eq!(
+        Path::new("/projects/ceremonies/pot16.ptau"),
+        build_options.ptau_path()
+    );
+    assert_eq!("ballot", build_options.circuit_name());
+    assert_eq!(
+        "/projects/artifacts/verification_key.json",
+        build_options.artifact_path("verification_key.json")
+    );
+}
+
+#[test]
+fn test_build_options_paths_are_anchored_to_the_project_root() {
+    let build_options =
+        BuildOptions::for_source(PathBuf::from("/projects/ballot"), Path::new("vote.ak"))
+            .with_ptau_path(PathBuf::from("/ceremonies/pot16.ptau"));
+
+    assert_eq!(
+        Path::new("/ceremonies/pot16.ptau"),
+        build_options.ptau_path()
+    );
+    assert_eq!(
+        "/projects/ballot/build/vote/verification_key.json",
+        build_options.artifact_path("verification_key.json")
+    );
+    assert_eq!(
+        Path::new("/projects/ballot/vote.circom"),
+        build_options.in_project("vote.circom")
+    );
+}
@@ -1,45 +1,134 @@
+use crate::aiken_zk_error::AikenZkError;
 use crate::circom_circuit::CircomCircuit;
-use crate::compiler::BUILD_DIR;
+use crate::compiler::aiken_zk_compiler::Groth16CompressedData;
+use crate::compiler::build_options::BuildOptions;
 use crate::compressed_groth16_proof_bls12_381::CompressedGroth16ProofBls12_381;
-use crate::tests::utils::create_sandbox_and_set_as_current_directory;
-use serial_test::serial;
+use crate::phase2_ceremony::Phase2Ceremony;
+use crate::tests::utils::create_sandbox;
 use std::fs;
+use std::path::Path;
 
 #[test]
-#[serial]
 fn test_circuit_can_generate_a_verification_key() {
-    let _temp_dir = create_sandbox_and_set_as_current_directory();
-    let circom_program_filename = "test.circom".to_string();
-    fs::write(&circom_program_filename, source_code_addition()).unwrap();
-    let circuit = CircomCircuit::from(circom_program_filename.clone());
+    let temp_dir = create_sandbox();
+    let (circuit, build_options) = addition_circuit(temp_dir.path());
     let random_seeds = ("asdasd", "dsadsa");
 
-    circuit.generate_verification_key(random_seeds).unwrap();
+    circuit
+        .generate_verification_key(random_seeds, &build_options)
+        .unwrap();
 
-    let stored_vk = fs::read_to_string(BUILD_DIR.to_string() + "verification_key.json")
+    let stored_vk = fs::read_to_string(build_options.artifact_path("verification_key.json"))
         .expect("No se pudo leer el archivo");
     assert!(stored_vk.contains("protocol"));
 }
 
 #[test]
-#[serial]
+fn test_verification_key_depends_on_the_contributions() {
+    let temp_dir = create_sandbox();
+    let (circuit, build_options) = addition_circuit(temp_dir.path());
+    let first_build_options = build_options.clone().with_build_dir("first".into());
+    let second_build_options = build_options.with_build_dir("second".into());
+
+    circuit
+        .generate_verification_key_with_name(("asdasd", "dsadsa"), &first_build_options, "vk")
+        .unwrap();
+    circuit
+        .generate_verification_key_with_name(("qwerty", "ytrewq"), &second_build_options, "vk")
+        .unwrap();
+
+    let first_vk = Groth16CompressedData::from_json(&first_build_options.artifact_path("vk.json"));
+    let second_vk =
+        Groth16CompressedData::from_json(&second_build_options.artifact_path("vk.json"));
+    assert_ne!(first_vk.unwrap().vk_delta_2, second_vk.unwrap().vk_delta_2);
+}
+
+#[test]
+fn test_ceremony_with_external_contributions_produces_a_verifiable_zkey() {
+    let temp_dir = create_sandbox();
+    let (circuit, build_options) = addition_circuit(temp_dir.path());
+    let zkey = |name: &str| build_options.artifact_path(name);
+
+    circuit
+        .initialize_ceremony(&build_options, &zkey("test_0000.zkey"))
+        .unwrap();
+    Phase2Ceremony::contribute(
+        &zkey("test_0000.zkey"),
+        &zkey("test_0001.zkey"),
+        "alice",
+        "entropy a",
+    )
+    .unwrap();
+    Phase2Ceremony::contribute(
+        &zkey("test_0001.zkey"),
+        &zkey("test_0002.zkey"),
+        "bob",
+        "entropy b",
+    )
+    .unwrap();
+    Phase2Ceremony::beacon(
+        &zkey("test_0002.zkey"),
+        &zkey("test_final.zkey"),
+        Phase2Ceremony::DEFAULT_BEACON_HASH,
+        Phase2Ceremony::DEFAULT_BEACON_ITERATIONS,
+        "beacon",
+    )
+    .unwrap();
+
+    assert!(
+        circuit
+            .verify_ceremony(&build_options, &zkey("test_final.zkey"))
+            .is_ok()
+    );
+}
+
+#[test]
 fn test_circuit_can_generate_a_proof() {
-    let circom_path = "test.circom";
-    let _temp_dir = create_sandbox_and_set_as_current_directory();
-    let circom_program_filename = circom_path.to_string();
-    fs::write(&circom_program_filename, source_code_addition()).unwrap();
+    let temp_dir = create_sandbox();
+    let (circom_circuit, build_options) = addition_circuit(temp_dir.path());
     fs::write(
-        "inputs.json",
+        temp_dir.path().join("inputs.json"),
         "{\"first_addend\":\"1\", \"second_addend\":\"2\", \"sum\":\"3\"}",
     )
     .unwrap();
-    let circom_circuit = CircomCircuit::from(circom_path.to_string());
 
-    let proof = circom_circuit.generate_groth16_proof("my_verification_key.zkey", "inputs.json");
+    let proof = circom_circuit
+        .generate_groth16_proof(&build_options, "my_verification_key.zkey", "inputs.json")
+        .unwrap();
 
     assert_proof_is_valid(proof);
 }
 
+#[test]
+fn test_circuit_rejects_inputs_that_do_not_match_its_signals() {
+    let temp_dir = create_sandbox();
+    let (circom_circuit, build_options) = addition_circuit(temp_dir.path());
+    fs::write(
+        temp_dir.path().join("inputs.json"),
+        "{\"first_addend\":\"1\", \"sum\":\"3\"}",
+    )
+    .unwrap();
+
+    let result = circom_circuit.generate_groth16_proof(
+        &build_options,
+        "my_verification_key.zkey",
+        "inputs.json",
+    );
+
+    let Err(AikenZkError::InvalidInputs { errors, .. }) = result else {
+        panic!("expected invalid inputs");
+    };
+    assert_eq!("second_addend is missing", errors[0].to_string());
+    assert!(!Path::new(&build_options.artifact_path("witness.wtns")).exists());
+}
+
+#[test]
+fn test_a_path_without_a_file_name_is_not_a_circuit() {
+    let result = CircomCircuit::from("..".to_string());
+
+    assert!(matches!(result, Err(AikenZkError::Io { .. })));
+}
+
 // Ideally we would run a verification using the proof and the circuit
 // but this would slow down noticeably the tests
 fn assert_proof_is_valid(proof: CompressedGroth16ProofBls12_381) {
@@ -70,6 +159,14 @@ fn assert_proof_is_valid(proof: CompressedGroth16ProofBls12_381) {
 }
 // ---------- AUX ---------- //
 
+fn addition_circuit(project_root: &Path) -> (CircomCircuit, BuildOptions) {
+    let build_options = BuildOptions::for_circuit(project_root.to_path_buf(), "test");
+    let circom_path = build_options.in_project("test.circom");
+    fs::write(&circom_path, source_code_addition()).unwrap();
+    let circuit = CircomCircuit::from(circom_path.to_string_lossy().to_string()).unwrap();
+    (circuit, build_options)
+}
+
 fn source_code_addition() -> String {
     r#"pragma circom 2.1.9;
 include "templates/addition.circom";
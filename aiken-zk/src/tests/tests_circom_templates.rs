@@ -0,0 +1,146 @@
+use crate::circom_templates::CircomTemplates;
+use crate::tests::utils::{calculate_witness, create_sandbox, template_witness_calculator};
+use crate::witness_calculator::WitnessError;
+use num_bigint::BigUint;
+use serde_json::json;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_the_templates_of_the_built_in_functions_are_embedded() {
+    for template_file_name in [
+        "addition",
+        "assert_eq",
+        "comparators",
+        "eddsa",
+        "fibonacci",
+        "hash",
+        "if",
+        "membership_with_nullifier",
+        "merkle_tree_checker",
+        "multiplication",
+        "num2bits",
+        "polynomials",
+        "poseidon_bls12_381",
+        "range_check",
+        "subtraction",
+    ] {
+        assert!(CircomTemplates::source(template_file_name).is_some());
+    }
+    assert!(CircomTemplates::source("missing").is_none());
+}
+
+#[test]
+fn test_a_template_is_written_into_the_templates_directory_of_the_build() {
+    let directory = tempdir().unwrap();
+
+    let template_path =
+        CircomTemplates::write_to_build_dir("addition", &directory.path().join("build")).unwrap();
+
+    assert_eq!(
+        directory.path().join("build/templates/addition.circom"),
+        template_path
+    );
+    assert_eq!(
+        CircomTemplates::source("addition").unwrap(),
+        fs::read(template_path).unwrap()
+    );
+}
+
+#[test]
+fn test_a_template_edited_in_the_build_directory_is_restored() {
+    let directory = tempdir().unwrap();
+    let template_path = CircomTemplates::write_to_build_dir("addition", directory.path()).unwrap();
+    fs::write(&template_path, "template Addition() {}").unwrap();
+
+    CircomTemplates::write_to_build_dir("addition", directory.path()).unwrap();
+
+    assert_eq!(
+        CircomTemplates::source("addition").unwrap(),
+        fs::read(template_path).unwrap()
+    );
+}
+
+#[test]
+fn test_the_templates_a_template_includes_are_written_next_to_it() {
+    let directory = tempdir().unwrap();
+
+    CircomTemplates::write_to_build_dir("membership_with_nullifier", directory.path()).unwrap();
+
+    assert_eq!(
+        CircomTemplates::source("merkle_tree_checker").unwrap(),
+        fs::read(
+            directory
+                .path()
+                .join("templates/merkle_tree_checker.circom")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_the_templates_are_written_with_the_templates_their_includes_include() {
+    let directory = tempdir().unwrap();
+
+    CircomTemplates::write_to_build_dir("membership_with_nullifier", directory.path()).unwrap();
+
+    assert!(
+        directory
+            .path()
+            .join("templates/poseidon_bls12_381.circom")
+            .is_file()
+    );
+}
+
+#[test]
+fn test_range_check_accepts_the_values_between_its_bounds() {
+    let sandbox = create_sandbox();
+    let mut witness_calculator =
+        template_witness_calculator(sandbox.path(), "range_check", "RangeCheck(8)");
+    let mut range_check = |value: u32, lower_bound: u32, upper_bound: u32| {
+        calculate_witness(
+            &mut witness_calculator,
+            json!({"value": value, "lower_bound": lower_bound, "upper_bound": upper_bound}),
+        )
+    };
+
+    assert!(range_check(10, 10, 20).is_ok());
+    assert!(range_check(19, 10, 20).is_ok());
+    assert!(range_check(0, 0, 256).is_ok());
+    assert!(range_check(255, 0, 256).is_ok());
+    assert!(matches!(
+        range_check(9, 10, 20),
+        Err(WitnessError::Failed { .. })
+    ));
+    assert!(matches!(
+        range_check(20, 10, 20),
+        Err(WitnessError::Failed { .. })
+    ));
+    assert!(matches!(
+        range_check(256, 0, 257),
+        Err(WitnessError::Failed { .. })
+    ));
+    assert!(matches!(
+        range_check(10, 10, 10),
+        Err(WitnessError::Failed { .. })
+    ));
+}
+
+#[test]
+fn test_at_least_compares_the_values_of_n_bits() {
+    let sandbox = create_sandbox();
+    let mut witness_calculator =
+        template_witness_calculator(sandbox.path(), "comparators", "AtLeast(8)");
+    let mut at_least = |lhs: u32, rhs: u32| {
+        // The output signal follows the constant 1 in the witness
+        calculate_witness(&mut witness_calculator, json!({"lhs": lhs, "rhs": rhs}))
+            .map(|witness| witness[1].clone())
+    };
+
+    assert_eq!(BigUint::from(1u8), at_least(7, 7).unwrap());
+    assert_eq!(BigUint::from(0u8), at_least(6, 7).unwrap());
+    assert_eq!(BigUint::from(1u8), at_least(255, 0).unwrap());
+    assert_eq!(BigUint::from(0u8), at_least(0, 255).unwrap());
+    assert!(matches!(at_least(256, 0), Err(WitnessError::Failed { .. })));
+    assert!(matches!(at_least(0, 256), Err(WitnessError::Failed { .. })));
+}
@@ -2,9 +2,13 @@ use crate::component_creator::ComponentCreator;
 use crate::tests::token_examples::{
     addition_token_with_all_private_inputs, addition_token_with_mixed_visibility,
     addition_token_with_public_inputs, assert_eq_token_with_mixed_visibility,
-    fibonacci_token_with_mixed_visibility, if_token_with_mixed_visibility,
+    eddsa_verify_token_with_public_key, fibonacci_token_with_mixed_visibility,
+    greater_equal_token_with_public_inputs, if_token_with_mixed_visibility,
+    is_zero_token_with_mixed_visibility, less_than_token_with_mixed_visibility,
+    membership_with_nullifier_token_with_public_inputs,
     merkle_tree_checker_token_with_mixed_visibility, multiplication_token_with_mixed_visibility,
-    polynomial_evaluations_token, poseidon_token_with_mixed_visibility,
+    offchain_block_token_with_mixed_visibility, polynomial_evaluations_token,
+    poseidon_token_with_mixed_visibility, range_check_token_with_mixed_visibility,
     sha256_token_with_mixed_visibility, subtraction_token_with_mixed_visibility,
 };
 
@@ -12,7 +16,7 @@ use crate::tests::token_examples::{
 fn test_circom_version_should_be_2_1_9() {
     let token = addition_token_with_public_inputs();
     let component_creator = ComponentCreator::from_token(token);
-    let output = component_creator.create();
+    let output = component_creator.create().unwrap();
     let lines = output.lines().collect::<Vec<_>>();
     assert_eq!("pragma circom 2.1.9;", lines[0])
 }
@@ -26,7 +30,7 @@ fn test_component_creator_can_create_addition_component_with_all_public_inputs()
     let expected_program = r#"pragma circom 2.1.9;
 include "templates/addition.circom";
 component main { public [first_addend,second_addend,sum] } = Addition();"#;
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 #[test]
@@ -36,7 +40,7 @@ fn test_component_creator_can_create_addition_component_with_all_private_inputs(
     let expected_program = r#"pragma circom 2.1.9;
 include "templates/addition.circom";
 component main = Addition();"#;
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 #[test]
@@ -46,7 +50,7 @@ fn test_component_creator_can_create_addition_component_with_mixed_visibilities(
     let expected_program = r#"pragma circom 2.1.9;
 include "templates/addition.circom";
 component main { public [second_addend,sum] } = Addition();"#;
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- SUBTRACTION ----------- //
@@ -58,7 +62,7 @@ fn test_component_creator_can_create_subtraction_component_with_mixed_visibiliti
     let expected_program = r#"pragma circom 2.1.9;
 include "templates/subtraction.circom";
 component main { public [minuend,difference] } = Subtraction();"#;
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- MULTIPLICATION ----------- //
@@ -70,7 +74,7 @@ fn test_component_creator_can_create_multiplication_component_with_mixed_visibil
     let expected_program = r#"pragma circom 2.1.9;
 include "templates/multiplication.circom";
 component main { public [multiplicand,product] } = Multiplication();"#;
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- FIBONACCI ----------- //
@@ -85,7 +89,7 @@ fn test_component_creator_can_create_fibonacci_component_with_mixed_visibilities
 include "templates/fibonacci.circom";
 component main {{ public [first_fibonacci,nth_fibonacci] }} = Fibonacci({length_of_fibonacci_series});"#
     );
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- IF ----------- //
@@ -97,7 +101,7 @@ fn test_component_creator_can_create_if_component_with_mixed_visibilities() {
     let expected_program = r#"pragma circom 2.1.9;
 include "templates/if.circom";
 component main { public [condition,true_branch,false_branch] } = If();"#;
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- ASSERT_EQ ----------- //
@@ -109,7 +113,7 @@ fn test_component_creator_can_create_assert_eq_component_with_mixed_visibilities
     let expected_program = r#"pragma circom 2.1.9;
 include "templates/assert_eq.circom";
 component main { public [rhs] } = AssertEq();"#;
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- SHA256 ----------- //
@@ -124,7 +128,7 @@ fn test_component_creator_can_create_sha256_component_with_mixed_visibilities()
 include "templates/hash.circom";
 component main {{ public [out] }} = Sha256({length_of_input});"#
     );
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- POSEIDON ----------- //
@@ -139,7 +143,7 @@ fn test_component_creator_can_create_poseidon_component() {
 include "templates/hash.circom";
 component main {{ public [out] }} = Poseidon({length_of_input});"#
     );
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- MERKLE_TREE_CHECKER ----------- //
@@ -154,7 +158,7 @@ fn test_component_creator_can_create_merkle_tree_checker_component() {
 include "templates/merkle_tree_checker.circom";
 component main {{ public [leaf,root,pathElements,pathIndices] }} = MerkleTreeChecker({tree_height});"#
     );
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
 }
 
 // ----------- POLYNOMIAL_EVALUATIONS ----------- //
@@ -170,5 +174,102 @@ fn test_component_creator_can_create_polynomial_evaluations_component() {
 include "templates/polynomials.circom";
 component main {{ public [coefficients,domain,evaluations] }} = PolynomialEvaluations({grade}, {amount_of_evaluations});"#
     );
-    assert_eq!(expected_program, component_creator.create())
+    assert_eq!(expected_program, component_creator.create().unwrap())
+}
+
+// ----------- RANGE_CHECK ----------- //
+
+#[test]
+fn test_component_creator_can_create_range_check_component() {
+    let token = range_check_token_with_mixed_visibility(8);
+    let component_creator = ComponentCreator::from_token(token);
+    let expected_program = r#"pragma circom 2.1.9;
+include "templates/range_check.circom";
+component main { public [lower_bound,upper_bound] } = RangeCheck(8);"#;
+    assert_eq!(expected_program, component_creator.create().unwrap())
+}
+
+// ----------- COMPARATORS ----------- //
+
+#[test]
+fn test_component_creator_can_create_less_than_component_with_mixed_visibilities() {
+    let token = less_than_token_with_mixed_visibility(32);
+    let component_creator = ComponentCreator::from_token(token);
+    let expected_program = r#"pragma circom 2.1.9;
+include "templates/comparators.circom";
+component main { public [rhs] } = LessThan(32);"#;
+    assert_eq!(expected_program, component_creator.create().unwrap())
+}
+
+#[test]
+fn test_component_creator_can_create_greater_equal_component_with_all_public_inputs() {
+    let token = greater_equal_token_with_public_inputs(64);
+    let component_creator = ComponentCreator::from_token(token);
+    let expected_program = r#"pragma circom 2.1.9;
+include "templates/comparators.circom";
+component main { public [lhs,rhs,out] } = GreaterEqual(64);"#;
+    assert_eq!(expected_program, component_creator.create().unwrap())
+}
+
+#[test]
+fn test_component_creator_can_create_is_zero_component_with_mixed_visibilities() {
+    let token = is_zero_token_with_mixed_visibility();
+    let component_creator = ComponentCreator::from_token(token);
+    let expected_program = r#"pragma circom 2.1.9;
+include "templates/comparators.circom";
+component main { public [out] } = IsZero();"#;
+    assert_eq!(expected_program, component_creator.create().unwrap())
+}
+
+#[test]
+fn test_component_creator_can_create_eddsa_verify_component_with_public_key() {
+    let token = eddsa_verify_token_with_public_key();
+    let component_creator = ComponentCreator::from_token(token);
+    let expected_program = r#"pragma circom 2.1.9;
+include "templates/eddsa.circom";
+component main { public [pub_key_x,pub_key_y] } = EddsaVerify();"#;
+    assert_eq!(expected_program, component_creator.create().unwrap())
+}
+
+#[test]
+fn test_component_creator_can_create_membership_with_nullifier_component() {
+    let token = membership_with_nullifier_token_with_public_inputs(2);
+    let component_creator = ComponentCreator::from_token(token);
+    let expected_program = r#"pragma circom 2.1.9;
+include "templates/membership_with_nullifier.circom";
+component main { public [root,nullifier,external_nullifier] } = MembershipWithNullifier(2);"#;
+    assert_eq!(expected_program, component_creator.create().unwrap())
+}
+
+#[test]
+fn test_component_creator_writes_the_template_of_an_offchain_block_in_the_circuit() {
+    let token = offchain_block_token_with_mixed_visibility();
+    let component_creator = ComponentCreator::from_token(token);
+    let expected_program = r#"pragma circom 2.1.9;
+template OffchainBlock() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal input d;
+    signal sum <== a + b;
+    sum * c === d;
+}
+component main { public [a,b,d] } = OffchainBlock();"#;
+    assert_eq!(expected_program, component_creator.create().unwrap());
+    assert_eq!(None, component_creator.template_file_name().unwrap())
+}
+
+#[test]
+fn test_component_creator_includes_the_template_from_the_given_directory() {
+    let token = addition_token_with_public_inputs();
+    let component_creator =
+        ComponentCreator::from_token(token).with_templates_dir("build/templates".to_string());
+    let expected_program = r#"pragma circom 2.1.9;
+include "build/templates/addition.circom";
+component main { public [first_addend,second_addend,sum] } = Addition();"#;
+    assert_eq!(expected_program, component_creator.create().unwrap());
+    assert_eq!(
+        Some("addition"),
+        component_creator.template_file_name().unwrap()
+    )
 }
@@ -0,0 +1,188 @@
+use crate::circom_templates::CircomTemplates;
+use crate::eddsa::{EddsaError, EddsaSignature};
+use crate::jubjub::JubjubPoint;
+use crate::tests::utils::{
+    calculate_witness, create_sandbox, field_elements_in, template_witness_calculator,
+};
+use crate::witness_calculator::WitnessError;
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use std::fs;
+
+#[test]
+fn test_a_signature_verifies_against_its_public_key_and_message() {
+    let signature = EddsaSignature::sign(&private_key(), Fr::from(42u64)).unwrap();
+
+    assert!(signature.verify());
+    assert_eq!(
+        EddsaSignature::public_key(&private_key()).unwrap(),
+        signature.public_key
+    );
+}
+
+#[test]
+fn test_signing_is_deterministic() {
+    let signature = EddsaSignature::sign(&private_key(), Fr::from(42u64)).unwrap();
+
+    assert_eq!(
+        signature,
+        EddsaSignature::sign(&private_key(), Fr::from(42u64)).unwrap()
+    );
+    assert_ne!(
+        signature.r,
+        EddsaSignature::sign(&private_key(), Fr::from(43u64))
+            .unwrap()
+            .r
+    );
+}
+
+#[test]
+fn test_a_signature_does_not_verify_another_message_or_key() {
+    let signature = EddsaSignature::sign(&private_key(), Fr::from(42u64)).unwrap();
+
+    let other_message = EddsaSignature {
+        msg: Fr::from(43u64),
+        ..signature.clone()
+    };
+    let other_key = EddsaSignature {
+        public_key: EddsaSignature::public_key(&BigUint::from(7u8)).unwrap(),
+        ..signature
+    };
+
+    assert!(!other_message.verify());
+    assert!(!other_key.verify());
+}
+
+#[test]
+fn test_s_must_be_below_the_subgroup_order() {
+    let signature = EddsaSignature::sign(&private_key(), Fr::from(42u64)).unwrap();
+
+    // The same point, so only the range check tells them apart.
+    let malleated = EddsaSignature {
+        s: &signature.s + JubjubPoint::subgroup_order(),
+        ..signature
+    };
+
+    assert!(!malleated.verify());
+}
+
+#[test]
+fn test_a_public_key_of_small_order_is_rejected() {
+    let point_of_order_two = JubjubPoint::new(Fr::from(0u64), -Fr::from(1u64)).unwrap();
+
+    let forged = EddsaSignature {
+        public_key: point_of_order_two,
+        msg: Fr::from(42u64),
+        r: JubjubPoint::identity(),
+        s: BigUint::from(0u8),
+    };
+
+    assert!(!forged.verify());
+}
+
+#[test]
+fn test_the_private_key_must_be_in_the_subgroup_range() {
+    assert_eq!(
+        Err(EddsaError::InvalidPrivateKey),
+        EddsaSignature::public_key(&BigUint::from(0u8))
+    );
+    assert_eq!(
+        Err(EddsaError::InvalidPrivateKey),
+        EddsaSignature::sign(&JubjubPoint::subgroup_order(), Fr::from(42u64))
+    );
+}
+
+#[test]
+fn test_the_private_key_is_read_from_a_file() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("private_key");
+    fs::write(&path, "0x1234\n").unwrap();
+
+    let private_key = EddsaSignature::private_key_from_file(&path.to_string_lossy()).unwrap();
+
+    assert_eq!(BigUint::from(0x1234u16), private_key);
+}
+
+#[test]
+fn test_the_message_must_be_a_field_element() {
+    let modulus = BigUint::from(Fr::MODULUS).to_string();
+
+    assert_eq!(Ok(Fr::from(255u64)), EddsaSignature::msg_from_text("0xff"));
+    assert_eq!(
+        Err(EddsaError::InvalidMessage {
+            value: modulus.clone()
+        }),
+        EddsaSignature::msg_from_text(&modulus)
+    );
+}
+
+#[test]
+fn test_the_inputs_are_named_like_the_signals_of_the_template() {
+    let signature = EddsaSignature::sign(&private_key(), Fr::from(42u64)).unwrap();
+
+    let inputs = signature.to_inputs_json();
+
+    assert_eq!("42", inputs["msg"]);
+    assert_eq!(signature.s.to_string(), inputs["s"]);
+    let mut names: Vec<_> = inputs.as_object().unwrap().keys().cloned().collect();
+    names.sort();
+    assert_eq!(
+        vec!["msg", "pub_key_x", "pub_key_y", "r_x", "r_y", "s"],
+        names
+    );
+}
+
+#[test]
+fn test_the_eddsa_template_uses_the_same_constants() {
+    let modulus = BigUint::from(Fr::MODULUS);
+    let expected_constants = vec![
+        JubjubPoint::d(),
+        JubjubPoint::generator().x,
+        JubjubPoint::generator().y,
+        Fr::from(JubjubPoint::subgroup_order()),
+        Fr::from(&modulus >> 128u8),
+        Fr::from(&modulus % (BigUint::from(1u8) << 128u8)),
+    ];
+
+    let template = String::from_utf8(CircomTemplates::source("eddsa").unwrap()).unwrap();
+
+    assert_eq!(expected_constants, field_elements_in(&template));
+}
+
+#[test]
+fn test_the_eddsa_template_accepts_the_signatures_the_rust_verifier_accepts() {
+    let sandbox = create_sandbox();
+    let mut witness_calculator =
+        template_witness_calculator(sandbox.path(), "eddsa", "EddsaVerify()");
+    let signature = EddsaSignature::sign(&private_key(), Fr::from(42u64)).unwrap();
+
+    let other_s = EddsaSignature {
+        s: &signature.s + 1u8,
+        ..signature.clone()
+    };
+    let other_message = EddsaSignature {
+        msg: Fr::from(43u64),
+        ..signature.clone()
+    };
+    let small_order_key = EddsaSignature {
+        public_key: JubjubPoint::new(Fr::from(0u64), -Fr::from(1u64)).unwrap(),
+        msg: Fr::from(42u64),
+        r: JubjubPoint::identity(),
+        s: BigUint::from(0u8),
+    };
+
+    assert!(calculate_witness(&mut witness_calculator, signature.to_inputs_json()).is_ok());
+    for rejected in [other_s, other_message, small_order_key] {
+        assert!(matches!(
+            calculate_witness(&mut witness_calculator, rejected.to_inputs_json()),
+            Err(WitnessError::Failed { .. })
+        ));
+    }
+}
+
+// ---------- AUX ---------- //
+
+fn private_key() -> BigUint {
+    BigUint::from(123456789u64)
+}
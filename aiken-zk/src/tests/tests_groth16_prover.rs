@@ -0,0 +1,87 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::compressed_groth16_proof_bls12_381::CompressedGroth16ProofBls12_381;
+use crate::groth16_prover_bls12_381::{Groth16ProverBls12_381, ProvingError};
+use crate::tests::utils::manifest_path;
+use ark_bls12_381::Fr;
+use serde_json::json;
+
+#[test]
+fn test_prover_generates_a_proof_of_a_satisfying_witness() {
+    let prover = addition_prover();
+
+    let (_, public_signals) = prover.prove(&witness(&[1, 1, 2, 3])).unwrap();
+
+    assert_eq!(
+        json!(["1", "2", "3"]),
+        Groth16ProverBls12_381::to_snarkjs_public_signals(&public_signals)
+    );
+}
+
+#[test]
+fn test_prover_rejects_a_witness_that_does_not_satisfy_the_constraints() {
+    let prover = addition_prover();
+
+    let result = prover.prove(&witness(&[1, 1, 2, 4]));
+
+    assert!(matches!(result, Err(ProvingError::UnsatisfiedConstraints)));
+}
+
+#[test]
+fn test_prover_rejects_a_witness_of_another_circuit() {
+    let prover = addition_prover();
+
+    let result = prover.prove(&witness(&[1, 1, 2]));
+
+    assert!(matches!(
+        result,
+        Err(ProvingError::WitnessSizeMismatch {
+            expected: 4,
+            actual: 3
+        })
+    ));
+}
+
+#[test]
+fn test_prover_formats_the_proof_as_snarkjs() {
+    let (proof, _) = addition_prover().prove(&witness(&[1, 1, 2, 3])).unwrap();
+
+    let snarkjs_proof = Groth16ProverBls12_381::to_snarkjs_proof(&proof);
+
+    assert_eq!("groth16", snarkjs_proof["protocol"]);
+    assert_eq!("bls12381", snarkjs_proof["curve"]);
+    assert!(CompressedGroth16ProofBls12_381::from_snarkjs_proof(&snarkjs_proof).is_ok());
+}
+
+#[test]
+fn test_prover_requires_a_bls12_381_groth16_proving_key() {
+    let missing_zkey = Groth16ProverBls12_381::from_zkey("missing.zkey");
+    let invalid_zkey = Groth16ProverBls12_381::from_zkey_bytes(b"not a zkey");
+
+    assert!(matches!(missing_zkey, Err(ProvingError::Io { .. })));
+    assert_eq!(Some("wrong magic number"), invalid_zkey.err());
+}
+
+#[test]
+fn test_a_proving_key_that_cannot_be_read_is_an_io_error() {
+    let Err(error) = Groth16ProverBls12_381::from_zkey("missing.zkey") else {
+        panic!("expected the proving key to be missing");
+    };
+
+    assert_eq!(
+        AikenZkError::IO_EXIT_CODE,
+        AikenZkError::from(error).exit_code()
+    );
+}
+
+// ---------- AUX ---------- //
+
+fn addition_prover() -> Groth16ProverBls12_381 {
+    Groth16ProverBls12_381::from_zkey(
+        &(manifest_path() + "/src/tests/sandbox/my_verification_key.zkey"),
+    )
+    .unwrap()
+}
+
+fn witness(values: &[u64]) -> Vec<Fr> {
+    values.iter().map(|value| Fr::from(*value)).collect()
+}
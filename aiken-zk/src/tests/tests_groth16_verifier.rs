@@ -0,0 +1,160 @@
+use crate::groth16_verifier_bls12_381::{Groth16VerifierBls12_381, VerificationError};
+use crate::point_compressor_bls12_381::PointCompressorBls12_381;
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use serde_json::{Value, json};
+
+// The fixtures use known trapdoors: alpha = 2·G1, beta = 3·G2, gamma = delta = G2 and
+// IC = [5·G1, 7·G1]. A proof A = x·G1, B = G2, C = 11·G1 for the public input p then verifies
+// whenever x = 2·3 + 5 + 7·p + 11.
+const PUBLIC_INPUT: u64 = 9;
+
+#[test]
+fn test_valid_snarkjs_proof_is_accepted() {
+    let verifier =
+        Groth16VerifierBls12_381::from_snarkjs_verification_key(&verification_key()).unwrap();
+
+    let result = verifier.verify(&public_inputs(PUBLIC_INPUT), &snarkjs_proof());
+
+    assert!(result.unwrap());
+}
+
+#[test]
+fn test_valid_compressed_json_proof_is_accepted() {
+    let verifier =
+        Groth16VerifierBls12_381::from_snarkjs_verification_key(&verification_key()).unwrap();
+
+    let result = verifier.verify(&public_inputs(PUBLIC_INPUT), &compressed_json_proof());
+
+    assert!(result.unwrap());
+}
+
+#[test]
+fn test_proof_is_rejected_for_other_public_inputs() {
+    let verifier =
+        Groth16VerifierBls12_381::from_snarkjs_verification_key(&verification_key()).unwrap();
+
+    let result = verifier.verify(&public_inputs(PUBLIC_INPUT + 1), &snarkjs_proof());
+
+    assert!(!result.unwrap());
+}
+
+#[test]
+fn test_wrong_amount_of_public_inputs_is_reported() {
+    let verifier =
+        Groth16VerifierBls12_381::from_snarkjs_verification_key(&verification_key()).unwrap();
+
+    let result = verifier.verify(&json!(["1", "2"]), &snarkjs_proof());
+
+    assert!(matches!(
+        result,
+        Err(VerificationError::PublicInputCountMismatch {
+            expected: 1,
+            actual: 2
+        })
+    ));
+}
+
+#[test]
+fn test_public_inputs_outside_the_scalar_field_are_rejected() {
+    let verifier =
+        Groth16VerifierBls12_381::from_snarkjs_verification_key(&verification_key()).unwrap();
+    let scalar_field_modulus =
+        "52435875175126190479447740508185965837690552500527637822603658699938581184513";
+
+    let result = verifier.verify(&json!([scalar_field_modulus]), &snarkjs_proof());
+
+    assert!(matches!(
+        result,
+        Err(VerificationError::InvalidPublicInput(_))
+    ));
+}
+
+#[test]
+fn test_compressed_proof_with_a_truncated_point_is_rejected() {
+    let verifier =
+        Groth16VerifierBls12_381::from_snarkjs_verification_key(&verification_key()).unwrap();
+    let mut proof = compressed_json_proof();
+    proof["piB"] = json!("93e02b6052719f607dacd3a0");
+
+    let result = verifier.verify(&public_inputs(PUBLIC_INPUT), &proof);
+
+    assert!(matches!(
+        result,
+        Err(VerificationError::InvalidPoint { document: "proof", ref field, .. }) if field == "piB"
+    ));
+}
+
+#[test]
+fn test_verification_key_without_ic_points_is_rejected() {
+    let mut verification_key = verification_key();
+    verification_key["IC"] = json!([]);
+
+    let result = Groth16VerifierBls12_381::from_snarkjs_verification_key(&verification_key);
+
+    assert!(matches!(
+        result,
+        Err(VerificationError::EmptyField {
+            document: "verification key",
+            field: "IC"
+        })
+    ));
+}
+
+// ---------- AUX ---------- //
+
+fn verification_key() -> Value {
+    json!({
+        "protocol": "groth16",
+        "curve": "bls12381",
+        "nPublic": 1,
+        "vk_alpha_1": snarkjs_g1_point(g1_multiple(2)),
+        "vk_beta_2": snarkjs_g2_point(g2_multiple(3)),
+        "vk_gamma_2": snarkjs_g2_point(g2_multiple(1)),
+        "vk_delta_2": snarkjs_g2_point(g2_multiple(1)),
+        "IC": [snarkjs_g1_point(g1_multiple(5)), snarkjs_g1_point(g1_multiple(7))],
+    })
+}
+
+fn public_inputs(public_input: u64) -> Value {
+    json!([public_input.to_string()])
+}
+
+fn snarkjs_proof() -> Value {
+    json!({
+        "pi_a": snarkjs_g1_point(g1_multiple(2 * 3 + 5 + 7 * PUBLIC_INPUT + 11)),
+        "pi_b": snarkjs_g2_point(g2_multiple(1)),
+        "pi_c": snarkjs_g1_point(g1_multiple(11)),
+        "protocol": "groth16",
+        "curve": "bls12381"
+    })
+}
+
+fn compressed_json_proof() -> Value {
+    let proof = snarkjs_proof();
+    json!({
+        "piA": PointCompressorBls12_381::compress_g1(&proof["pi_a"]).unwrap(),
+        "piB": PointCompressorBls12_381::compress_g2(&proof["pi_b"]).unwrap(),
+        "piC": PointCompressorBls12_381::compress_g1(&proof["pi_c"]).unwrap(),
+    })
+}
+
+fn g1_multiple(scalar: u64) -> G1Affine {
+    (G1Projective::from(G1Affine::generator()) * Fr::from(scalar)).into_affine()
+}
+
+fn g2_multiple(scalar: u64) -> G2Affine {
+    (G2Projective::from(G2Affine::generator()) * Fr::from(scalar)).into_affine()
+}
+
+fn snarkjs_g1_point(point: G1Affine) -> Value {
+    json!([point.x.to_string(), point.y.to_string(), "1"])
+}
+
+fn snarkjs_g2_point(point: G2Affine) -> Value {
+    json!([
+        [point.x.c0.to_string(), point.x.c1.to_string()],
+        [point.y.c0.to_string(), point.y.c1.to_string()],
+        ["1", "0"]
+    ])
+}
@@ -0,0 +1,217 @@
+use crate::compiler::build_options::BuildOptions;
+use crate::inputs_template::InputsTemplate;
+use crate::r1cs_header::R1csHeader;
+use crate::tests::token_examples::{
+    addition_token_with_mixed_visibility, eddsa_verify_token_with_public_key,
+    fibonacci_token_with_mixed_visibility, membership_with_nullifier_token_with_public_inputs,
+    merkle_tree_checker_token_with_mixed_visibility, offchain_block_token_with_mixed_visibility,
+    polynomial_evaluations_token, range_check_token_with_mixed_visibility,
+    sha256_token_with_mixed_visibility,
+};
+use num_bigint::BigUint;
+use serde_json::{Value, json};
+use std::env;
+
+#[test]
+fn test_template_marks_public_and_private_signals() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &addition_token_with_mixed_visibility(),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json!({"first_addend": "<private>", "second_addend": "<public>", "sum": "<public>"}),
+        template_json(&inputs_template)
+    );
+}
+
+#[test]
+fn test_template_keeps_the_signals_in_circuit_order() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &fibonacci_token_with_mixed_visibility(5),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    let template = inputs_template.to_json();
+    let keys: Vec<&str> = template
+        .lines()
+        .filter_map(|line| line.trim().split('"').nth(1))
+        .collect();
+    assert_eq!(
+        vec!["first_fibonacci", "second_fibonacci", "nth_fibonacci"],
+        keys
+    );
+}
+
+#[test]
+fn test_sha256_input_has_one_element_per_bit() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &sha256_token_with_mixed_visibility(16),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    let template = template_json(&inputs_template);
+    assert_eq!(16, template["in"].as_array().unwrap().len());
+    assert_eq!(json!(["<public>", "<public>"]), template["out"]);
+}
+
+#[test]
+fn test_merkle_tree_paths_have_one_element_per_level() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &merkle_tree_checker_token_with_mixed_visibility(3),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    let template = template_json(&inputs_template);
+    assert_eq!("<public>", template["leaf"]);
+    assert_eq!(
+        json!(["<public>", "<public>", "<public>"]),
+        template["pathElements"]
+    );
+    assert_eq!(
+        json!(["<public>", "<public>", "<public>"]),
+        template["pathIndices"]
+    );
+}
+
+#[test]
+fn test_polynomial_has_one_coefficient_more_than_its_grade() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &polynomial_evaluations_token(2, 4),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    let template = template_json(&inputs_template);
+    assert_eq!(3, template["coefficients"].as_array().unwrap().len());
+    assert_eq!(4, template["domain"].as_array().unwrap().len());
+    assert_eq!(4, template["evaluations"].as_array().unwrap().len());
+}
+
+#[test]
+fn test_range_check_value_is_private_and_its_bounds_public() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &range_check_token_with_mixed_visibility(8),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json!({"value": "<private>", "lower_bound": "<public>", "upper_bound": "<public>"}),
+        template_json(&inputs_template)
+    );
+}
+
+#[test]
+fn test_eddsa_verify_public_key_is_public_and_the_signed_message_private() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &eddsa_verify_token_with_public_key(),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json!({
+            "pub_key_x": "<public>",
+            "pub_key_y": "<public>",
+            "msg": "<private>",
+            "r_x": "<private>",
+            "r_y": "<private>",
+            "s": "<private>",
+        }),
+        template_json(&inputs_template)
+    );
+}
+
+#[test]
+fn test_membership_secrets_and_path_are_private_even_if_not_in_the_statement() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &membership_with_nullifier_token_with_public_inputs(2),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json!({
+            "root": "<public>",
+            "nullifier": "<public>",
+            "external_nullifier": "<public>",
+            "secret": "<private>",
+            "trapdoor": "<private>",
+            "pathElements": ["<private>", "<private>"],
+            "pathIndices": ["<private>", "<private>"],
+        }),
+        template_json(&inputs_template)
+    );
+}
+
+#[test]
+fn test_offchain_block_template_has_the_variables_the_block_reads() {
+    let inputs_template = InputsTemplate::for_offchain(
+        &offchain_block_token_with_mixed_visibility(),
+        &built_in_build_options(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json!({"a": "<public>", "b": "<public>", "c": "<private>", "d": "<public>"}),
+        template_json(&inputs_template)
+    );
+}
+
+#[test]
+fn test_template_is_read_from_the_symbols_of_the_main_component() {
+    let symbols = "1,1,0,main.out\n\
+                   2,2,0,main.l[0]\n\
+                   3,3,0,main.l[1]\n\
+                   4,4,0,main.m[0][0]\n\
+                   5,5,0,main.m[1][2]\n\
+                   6,6,0,main.val\n\
+                   7,7,1,main.inner.a\n\
+                   8,-1,0,main.tmp\n";
+
+    let inputs_template = InputsTemplate::from_symbols(&header(1, 4, 1), symbols).unwrap();
+
+    assert_eq!(
+        json!({
+            "l": ["<public>", "<public>"],
+            "m": [["<public>", "<public>", "<public>"], ["<public>", "<public>", "<public>"]],
+            "val": "<private>"
+        }),
+        template_json(&inputs_template)
+    );
+}
+
+#[test]
+fn test_symbols_with_a_malformed_wire_are_rejected() {
+    let result = InputsTemplate::from_symbols(&header(0, 1, 0), "1,one,0,main.a\n");
+
+    assert_eq!(Err("wire index is not a number"), result);
+}
+
+// ---------- AUX ---------- //
+
+// The signals of the built-in functions are known without compiling anything in the project.
+fn built_in_build_options() -> BuildOptions {
+    BuildOptions::for_circuit(env::temp_dir(), "output")
+}
+
+fn template_json(inputs_template: &InputsTemplate) -> Value {
+    serde_json::from_str(&inputs_template.to_json()).unwrap()
+}
+
+fn header(n_public_outputs: u32, n_public_inputs: u32, n_private_inputs: u32) -> R1csHeader {
+    R1csHeader {
+        prime: BigUint::from(7u32),
+        n_wires: 8,
+        n_public_outputs,
+        n_public_inputs,
+        n_private_inputs,
+        n_labels: 8,
+        n_constraints: 1,
+    }
+}
@@ -0,0 +1,209 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::inputs_template::CircuitSignal;
+use crate::inputs_validator::{InputsError, InputsValidator};
+use crate::zk_examples::InputVisibility;
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use serde_json::json;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_inputs_matching_the_circuit_are_valid() {
+    let validator = merkle_tree_validator(3);
+
+    let errors = validator.validate(&json!({
+        "leaf": "7",
+        "root": 12,
+        "pathElements": ["1", "0x2f", "-3"],
+        "pathIndices": [0, 1, 0]
+    }));
+
+    assert_eq!(Vec::<InputsError>::new(), errors);
+}
+
+#[test]
+fn test_missing_and_unknown_signals_are_reported() {
+    let validator = merkle_tree_validator(1);
+
+    let errors = validator.validate(&json!({
+        "leaf": "7",
+        "pathElements": ["1"],
+        "pathIndices": [0],
+        "path_indices": [0]
+    }));
+
+    assert_eq!(
+        vec![
+            InputsError::MissingSignal {
+                signal: "root".to_string()
+            },
+            InputsError::UnknownSignal {
+                signal: "path_indices".to_string()
+            },
+        ],
+        errors
+    );
+}
+
+#[test]
+fn test_lists_of_the_wrong_length_are_reported() {
+    let validator = merkle_tree_validator(10);
+
+    let errors = validator.validate(&json!({
+        "leaf": "7",
+        "root": "12",
+        "pathElements": vec!["1"; 10],
+        "pathIndices": vec![0; 9]
+    }));
+
+    assert_eq!(1, errors.len());
+    assert_eq!(
+        "pathIndices expected 10 elements, got 9",
+        errors[0].to_string()
+    );
+}
+
+#[test]
+fn test_values_outside_the_scalar_field_are_reported() {
+    let validator = merkle_tree_validator(1);
+    let field_modulus = BigUint::from(Fr::MODULUS).to_string();
+
+    let errors = validator.validate(&json!({
+        "leaf": field_modulus,
+        "root": "12",
+        "pathElements": ["1"],
+        "pathIndices": [0]
+    }));
+
+    assert_eq!(
+        vec![InputsError::OutOfField {
+            signal: "leaf".to_string(),
+            value: format!("\"{}\"", field_modulus),
+        }],
+        errors
+    );
+    assert!(
+        errors[0]
+            .to_string()
+            .contains("value exceeds BLS12-381 scalar field")
+    );
+}
+
+#[test]
+fn test_values_that_are_not_numbers_are_reported_with_their_position() {
+    let validator = merkle_tree_validator(2);
+
+    let errors = validator.validate(&json!({
+        "leaf": ["7"],
+        "root": "twelve",
+        "pathElements": "1",
+        "pathIndices": [0, true]
+    }));
+
+    assert_eq!(
+        vec![
+            InputsError::ExpectedSingleValue {
+                signal: "leaf".to_string()
+            },
+            InputsError::NotANumber {
+                signal: "root".to_string(),
+                value: "\"twelve\"".to_string()
+            },
+            InputsError::ExpectedList {
+                signal: "pathElements".to_string(),
+                expected: 2
+            },
+            InputsError::NotANumber {
+                signal: "pathIndices[1]".to_string(),
+                value: "true".to_string()
+            },
+        ],
+        errors
+    );
+}
+
+#[test]
+fn test_nested_lists_are_checked_level_by_level() {
+    let validator = InputsValidator::new(
+        vec![signal("m", vec![2, 3], InputVisibility::Private)],
+        BigUint::from(Fr::MODULUS),
+    );
+
+    let errors = validator.validate(&json!({"m": [[1, 2, 3], [4, 5]]}));
+
+    assert_eq!(
+        vec![InputsError::WrongLength {
+            signal: "m[1]".to_string(),
+            expected: 3,
+            actual: 2
+        }],
+        errors
+    );
+}
+
+#[test]
+fn test_invalid_inputs_files_fail_with_every_error() {
+    let directory = tempdir().unwrap();
+    let inputs_path = directory.path().join("inputs.json");
+    fs::write(&inputs_path, r#"{"leaf": "7"}"#).unwrap();
+
+    let result = merkle_tree_validator(1).validate_file(inputs_path.to_str().unwrap());
+
+    let Err(AikenZkError::InvalidInputs { errors, .. }) = result else {
+        panic!("expected invalid inputs, got {:?}", result);
+    };
+    assert_eq!(3, errors.len());
+}
+
+#[test]
+fn test_field_elements_are_neither_negative_nor_reduced() {
+    let modulus = BigUint::from(Fr::MODULUS);
+
+    assert_eq!(
+        Some(Fr::from(255u64)),
+        InputsValidator::parse_field_element("0xff")
+    );
+    assert_eq!(
+        Some(-Fr::from(1u64)),
+        InputsValidator::parse_field_element(&(&modulus - 1u8).to_string())
+    );
+    assert_eq!(None, InputsValidator::parse_field_element("-1"));
+    assert_eq!(
+        None,
+        InputsValidator::parse_field_element(&modulus.to_string())
+    );
+    assert_eq!(
+        (&modulus - 1u8).to_string(),
+        InputsValidator::field_element_to_decimal(&-Fr::from(1u64))
+    );
+}
+
+#[test]
+fn test_numbers_are_read_quoted_or_not() {
+    assert_eq!("7", InputsValidator::number_text(&json!(7)));
+    assert_eq!("0x7", InputsValidator::number_text(&json!("0x7")));
+}
+
+// ---------- AUX ---------- //
+
+fn merkle_tree_validator(levels: usize) -> InputsValidator {
+    InputsValidator::new(
+        vec![
+            signal("leaf", vec![], InputVisibility::Public),
+            signal("root", vec![], InputVisibility::Public),
+            signal("pathElements", vec![levels], InputVisibility::Private),
+            signal("pathIndices", vec![levels], InputVisibility::Private),
+        ],
+        BigUint::from(Fr::MODULUS),
+    )
+}
+
+fn signal(name: &str, dimensions: Vec<usize>, visibility: InputVisibility) -> CircuitSignal {
+    CircuitSignal {
+        name: name.to_string(),
+        dimensions,
+        visibility,
+    }
+}
@@ -1,32 +1,40 @@
+use crate::build_manifest::BuildManifest;
 use crate::circom_circuit::CircomCircuit;
+use crate::compiler::build_options::BuildOptions;
 use crate::tests::circom_component_factory::addition_custom_circom_template_and_component;
-use crate::tests::utils::{create_sandbox_and_set_as_current_directory, manifest_path};
-use serial_test::serial;
+use crate::tests::utils::{create_sandbox, manifest_path};
+use serde_json::{Value, json};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 use std::process::Command;
 use std::{fs, io};
+use tempfile::tempdir;
 
 pub const BUILD_COMMAND: &str = "build";
 
 #[test]
-#[serial]
 fn test_user_can_convert_aiken_with_offchain_to_valid_aiken() {
-    let _temporal_directory = create_sandbox_and_set_as_current_directory();
+    let temporal_directory = create_sandbox();
+    let project = temporal_directory.path();
     let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
     let output_path = "validators/output.ak";
-    create_original_aiken_file();
+    create_original_aiken_file(project);
 
     Command::new(aiken_zk_binary_path)
+        .current_dir(project)
         .arg(BUILD_COMMAND)
         .arg(source_aiken_filename())
         .arg(output_path)
         .output()
         .unwrap();
 
-    let compilation_result = Command::new("aiken").arg(BUILD_COMMAND).output().unwrap();
-    let file = File::open(output_path).unwrap();
+    let compilation_result = Command::new("aiken")
+        .current_dir(project)
+        .arg(BUILD_COMMAND)
+        .output()
+        .unwrap();
+    let file = File::open(project.join(output_path)).unwrap();
     let lines: Vec<String> = io::BufReader::new(file)
         .lines()
         .collect::<Result<_, _>>()
@@ -35,72 +43,215 @@ fn test_user_can_convert_aiken_with_offchain_to_valid_aiken() {
     let expected_line_declaration = "fn zk_verify_or_fail(";
     assert!(lines[19].contains(expected_line_replacement));
     assert!(lines[33].contains(expected_line_declaration));
-    assert!(Path::new("verification_key.zkey").exists());
-    assert!(Path::new("output.circom").exists());
+    assert!(
+        project
+            .join("build/original_aiken_code/verification_key.zkey")
+            .exists()
+    );
+    assert!(
+        project
+            .join("build/original_aiken_code/original_aiken_code.circom")
+            .exists()
+    );
+    let manifest: Value = serde_json::from_str(
+        &fs::read_to_string(project.join("validators/output.zk-manifest.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        project
+            .join("build/original_aiken_code/original_aiken_code.circom")
+            .to_str()
+            .unwrap(),
+        manifest["circuits"][0]["circuit_path"]
+    );
+    assert_eq!(2, manifest["circuits"][0]["n_public"]);
+    assert_eq!(
+        json!([
+            {"identifier": "b", "cardinality": "single"},
+            {"identifier": "10", "cardinality": "single"}
+        ]),
+        manifest["circuits"][0]["public_inputs"]
+    );
 
     assert!(compilation_result.status.success());
-    assert!(Path::new("plutus.json").exists());
+    assert!(project.join("plutus.json").exists());
 }
 
 #[test]
-#[serial]
 fn test_user_can_convert_aiken_with_custom_circom_offchain_to_valid_aiken() {
-    let _temporal_directory = create_sandbox_and_set_as_current_directory();
+    let temporal_directory = create_sandbox();
+    let project = temporal_directory.path();
     let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
     let output_path = "validators/output.ak";
     fs::write(
-        "./addition.circom",
+        project.join("addition.circom"),
         addition_custom_circom_template_and_component(),
     )
     .unwrap();
     fs::write(
-        source_aiken_filename(),
+        project.join(source_aiken_filename()),
         original_aiken_code_with_custom_token(),
     )
     .expect("output file write failed");
 
     Command::new(aiken_zk_binary_path)
+        .current_dir(project)
         .arg(BUILD_COMMAND)
         .arg(source_aiken_filename())
         .arg(output_path)
         .output()
         .unwrap();
 
-    let compilation_result = Command::new("aiken").arg(BUILD_COMMAND).output().unwrap();
-    let file = fs::read_to_string(output_path).unwrap();
+    let compilation_result = Command::new("aiken")
+        .current_dir(project)
+        .arg(BUILD_COMMAND)
+        .output()
+        .unwrap();
+    let file = fs::read_to_string(project.join(output_path)).unwrap();
     let expected_line_replacement = "zk_verify_or_fail(redeemer, [Single(b), Single(5)])";
     let expected_line_declaration = "fn zk_verify_or_fail(";
     assert!(file.contains(expected_line_replacement));
     assert!(file.contains(expected_line_declaration));
-    assert!(Path::new("verification_key.zkey").exists());
-    assert!(Path::new("addition.circom").exists());
+    assert!(
+        project
+            .join("build/original_aiken_code/verification_key.zkey")
+            .exists()
+    );
+    assert!(project.join("addition.circom").exists());
 
     assert!(compilation_result.status.success());
-    assert!(Path::new("plutus.json").exists());
+    assert!(project.join("plutus.json").exists());
+}
+
+#[test]
+fn test_user_can_choose_the_build_directory_ptau_and_circuit_name() {
+    let temporal_directory = create_sandbox();
+    let project = temporal_directory.path();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    let output_path = "validators/output.ak";
+    create_original_aiken_file(project);
+    fs::rename(
+        project.join("ceremony.ptau"),
+        project.join("my_ceremony.ptau"),
+    )
+    .unwrap();
+
+    let build_result = Command::new(aiken_zk_binary_path)
+        .current_dir(project)
+        .arg(BUILD_COMMAND)
+        .arg(source_aiken_filename())
+        .arg(output_path)
+        .arg("--build-dir")
+        .arg("artifacts")
+        .arg("--ptau")
+        .arg("my_ceremony.ptau")
+        .arg("--circuit-name")
+        .arg("my_circuit")
+        .output()
+        .unwrap();
+
+    assert!(build_result.status.success());
+    assert!(project.join("artifacts/my_circuit.circom").exists());
+    assert!(project.join("artifacts/my_circuit.r1cs").exists());
+    assert!(project.join("artifacts/verification_key.zkey").exists());
+    assert!(project.join("artifacts/verification_key.json").exists());
+    assert!(!project.join("build").exists());
+}
+
+#[test]
+fn test_user_can_build_a_project_from_another_directory() {
+    let project_root = create_sandbox();
+    let working_directory = tempdir().unwrap();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    fs::write(
+        project_root.path().join(source_aiken_filename()),
+        original_aiken_code(),
+    )
+    .unwrap();
+
+    let build_result = Command::new(aiken_zk_binary_path)
+        .arg(BUILD_COMMAND)
+        .arg(source_aiken_filename())
+        .arg("validators/output.ak")
+        .arg("--project-root")
+        .arg(project_root.path())
+        .current_dir(working_directory.path())
+        .output()
+        .unwrap();
+
+    assert!(build_result.status.success());
+    assert!(project_root.path().join("validators/output.ak").exists());
+    assert!(
+        project_root
+            .path()
+            .join("build/original_aiken_code/verification_key.zkey")
+            .exists()
+    );
+    assert!(
+        project_root
+            .path()
+            .join("build/original_aiken_code/original_aiken_code.circom")
+            .exists()
+    );
+    assert_eq!(0, fs::read_dir(working_directory.path()).unwrap().count());
+}
+
+#[test]
+fn test_builds_with_the_same_seed_are_byte_identical() {
+    let temporal_directory = create_sandbox();
+    let project = temporal_directory.path();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    create_original_aiken_file(project);
+
+    for output_path in ["validators/first.ak", "validators/second.ak"] {
+        let build_result = Command::new(&aiken_zk_binary_path)
+            .current_dir(project)
+            .arg(BUILD_COMMAND)
+            .arg(source_aiken_filename())
+            .arg(output_path)
+            .arg("--seed")
+            .arg("my secret seed")
+            .output()
+            .unwrap();
+        assert!(build_result.status.success());
+    }
+
+    assert_eq!(
+        fs::read_to_string(project.join("validators/first.ak")).unwrap(),
+        fs::read_to_string(project.join("validators/second.ak")).unwrap()
+    );
+    let manifest = fs::read_to_string(project.join("validators/first.zk-manifest.json")).unwrap();
+    assert!(manifest.contains("\"entropy_source\": \"seed\""));
+    assert!(!manifest.contains("my secret seed"));
+    let manifest: BuildManifest = serde_json::from_str(&manifest).unwrap();
+    assert!(manifest.seed_commitment.unwrap().matches(b"my secret seed"));
 }
 
 #[test]
-#[serial]
 fn test_user_can_generate_an_aiken_proof() {
-    let _temporal_directory = create_sandbox_and_set_as_current_directory();
+    let temporal_directory = create_sandbox();
+    let project = temporal_directory.path();
     let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
     let circom_path = "my_program.circom";
-    let verification_key_path = "verification_key.zkey";
+    let verification_key_path = "build/verification_key.zkey";
     let inputs_path = "inputs.json";
     let output_path = "proof.ak";
-    create_circom_and_inputs_file(inputs_path);
+    create_circom_and_inputs_file(project, inputs_path);
 
     Command::new(aiken_zk_binary_path)
+        .current_dir(project)
         .arg("prove")
         .arg("aiken")
+        .arg("--circom")
         .arg(circom_path)
+        .arg("--zkey")
         .arg(verification_key_path)
         .arg(inputs_path)
         .arg(output_path)
         .output()
         .unwrap();
 
-    let file = File::open(output_path).unwrap();
+    let file = File::open(project.join(output_path)).unwrap();
     let lines: Vec<String> = io::BufReader::new(file)
         .lines()
         .collect::<Result<_, _>>()
@@ -115,27 +266,30 @@ fn test_user_can_generate_an_aiken_proof() {
 }
 
 #[test]
-#[serial]
 fn test_user_can_generate_a_meshjs_proof() {
-    let _temporal_directory = create_sandbox_and_set_as_current_directory();
+    let temporal_directory = create_sandbox();
+    let project = temporal_directory.path();
     let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
     let circom_path = "my_program.circom";
-    let verification_key_path = "verification_key.zkey";
+    let verification_key_path = "build/verification_key.zkey";
     let inputs_path = "inputs.json";
     let output_path = "zk_redeemer.ts";
-    create_circom_and_inputs_file(inputs_path);
+    create_circom_and_inputs_file(project, inputs_path);
 
     Command::new(aiken_zk_binary_path)
+        .current_dir(project)
         .arg("prove")
         .arg("meshjs")
+        .arg("--circom")
         .arg(circom_path)
+        .arg("--zkey")
         .arg(verification_key_path)
         .arg(inputs_path)
         .arg(output_path)
         .output()
         .unwrap();
 
-    let file = File::open(output_path).unwrap();
+    let file = File::open(project.join(output_path)).unwrap();
     let mut reader = io::BufReader::new(file);
 
     assert_text_matches(&mut reader, meshjs_file_prefix());
@@ -151,6 +305,199 @@ fn test_user_can_generate_a_meshjs_proof() {
     assert_text_matches(&mut reader, meshjs_file_suffix());
 }
 
+#[test]
+fn test_user_can_generate_a_proof_for_a_built_validator() {
+    let temporal_directory = create_sandbox();
+    let project = temporal_directory.path();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    let validator_path = "validators/output.ak";
+    create_original_aiken_file(project);
+    fs::write(
+        project.join("inputs.json"),
+        r#"{"a": "3", "b": "7", "c": "10"}"#,
+    )
+    .unwrap();
+    Command::new(&aiken_zk_binary_path)
+        .current_dir(project)
+        .arg(BUILD_COMMAND)
+        .arg(source_aiken_filename())
+        .arg(validator_path)
+        .output()
+        .unwrap();
+
+    for _ in 0..2 {
+        let prove_result = Command::new(&aiken_zk_binary_path)
+            .current_dir(project)
+            .arg("prove")
+            .arg("aiken")
+            .arg("--validator")
+            .arg(validator_path)
+            .arg("inputs.json")
+            .output()
+            .unwrap();
+        assert!(prove_result.status.success());
+    }
+
+    let proof = fs::read_to_string(project.join("proof.ak")).unwrap();
+    assert!(proof.starts_with("Proof {"));
+    assert!(
+        project
+            .join(
+                "build/original_aiken_code/original_aiken_code_js/original_aiken_code.wasm.sha256"
+            )
+            .exists()
+    );
+    assert!(
+        project
+            .join("build/original_aiken_code/public.json")
+            .exists()
+    );
+}
+
+#[test]
+fn test_proving_for_a_validator_whose_circuit_changed_fails() {
+    let directory = tempfile::tempdir().unwrap();
+    let working_directory = tempfile::tempdir().unwrap();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    fs::create_dir(directory.path().join("validators")).unwrap();
+    fs::write(directory.path().join("output.circom"), "edited circuit").unwrap();
+    fs::write(
+        directory.path().join("validators/output.zk-manifest.json"),
+        manifest_for_circuit("../output.circom"),
+    )
+    .unwrap();
+
+    let prove_result = Command::new(aiken_zk_binary_path)
+        .current_dir(working_directory.path())
+        .arg("prove")
+        .arg("json")
+        .arg("--validator")
+        .arg(directory.path().join("validators/output.ak"))
+        .arg("inputs.json")
+        .output()
+        .unwrap();
+
+    assert_eq!(Some(65), prove_result.status.code());
+    assert!(String::from_utf8_lossy(&prove_result.stderr).contains("changed after"));
+}
+
+#[test]
+fn test_proving_requires_a_validator_or_a_circuit() {
+    let directory = tempfile::tempdir().unwrap();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+
+    let prove_result = Command::new(aiken_zk_binary_path)
+        .current_dir(directory.path())
+        .arg("prove")
+        .arg("json")
+        .arg("inputs.json")
+        .output()
+        .unwrap();
+
+    assert!(!prove_result.status.success());
+    assert!(String::from_utf8_lossy(&prove_result.stderr).contains("--validator"));
+}
+
+#[test]
+fn test_user_can_get_a_template_of_the_circuit_inputs() {
+    let directory = tempfile::tempdir().unwrap();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    fs::write(
+        directory.path().join(source_aiken_filename()),
+        original_aiken_code(),
+    )
+    .unwrap();
+
+    let template_result = Command::new(aiken_zk_binary_path)
+        .current_dir(directory.path())
+        .arg("inputs")
+        .arg("template")
+        .arg(source_aiken_filename())
+        .output()
+        .unwrap();
+
+    assert!(template_result.status.success());
+    let template: Value = serde_json::from_slice(&template_result.stdout).unwrap();
+    assert_eq!(
+        json!({"first_addend": "<private>", "second_addend": "<public>", "sum": "<public>"}),
+        template
+    );
+}
+
+#[test]
+fn test_user_can_get_the_merkle_tree_checker_inputs_of_a_leaf() {
+    let directory = tempfile::tempdir().unwrap();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    fs::write(directory.path().join("leaves.json"), r#"["1", 2, "0x3"]"#).unwrap();
+
+    let merkle_result = Command::new(aiken_zk_binary_path)
+        .current_dir(directory.path())
+        .arg("inputs")
+        .arg("merkle")
+        .arg("leaves.json")
+        .arg("2")
+        .arg("--depth")
+        .arg("3")
+        .output()
+        .unwrap();
+
+    assert!(merkle_result.status.success());
+    let inputs: Value = serde_json::from_slice(&merkle_result.stdout).unwrap();
+    assert_eq!("3", inputs["leaf"]);
+    assert_eq!(json!(["0", "1", "0"]), inputs["pathIndices"]);
+    assert_eq!(3, inputs["pathElements"].as_array().unwrap().len());
+}
+
+#[test]
+fn test_user_can_sign_a_message_into_the_eddsa_verify_inputs() {
+    let directory = tempfile::tempdir().unwrap();
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+    fs::write(directory.path().join("private_key"), "123456789\n").unwrap();
+
+    let eddsa_result = Command::new(aiken_zk_binary_path)
+        .current_dir(directory.path())
+        .arg("inputs")
+        .arg("eddsa")
+        .arg("private_key")
+        .arg("42")
+        .output()
+        .unwrap();
+
+    assert!(eddsa_result.status.success());
+    let inputs: Value = serde_json::from_slice(&eddsa_result.stdout).unwrap();
+    assert_eq!("42", inputs["msg"]);
+    assert!(inputs["pub_key_x"].is_string());
+    assert!(inputs["s"].is_string());
+}
+
+#[test]
+fn test_user_can_get_the_membership_with_nullifier_inputs_of_a_member() {
+    let milestone_example_path = manifest_path() + "/milestone_example";
+    let aiken_zk_binary_path = manifest_path() + "/target/debug/aiken-zk";
+
+    let membership_result = Command::new(aiken_zk_binary_path)
+        .current_dir(&milestone_example_path)
+        .arg("inputs")
+        .arg("membership")
+        .arg("circuit_inputs/membership_commitments.json")
+        .arg("2")
+        .arg("circuit_inputs/membership_identity.json")
+        .arg("2026")
+        .output()
+        .unwrap();
+
+    assert!(membership_result.status.success());
+    let inputs: Value = serde_json::from_slice(&membership_result.stdout).unwrap();
+    let expected_inputs: Value = serde_json::from_str(
+        &fs::read_to_string(
+            milestone_example_path + "/circuit_inputs/inputs_membership_with_nullifier.json",
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(expected_inputs, inputs);
+}
+
 fn assert_line_matches(reader: &mut BufReader<File>, expected_line: &str) {
     let mut line_to_assert = String::new();
     reader.read_line(&mut line_to_assert).unwrap();
@@ -222,19 +569,56 @@ fn source_aiken_filename() -> &'static str {
     "original_aiken_code.ak"
 }
 
-fn create_original_aiken_file() {
-    fs::write(source_aiken_filename(), original_aiken_code()).expect("output file write failed");
+fn create_original_aiken_file(project: &Path) {
+    fs::write(project.join(source_aiken_filename()), original_aiken_code())
+        .expect("output file write failed");
 }
 
-fn create_circom_and_inputs_file(inputs_path: &str) {
-    let circom_path = "my_program.circom";
-    fs::write(circom_path, circom_file()).unwrap();
-    let circom_compiler = CircomCircuit::from(circom_path.to_string());
+fn manifest_for_circuit(circuit_path: &str) -> String {
+    json!({
+        "source_path": "original_aiken_code.ak",
+        "source_hash": "",
+        "output_path": "validators/output.ak",
+        "output_hash": "",
+        "build_dir": "build/output",
+        "ptau_path": "ceremony.ptau",
+        "ptau_hash": "",
+        "entropy_source": "random",
+        "seed_commitment": null,
+        "contribution_seed_commitments": [],
+        "circuits": [{
+            "proof_index": null,
+            "circuit_path": circuit_path,
+            "circuit_hash": "hash of the circuit at build time",
+            "r1cs_path": "build/output/output.r1cs",
+            "r1cs": {
+                "n_wires": 4,
+                "n_public_outputs": 0,
+                "n_public_inputs": 2,
+                "n_private_inputs": 1,
+                "n_labels": 4,
+                "n_constraints": 1
+            },
+            "n_public": 2,
+            "zkey_path": "build/output/verification_key.zkey",
+            "verification_key_path": "build/output/verification_key.json",
+            "verification_key_hash": "",
+            "public_inputs": []
+        }]
+    })
+    .to_string()
+}
+
+fn create_circom_and_inputs_file(project: &Path, inputs_path: &str) {
+    let build_options = BuildOptions::for_circuit(project.to_path_buf(), "my_program");
+    let circom_path = build_options.in_project("my_program.circom");
+    fs::write(&circom_path, circom_file()).unwrap();
+    let circom_compiler = CircomCircuit::from(circom_path.to_string_lossy().to_string()).unwrap();
     circom_compiler
-        .generate_verification_key(("a", "b"))
+        .generate_verification_key(("a", "b"), &build_options)
         .unwrap();
 
-    fs::write(inputs_path, inputs_json()).expect("output file write failed");
+    fs::write(project.join(inputs_path), inputs_json()).expect("output file write failed");
 }
 
 fn circom_file() -> String {
@@ -0,0 +1,59 @@
+use crate::jubjub::JubjubPoint;
+use ark_bls12_381::Fr;
+use num_bigint::BigUint;
+
+#[test]
+fn test_the_generator_is_on_the_curve_and_has_the_subgroup_order() {
+    let generator = JubjubPoint::generator();
+
+    assert!(generator.is_on_curve());
+    assert_ne!(JubjubPoint::identity(), generator);
+    assert_eq!(
+        JubjubPoint::identity(),
+        generator.mul(&JubjubPoint::subgroup_order())
+    );
+}
+
+#[test]
+fn test_the_identity_is_neutral_for_the_addition() {
+    let point = JubjubPoint::generator().mul(&BigUint::from(5u8));
+
+    assert_eq!(point, point.add(&JubjubPoint::identity()));
+    assert_eq!(point, JubjubPoint::identity().add(&point));
+}
+
+#[test]
+fn test_multiplying_by_a_scalar_adds_the_point_that_many_times() {
+    let generator = JubjubPoint::generator();
+
+    let tripled = generator.add(&generator).add(&generator);
+
+    assert_eq!(tripled, generator.mul(&BigUint::from(3u8)));
+    assert_eq!(
+        generator.mul(&BigUint::from(JubjubPoint::COFACTOR)),
+        generator.mul_by_cofactor()
+    );
+}
+
+#[test]
+fn test_points_outside_the_curve_are_rejected() {
+    let generator = JubjubPoint::generator();
+
+    assert_eq!(
+        None,
+        JubjubPoint::new(generator.x, generator.y + Fr::from(1u64))
+    );
+    assert_eq!(Some(generator), JubjubPoint::new(generator.x, generator.y));
+}
+
+// (0, -1) has order 2: a point of small order, which the cofactor sends to the identity.
+#[test]
+fn test_the_cofactor_clears_the_points_of_small_order() {
+    let point_of_order_two = JubjubPoint::new(Fr::from(0u64), -Fr::from(1u64)).unwrap();
+
+    assert_eq!(JubjubPoint::identity(), point_of_order_two.double());
+    assert_eq!(
+        JubjubPoint::identity(),
+        point_of_order_two.mul_by_cofactor()
+    );
+}
@@ -0,0 +1,149 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::membership::{MembershipError, MembershipIdentity};
+use crate::merkle_tree::{MerkleProof, MerkleTree};
+use crate::poseidon_bls12_381::PoseidonBls12381;
+use crate::tests::utils::{calculate_witness, create_sandbox, template_witness_calculator};
+use crate::witness_calculator::WitnessError;
+use ark_bls12_381::Fr;
+use num_bigint::BigUint;
+use serde_json::{Value, json};
+use std::str::FromStr;
+
+#[test]
+fn test_the_commitment_and_the_nullifier_hash_keys_derived_from_the_secret() {
+    let identity = identity(33, 333);
+    let key = |domain: u64| PoseidonBls12381::hash_left_right(Fr::from(33u64), Fr::from(domain));
+
+    assert_eq!(
+        PoseidonBls12381::hash_left_right(key(1), Fr::from(333u64)),
+        identity.commitment()
+    );
+    assert_eq!(
+        PoseidonBls12381::hash_left_right(key(2), Fr::from(2026u64)),
+        identity.nullifier(Fr::from(2026u64))
+    );
+}
+
+#[test]
+fn test_a_nullifier_is_not_a_commitment_even_with_the_trapdoor_as_external_nullifier() {
+    let identity = identity(33, 333);
+
+    assert_ne!(identity.commitment(), identity.nullifier(identity.trapdoor));
+}
+
+#[test]
+fn test_the_nullifier_depends_on_the_external_nullifier_but_not_on_the_trapdoor() {
+    assert_eq!(
+        identity(33, 333).nullifier(Fr::from(2026u64)),
+        identity(33, 444).nullifier(Fr::from(2026u64))
+    );
+    assert_ne!(
+        identity(33, 333).nullifier(Fr::from(2026u64)),
+        identity(33, 333).nullifier(Fr::from(2027u64))
+    );
+}
+
+#[test]
+fn test_the_identity_is_read_from_json() {
+    let identity = MembershipIdentity::from_json(&json!({"secret": "0x21", "trapdoor": 333}));
+
+    assert_eq!(Ok(self::identity(33, 333)), identity);
+}
+
+#[test]
+fn test_an_identity_without_trapdoor_is_rejected() {
+    let identity = MembershipIdentity::from_json(&json!({"secret": "33"}));
+
+    assert_eq!(
+        Err(MembershipError::InvalidIdentity {
+            reason: "trapdoor is missing".to_string()
+        }),
+        identity
+    );
+}
+
+#[test]
+fn test_the_inputs_prove_the_commitment_is_in_the_tree() {
+    let members = [identity(11, 111), identity(22, 222), identity(33, 333)];
+    let tree = tree_of(&members);
+
+    let inputs = members[2]
+        .to_inputs_json(&tree, 2, Fr::from(2026u64))
+        .unwrap();
+
+    let proof = MerkleProof {
+        leaf: members[2].commitment(),
+        root: tree.root(),
+        path_elements: field_elements(&inputs["pathElements"]),
+        path_indices: vec![0, 1],
+    };
+    assert_eq!(tree.root(), proof.computed_root());
+    assert_eq!(json!(["0", "1"]), inputs["pathIndices"]);
+    assert_eq!(json!("2026"), inputs["external_nullifier"]);
+    assert_eq!(json!("33"), inputs["secret"]);
+    assert_eq!(
+        members[2].nullifier(Fr::from(2026u64)),
+        field_elements(&json!([inputs["nullifier"]]))[0]
+    );
+}
+
+#[test]
+fn test_the_inputs_of_another_leaf_are_rejected() {
+    let members = [identity(11, 111), identity(22, 222)];
+    let tree = tree_of(&members);
+
+    let inputs = members[1].to_inputs_json(&tree, 0, Fr::from(2026u64));
+
+    assert!(matches!(
+        inputs,
+        Err(AikenZkError::Membership(MembershipError::NotAMember {
+            index: 0
+        }))
+    ));
+}
+
+#[test]
+fn test_the_template_accepts_the_inputs_and_hashes_like_rust() {
+    let sandbox = create_sandbox();
+    let mut witness_calculator = template_witness_calculator(
+        sandbox.path(),
+        "membership_with_nullifier",
+        "MembershipWithNullifier(2)",
+    );
+    let members = [identity(11, 111), identity(22, 222), identity(33, 333)];
+    let inputs = members[2]
+        .to_inputs_json(&tree_of(&members), 2, Fr::from(2026u64))
+        .unwrap();
+
+    let mut other_nullifier = inputs.clone();
+    other_nullifier["nullifier"] = inputs["external_nullifier"].clone();
+
+    assert!(calculate_witness(&mut witness_calculator, inputs).is_ok());
+    assert!(matches!(
+        calculate_witness(&mut witness_calculator, other_nullifier),
+        Err(WitnessError::Failed { .. })
+    ));
+}
+
+// ---------- AUX ---------- //
+
+fn identity(secret: u64, trapdoor: u64) -> MembershipIdentity {
+    MembershipIdentity {
+        secret: Fr::from(secret),
+        trapdoor: Fr::from(trapdoor),
+    }
+}
+
+fn tree_of(members: &[MembershipIdentity]) -> MerkleTree {
+    let commitments: Vec<Fr> = members.iter().map(|member| member.commitment()).collect();
+    MerkleTree::from_leaves(&commitments, None).unwrap()
+}
+
+fn field_elements(values: &Value) -> Vec<Fr> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| Fr::from(BigUint::from_str(value.as_str().unwrap()).unwrap()))
+        .collect()
+}
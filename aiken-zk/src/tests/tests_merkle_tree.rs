@@ -0,0 +1,144 @@
+use crate::merkle_tree::{MerkleTree, MerkleTreeError};
+use crate::poseidon_bls12_381::PoseidonBls12381;
+use ark_bls12_381::Fr;
+use serde_json::json;
+
+#[test]
+fn test_the_root_of_two_leaves_is_their_hash() {
+    let tree = MerkleTree::from_leaves(&leaves(&[1, 2]), None).unwrap();
+
+    assert_eq!(1, tree.depth());
+    assert_eq!(
+        PoseidonBls12381::hash_left_right(Fr::from(1u64), Fr::from(2u64)),
+        tree.root()
+    );
+}
+
+#[test]
+fn test_the_tree_is_as_shallow_as_the_leaves_allow() {
+    assert_eq!(
+        1,
+        MerkleTree::from_leaves(&leaves(&[1]), None)
+            .unwrap()
+            .depth()
+    );
+    assert_eq!(
+        2,
+        MerkleTree::from_leaves(&leaves(&[1, 2, 3]), None)
+            .unwrap()
+            .depth()
+    );
+    assert_eq!(
+        3,
+        MerkleTree::from_leaves(&leaves(&[1; 5]), None)
+            .unwrap()
+            .depth()
+    );
+}
+
+#[test]
+fn test_missing_leaves_are_zeros() {
+    let padded_tree = MerkleTree::from_leaves(&leaves(&[1, 2, 3]), Some(3)).unwrap();
+    let full_tree = MerkleTree::from_leaves(&leaves(&[1, 2, 3, 0, 0, 0, 0, 0]), None).unwrap();
+
+    assert_eq!(full_tree.root(), padded_tree.root());
+}
+
+#[test]
+fn test_the_proof_of_every_leaf_leads_to_the_root() {
+    let tree = MerkleTree::from_leaves(&leaves(&[5, 6, 7, 8, 9]), None).unwrap();
+
+    for index in 0..5 {
+        let proof = tree.proof(index).unwrap();
+        assert_eq!(Fr::from(5 + index as u64), proof.leaf);
+        assert_eq!(3, proof.path_elements.len());
+        assert_eq!(tree.root(), proof.computed_root());
+    }
+}
+
+#[test]
+fn test_the_path_indices_are_the_bits_of_the_leaf_index() {
+    let tree = MerkleTree::from_leaves(&leaves(&[1; 8]), None).unwrap();
+
+    let proof = tree.proof(6).unwrap();
+
+    assert_eq!(vec![0, 1, 1], proof.path_indices);
+}
+
+#[test]
+fn test_the_proof_is_written_as_the_merkle_tree_checker_inputs() {
+    let tree = MerkleTree::from_leaves(&leaves(&[1, 2]), None).unwrap();
+
+    let inputs = tree.proof(1).unwrap().to_inputs_json();
+
+    assert_eq!(json!("2"), inputs["leaf"]);
+    assert_eq!(json!(["1"]), inputs["pathElements"]);
+    assert_eq!(json!(["1"]), inputs["pathIndices"]);
+    assert!(inputs["root"].is_string());
+}
+
+#[test]
+fn test_leaves_are_read_like_the_numbers_of_the_inputs() {
+    let leaves = MerkleTree::leaves_from_json(&json!(["1", 2, "0x3"])).unwrap();
+
+    assert_eq!(vec![Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)], leaves);
+}
+
+#[test]
+fn test_leaves_must_be_a_list_of_field_elements() {
+    assert!(matches!(
+        MerkleTree::leaves_from_json(&json!({"leaves": ["1"]})),
+        Err(MerkleTreeError::NotAList { .. })
+    ));
+    assert_eq!(
+        Err(MerkleTreeError::InvalidLeaf {
+            index: 1,
+            value: "-2".to_string()
+        }),
+        MerkleTree::leaves_from_json(&json!(["1", "-2"]))
+    );
+    assert!(matches!(
+        MerkleTree::leaves_from_json(&json!([
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+        ])),
+        Err(MerkleTreeError::InvalidLeaf { index: 0, .. })
+    ));
+}
+
+#[test]
+fn test_building_a_tree_fails_without_room_for_the_leaves() {
+    assert_eq!(
+        Err(MerkleTreeError::NoLeaves),
+        MerkleTree::from_leaves(&[], None)
+    );
+    assert_eq!(
+        Err(MerkleTreeError::ZeroDepth),
+        MerkleTree::from_leaves(&leaves(&[1]), Some(0))
+    );
+    assert_eq!(
+        Err(MerkleTreeError::TooManyLeaves {
+            leaves: 3,
+            depth: 1
+        }),
+        MerkleTree::from_leaves(&leaves(&[1, 2, 3]), Some(1))
+    );
+}
+
+#[test]
+fn test_there_is_no_proof_of_a_padding_leaf() {
+    let tree = MerkleTree::from_leaves(&leaves(&[1, 2, 3]), None).unwrap();
+
+    assert_eq!(
+        Err(MerkleTreeError::UnknownLeaf {
+            index: 3,
+            leaves: 3
+        }),
+        tree.proof(3)
+    );
+}
+
+// ---------- AUX ---------- //
+
+fn leaves(values: &[u64]) -> Vec<Fr> {
+    values.iter().map(|value| Fr::from(*value)).collect()
+}
@@ -0,0 +1,361 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::compiler::lexer;
+use crate::compiler::offchain_block::{BlockExpression, BlockStatement, OffchainBlock};
+use crate::compiler::token_zk::TokenZK as Token;
+use crate::tests::token_examples::single_variable_token;
+use crate::zk_examples::{InputVisibility, InputZK, OffchainArgumentError, ZkExample};
+use aiken_lang::ast::Span;
+
+#[test]
+fn test_lexer_translates_an_offchain_block() {
+    let program = "offchain {\n    let sum = a + b\n    assert sum * priv c == 10\n}";
+
+    assert_eq!(
+        vec![
+            BlockStatement::Let {
+                name: "sum".to_string(),
+                value: addition(variable("a"), variable("b")),
+            },
+            BlockStatement::Assert {
+                condition: equality(
+                    multiplication(
+                        variable("sum"),
+                        BlockExpression::Variable {
+                            name: "c".to_string(),
+                            visibility: Some(InputVisibility::Private),
+                        }
+                    ),
+                    int(10)
+                ),
+            },
+        ],
+        lex_block(program).statements
+    );
+}
+
+#[test]
+fn test_block_inputs_are_the_variables_it_reads_in_order() {
+    let program = "offchain {\n    let sum = b + a\n    assert sum * priv c == pub d\n}";
+
+    let inputs: Vec<(String, InputZK)> = lex_block(program)
+        .inputs
+        .into_iter()
+        .map(|block_input| (block_input.name, block_input.input))
+        .collect();
+    assert_eq!(
+        vec![
+            ("b".to_string(), public_input("b")),
+            ("a".to_string(), public_input("a")),
+            ("c".to_string(), private_input()),
+            ("d".to_string(), public_input("d")),
+        ],
+        inputs
+    );
+}
+
+#[test]
+fn test_a_visibility_marker_applies_to_every_read_of_the_variable() {
+    let program = "offchain { assert a * a == priv a }";
+
+    let block = lex_block(program);
+    assert_eq!(1, block.inputs.len());
+    assert_eq!(private_input(), block.inputs[0].input);
+}
+
+#[test]
+fn test_block_operators_have_the_usual_precedence() {
+    let program = "offchain { assert a - b * -c + 2 == (a - b) * c }";
+
+    assert_eq!(
+        vec![BlockStatement::Assert {
+            condition: equality(
+                addition(
+                    subtraction(
+                        variable("a"),
+                        multiplication(variable("b"), negation(variable("c")))
+                    ),
+                    int(2)
+                ),
+                multiplication(subtraction(variable("a"), variable("b")), variable("c"))
+            ),
+        }],
+        lex_block(program).statements
+    );
+}
+
+#[test]
+fn test_block_can_have_comments() {
+    let program = "offchain {\n    // The total of the order\n    let total = price * amount\n\n    assert total == paid // nothing is left to pay\n}";
+
+    assert_eq!(2, lex_block(program).statements.len());
+}
+
+#[test]
+fn test_lexer_goes_on_after_the_block() {
+    let program = "expect _ = offchain { assert a == b }\nTrue";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+
+    let (offchain_token, span) = tokens
+        .iter()
+        .find(|(token, _)| matches!(token, Token::Offchain { .. }))
+        .unwrap();
+    assert!(matches!(
+        offchain_token,
+        Token::Offchain {
+            example: ZkExample::ExpressionBlock { .. }
+        }
+    ));
+    assert_eq!(Span::create(11, 26), *span);
+    assert_eq!(
+        Token::UpName {
+            name: "True".to_string()
+        },
+        tokens.last().unwrap().0
+    );
+}
+
+// ----------- CIRCOM ----------- //
+
+#[test]
+fn test_block_template_declares_the_inputs_and_asserts_the_equality() {
+    let program = "offchain { assert (a + b) * priv c == d }";
+
+    let expected_template = r#"template OffchainBlock() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal input d;
+    (a + b) * c === d;
+}"#;
+    assert_eq!(
+        expected_template,
+        lex_block(program).circom_template("OffchainBlock")
+    );
+}
+
+#[test]
+fn test_block_template_assigns_let_bindings_to_signals() {
+    let program =
+        "offchain {\n    let total = price * amount\n    assert total - discount == paid\n}";
+
+    let expected_template = r#"template OffchainBlock() {
+    signal input price;
+    signal input amount;
+    signal input discount;
+    signal input paid;
+    signal total <== price * amount;
+    total - discount === paid;
+}"#;
+    assert_eq!(
+        expected_template,
+        lex_block(program).circom_template("OffchainBlock")
+    );
+}
+
+#[test]
+fn test_block_template_keeps_constraints_quadratic() {
+    let program = "offchain { assert a * b * c + d * e == f * g }";
+
+    let expected_template = r#"template OffchainBlock() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal input d;
+    signal input e;
+    signal input f;
+    signal input g;
+    signal _aux0 <== a * b;
+    signal _aux1 <== d * e;
+    signal _aux2 <== f * g;
+    _aux0 * c + _aux1 === _aux2;
+}"#;
+    assert_eq!(
+        expected_template,
+        lex_block(program).circom_template("OffchainBlock")
+    );
+}
+
+#[test]
+fn test_block_template_does_not_need_signals_to_multiply_by_constants() {
+    let program = "offchain { assert 2 * (a - 3) * -b == c }";
+
+    let expected_template = r#"template OffchainBlock() {
+    signal input a;
+    signal input b;
+    signal input c;
+    2 * (a - 3) * (-b) === c;
+}"#;
+    assert_eq!(
+        expected_template,
+        lex_block(program).circom_template("OffchainBlock")
+    );
+}
+
+#[test]
+fn test_block_template_computes_equalities_used_as_numbers() {
+    let program =
+        "offchain {\n    let is_full = total == capacity\n    assert is_full + priv spare == 1\n}";
+
+    let expected_template = r#"template OffchainBlock() {
+    signal input total;
+    signal input capacity;
+    signal input spare;
+    signal _aux0_inverse <-- (total - capacity) != 0 ? 1 / (total - capacity) : 0;
+    signal _aux0 <== 1 - (total - capacity) * _aux0_inverse;
+    (total - capacity) * _aux0 === 0;
+    signal is_full <== _aux0;
+    is_full + spare === 1;
+}"#;
+    assert_eq!(
+        expected_template,
+        lex_block(program).circom_template("OffchainBlock")
+    );
+}
+
+#[test]
+fn test_block_template_requires_other_assertions_to_be_one() {
+    let program = "offchain { assert (a == b) + (a == c) }";
+
+    let template = lex_block(program).circom_template("OffchainBlock");
+    assert!(template.contains("    _aux0 + _aux1 === 1;\n"));
+}
+
+// ----------- ERRORS ----------- //
+
+#[test]
+fn test_block_variables_cannot_be_both_public_and_private() {
+    assert_block_error(
+        "offchain { assert pub a == priv a }",
+        OffchainArgumentError::ConflictingVisibility {
+            name: "a".to_string(),
+        },
+    );
+}
+
+#[test]
+fn test_block_cannot_bind_a_variable_it_already_read() {
+    assert_block_error(
+        "offchain {\n    let a = b\n    let b = a\n    assert a == b\n}",
+        OffchainArgumentError::RedefinedName {
+            name: "b".to_string(),
+        },
+    );
+}
+
+#[test]
+fn test_block_let_bindings_cannot_have_visibility() {
+    assert_block_error(
+        "offchain {\n    let a = b * c\n    assert priv a == 1\n}",
+        OffchainArgumentError::LetBindingWithVisibility {
+            name: "a".to_string(),
+        },
+    );
+}
+
+#[test]
+fn test_block_variables_cannot_be_circom_keywords() {
+    assert_block_error(
+        "offchain { assert signal == 1 }",
+        OffchainArgumentError::ReservedName {
+            name: "signal".to_string(),
+        },
+    );
+    assert_block_error(
+        "offchain { assert _aux0 == 1 }",
+        OffchainArgumentError::ReservedName {
+            name: "_aux0".to_string(),
+        },
+    );
+}
+
+#[test]
+fn test_block_must_assert_something() {
+    assert_block_error(
+        "offchain { let a = b }",
+        OffchainArgumentError::BlockWithoutAssertion,
+    );
+}
+
+// ---------- AUX ---------- //
+
+fn lex_block(program: &str) -> OffchainBlock {
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    match &tokens[0].0 {
+        Token::Offchain {
+            example: ZkExample::ExpressionBlock { block },
+        } => block.clone(),
+        token => panic!("Unexpected token: {}", token),
+    }
+}
+
+fn assert_block_error(program: &str, expected_reason: OffchainArgumentError) {
+    match lexer::Lexer::new().run(program) {
+        Err(AikenZkError::InvalidOffchainArgument { reason, span }) => {
+            assert_eq!(expected_reason, reason);
+            assert_eq!(Span::create(0, program.len()), span);
+        }
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("The program should not lex"),
+    }
+}
+
+fn public_input(name: &str) -> InputZK {
+    InputZK {
+        visibility: InputVisibility::Public,
+        token: single_variable_token(name),
+    }
+}
+
+fn private_input() -> InputZK {
+    InputZK {
+        visibility: InputVisibility::Private,
+        token: None,
+    }
+}
+
+fn int(value: u32) -> BlockExpression {
+    BlockExpression::Int {
+        value: value.to_string(),
+    }
+}
+
+fn variable(name: &str) -> BlockExpression {
+    BlockExpression::Variable {
+        name: name.to_string(),
+        visibility: None,
+    }
+}
+
+fn negation(operand: BlockExpression) -> BlockExpression {
+    BlockExpression::Negation {
+        operand: Box::new(operand),
+    }
+}
+
+fn addition(lhs: BlockExpression, rhs: BlockExpression) -> BlockExpression {
+    BlockExpression::Addition {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+fn subtraction(lhs: BlockExpression, rhs: BlockExpression) -> BlockExpression {
+    BlockExpression::Subtraction {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+fn multiplication(lhs: BlockExpression, rhs: BlockExpression) -> BlockExpression {
+    BlockExpression::Multiplication {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+fn equality(lhs: BlockExpression, rhs: BlockExpression) -> BlockExpression {
+    BlockExpression::Equality {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
@@ -0,0 +1,179 @@
+use crate::compressed_groth16_proof_bls12_381::{
+    CompressedGroth16ProofBls12_381, ProofCompressionError,
+};
+use crate::point_compressor_bls12_381::{PointCompressionError, PointCompressorBls12_381};
+use serde_json::{Value, json};
+
+#[test]
+fn test_g1_generator_is_compressed_without_the_sign_flag() {
+    let compressed_point = PointCompressorBls12_381::compress_g1(&g1_generator()).unwrap();
+
+    assert_eq!(compressed_g1_generator(), compressed_point);
+}
+
+#[test]
+fn test_negated_g1_generator_is_compressed_with_the_sign_flag() {
+    let compressed_point = PointCompressorBls12_381::compress_g1(&negated_g1_generator()).unwrap();
+
+    assert_eq!(compressed_negated_g1_generator(), compressed_point);
+}
+
+#[test]
+fn test_g2_generator_is_compressed_with_the_imaginary_part_first() {
+    let compressed_point = PointCompressorBls12_381::compress_g2(&g2_generator()).unwrap();
+
+    assert_eq!(compressed_g2_generator(), compressed_point);
+}
+
+#[test]
+fn test_g1_point_at_infinity_cannot_be_compressed() {
+    let point_at_infinity = json!(["0", "1", "0"]);
+
+    let result = PointCompressorBls12_381::compress_g1(&point_at_infinity);
+
+    assert_eq!(
+        Err(PointCompressionError::PointAtInfinity { curve: "G1" }),
+        result
+    );
+}
+
+#[test]
+fn test_g2_point_at_infinity_cannot_be_compressed() {
+    let point_at_infinity = json!([["0", "0"], ["1", "0"], ["0", "0"]]);
+
+    let result = PointCompressorBls12_381::compress_g2(&point_at_infinity);
+
+    assert_eq!(
+        Err(PointCompressionError::PointAtInfinity { curve: "G2" }),
+        result
+    );
+}
+
+#[test]
+fn test_g1_point_off_the_curve_cannot_be_compressed() {
+    let point_off_the_curve = json!(["1", "2", "1"]);
+
+    let result = PointCompressorBls12_381::compress_g1(&point_off_the_curve);
+
+    assert_eq!(
+        Err(PointCompressionError::NotOnCurve { curve: "G1" }),
+        result
+    );
+}
+
+#[test]
+fn test_coordinates_out_of_the_base_field_are_rejected() {
+    let base_field_modulus = "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787";
+    let point = json!([base_field_modulus, "2", "1"]);
+
+    let result = PointCompressorBls12_381::compress_g1(&point);
+
+    assert!(matches!(
+        result,
+        Err(PointCompressionError::MalformedPoint { curve: "G1", .. })
+    ));
+}
+
+#[test]
+fn test_snarkjs_proof_is_compressed_point_by_point() {
+    let proof = json!({
+        "pi_a": g1_generator(),
+        "pi_b": g2_generator(),
+        "pi_c": negated_g1_generator(),
+        "protocol": "groth16",
+        "curve": "bls12381"
+    });
+
+    let compressed_proof = CompressedGroth16ProofBls12_381::from_snarkjs_proof(&proof).unwrap();
+
+    assert_eq!(
+        compressed_g1_generator(),
+        compressed_proof.pi_a_as_byte_string()
+    );
+    assert_eq!(
+        compressed_g2_generator(),
+        compressed_proof.pi_b_as_byte_string()
+    );
+    assert_eq!(
+        compressed_negated_g1_generator(),
+        compressed_proof.pi_c_as_byte_string()
+    );
+}
+
+#[test]
+fn test_snarkjs_proof_without_pi_b_cannot_be_compressed() {
+    let proof = json!({
+        "pi_a": g1_generator(),
+        "pi_c": g1_generator(),
+    });
+
+    let result = CompressedGroth16ProofBls12_381::from_snarkjs_proof(&proof);
+
+    assert!(matches!(
+        result,
+        Err(ProofCompressionError::MissingField("pi_b"))
+    ));
+}
+
+#[test]
+fn test_snarkjs_proof_with_a_point_at_infinity_reports_the_offending_field() {
+    let proof = json!({
+        "pi_a": g1_generator(),
+        "pi_b": g2_generator(),
+        "pi_c": ["0", "1", "0"],
+    });
+
+    let result = CompressedGroth16ProofBls12_381::from_snarkjs_proof(&proof);
+
+    assert!(matches!(
+        result,
+        Err(ProofCompressionError::InvalidPoint {
+            field: "pi_c",
+            source: PointCompressionError::PointAtInfinity { .. }
+        })
+    ));
+}
+
+// ---------- AUX ---------- //
+
+fn g1_generator() -> Value {
+    json!([
+        "3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507",
+        "1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569",
+        "1"
+    ])
+}
+
+fn negated_g1_generator() -> Value {
+    json!([
+        "3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507",
+        "2662903010277190920397318445793982934971948944000658264905514399707520226534504357969962973775649129045502516118218",
+        "1"
+    ])
+}
+
+fn g2_generator() -> Value {
+    json!([
+        [
+            "352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160",
+            "3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758"
+        ],
+        [
+            "1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905",
+            "927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582"
+        ],
+        ["1", "0"]
+    ])
+}
+
+fn compressed_g1_generator() -> &'static str {
+    "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+}
+
+fn compressed_negated_g1_generator() -> &'static str {
+    "b7f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+}
+
+fn compressed_g2_generator() -> &'static str {
+    "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"
+}
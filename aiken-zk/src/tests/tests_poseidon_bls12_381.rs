@@ -0,0 +1,47 @@
+use crate::circom_templates::CircomTemplates;
+use crate::poseidon_bls12_381::PoseidonBls12381;
+use crate::tests::utils::field_elements_in;
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+#[test]
+fn test_the_permutation_matches_the_reference_test_vector() {
+    let parameters = PoseidonBls12381::parameters();
+
+    let state = parameters.permute([Fr::from(0u64), Fr::from(1u64), Fr::from(2u64)]);
+
+    assert_eq!(
+        [
+            "28ce19420fc246a05553ad1e8c98f5c9d67166be2c18e9e4cb4b4e317dd2a78a",
+            "51f3e312c95343a896cfd8945ea82ba956c1118ce9b9859b6ea56637b4b1ddc4",
+            "3b2b69139b235626a0bfb56c9527ae66a7bf486ad8c11c14d1da0c69bbe0f79a",
+        ],
+        state.map(|element| format!("{:064x}", BigUint::from(element.into_bigint())))
+    );
+}
+
+#[test]
+fn test_hashing_left_and_right_is_the_first_element_of_the_permutation() {
+    let (left, right) = (Fr::from(1u64), Fr::from(2u64));
+
+    let hash = PoseidonBls12381::hash_left_right(left, right);
+
+    assert_eq!(
+        PoseidonBls12381::parameters().permute([Fr::from(0u64), left, right])[0],
+        hash
+    );
+    assert_ne!(PoseidonBls12381::hash_left_right(right, left), hash);
+}
+
+#[test]
+fn test_the_poseidon_template_uses_the_same_parameters() {
+    let parameters = PoseidonBls12381::parameters();
+    let mut expected_constants = parameters.round_constants().to_vec();
+    expected_constants.extend(parameters.mds().iter().flatten());
+
+    let template =
+        String::from_utf8(CircomTemplates::source("poseidon_bls12_381").unwrap()).unwrap();
+
+    assert_eq!(expected_constants, field_elements_in(&template));
+}
@@ -1,23 +1,15 @@
 use crate::circom_circuit::CircomCircuit;
+use crate::compiler::build_options::BuildOptions;
+use crate::compressed_groth16_proof_bls12_381::CompressedGroth16ProofBls12_381;
 use crate::presenter::compressed_groth16_proof_bls12_381_to_aiken_presenter::CompressedGroth16ProofBls12_381ToAikenPresenter;
+use crate::presenter::compressed_groth16_proof_bls12_381_to_json_presenter::CompressedGroth16ProofBls12_381ToJsonPresenter;
 use crate::presenter::meshjs_zk_redeemer_presenter::MeshJsZKRedeemerPresenter;
-use crate::tests::utils::create_sandbox_and_set_as_current_directory;
-use serial_test::serial;
+use crate::tests::utils::create_sandbox;
 use std::fs;
-use crate::presenter::compressed_groth16_proof_bls12_381_to_json_presenter::CompressedGroth16ProofBls12_381ToJsonPresenter;
 
 #[test]
-#[serial]
 fn test_aiken_proof_is_correctly_presented() {
-    let _temporal_directory = create_sandbox_and_set_as_current_directory();
-    let circom_path = "my_program.circom";
-    let verification_key_path = "my_verification_key.zkey";
-    let inputs_path = "inputs.json";
-
-    create_circom_and_inputs_file();
-
-    let circuit = CircomCircuit::from(circom_path.to_string());
-    let proof = circuit.generate_groth16_proof(verification_key_path, inputs_path);
+    let proof = addition_proof_in_sandbox();
 
     let aiken_presenter = CompressedGroth16ProofBls12_381ToAikenPresenter::new(proof.clone());
 
@@ -37,17 +29,8 @@ fn test_aiken_proof_is_correctly_presented() {
 }
 
 #[test]
-#[serial]
 fn test_meshjs_library_is_correctly_presented() {
-    let _temporal_directory = create_sandbox_and_set_as_current_directory();
-    let circom_path = "my_program.circom";
-    let verification_key_path = "my_verification_key.zkey";
-    let inputs_path = "inputs.json";
-
-    create_circom_and_inputs_file();
-
-    let circuit = CircomCircuit::from(circom_path.to_string());
-    let proof = circuit.generate_groth16_proof(verification_key_path, inputs_path);
+    let proof = addition_proof_in_sandbox();
     let mesh_js_presenter = MeshJsZKRedeemerPresenter::new_for_proof(proof.clone());
     let presented_meshjs_library = mesh_js_presenter.present();
 
@@ -73,22 +56,14 @@ fn test_meshjs_library_is_correctly_presented() {
 }
 
 #[test]
-#[serial]
 fn test_json_proof_is_correctly_presented() {
-    let _temporal_directory = create_sandbox_and_set_as_current_directory();
-    let circom_path = "my_program.circom";
-    let verification_key_path = "my_verification_key.zkey";
-    let inputs_path = "inputs.json";
-
-    create_circom_and_inputs_file();
-
-    let circuit = CircomCircuit::from(circom_path.to_string());
-    let proof = circuit.generate_groth16_proof(verification_key_path, inputs_path);
+    let proof = addition_proof_in_sandbox();
 
     let json_presenter = CompressedGroth16ProofBls12_381ToJsonPresenter::new(proof.clone());
 
     let json_proof = json_presenter.present();
-    let expected_presented_proof = format!("{{
+    let expected_presented_proof = format!(
+        "{{
 \t\"piA\": \"{}\",
 \t\"piB\": \"{}\",
 \t\"piC\": \"{}\"
@@ -101,9 +76,19 @@ fn test_json_proof_is_correctly_presented() {
     assert_eq!(expected_presented_proof, json_proof);
 }
 
-fn create_circom_and_inputs_file() {
-    fs::write("my_program.circom", circom_file()).expect("output file write failed");
-    fs::write("inputs.json", inputs_json()).expect("output file write failed");
+fn addition_proof_in_sandbox() -> CompressedGroth16ProofBls12_381 {
+    let temporal_directory = create_sandbox();
+    let build_options =
+        BuildOptions::for_circuit(temporal_directory.path().to_path_buf(), "my_program");
+    let circom_path = build_options.in_project("my_program.circom");
+    fs::write(&circom_path, circom_file()).expect("output file write failed");
+    fs::write(build_options.in_project("inputs.json"), inputs_json())
+        .expect("output file write failed");
+
+    CircomCircuit::from(circom_path.to_string_lossy().to_string())
+        .unwrap()
+        .generate_groth16_proof(&build_options, "my_verification_key.zkey", "inputs.json")
+        .unwrap()
 }
 
 fn circom_file() -> String {
@@ -0,0 +1,138 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::build_manifest::EntropySource;
+use crate::compiler::build_options::BuildOptions;
+use crate::project::Project;
+use crate::tests::utils::create_sandbox;
+use std::path::Path;
+use std::{env, fs};
+
+#[test]
+fn test_project_returns_the_validator_circuit_and_verification_key() {
+    let project_root = create_sandbox();
+    let project = sandbox_project(project_root.path());
+
+    let build_output = project
+        .build(
+            Path::new("addition.ak"),
+            &offchain_addition_source(),
+            Path::new("validators/addition.ak"),
+        )
+        .unwrap();
+
+    assert!(
+        build_output
+            .aiken_src
+            .contains("zk_verify_or_fail(redeemer, [Single(b), Single(10)])")
+    );
+    assert_eq!(1, build_output.circuits.len());
+    assert!(build_output.circuits[0].circom_src.contains("Addition()"));
+    assert_eq!(
+        "groth16",
+        build_output.circuits[0].verification_key["protocol"]
+    );
+    assert_eq!(EntropySource::Random, build_output.manifest.entropy_source);
+    assert_eq!(
+        build_output.circuits[0].circuit.public_inputs,
+        build_output.manifest.circuits[0].public_inputs
+    );
+    assert!(
+        build_output.circuits[0]
+            .circuit
+            .circuit_path
+            .starts_with(&project_root.path().to_string_lossy().to_string())
+    );
+    assert!(!project_root.path().join("validators/addition.ak").exists());
+}
+
+#[test]
+fn test_build_output_writes_the_validator_and_its_manifest() {
+    let project_root = create_sandbox();
+    fs::create_dir(project_root.path().join("validators")).unwrap();
+    let project = sandbox_project(project_root.path());
+    let build_output = project
+        .build(
+            Path::new("addition.ak"),
+            &offchain_addition_source(),
+            Path::new("validators/addition.ak"),
+        )
+        .unwrap();
+
+    build_output.write().unwrap();
+
+    assert_eq!(
+        build_output.aiken_src,
+        fs::read_to_string(project_root.path().join("validators/addition.ak")).unwrap()
+    );
+    assert!(
+        project_root
+            .path()
+            .join("validators/addition.zk-manifest.json")
+            .exists()
+    );
+}
+
+#[test]
+fn test_seeded_projects_have_a_reproducible_setup() {
+    let build_options = BuildOptions::for_source(env::temp_dir(), Path::new("addition.ak"));
+
+    let random_project = Project::new(build_options.clone());
+    let seeded_project = Project::new(build_options).with_seed(b"seed");
+
+    assert!(!random_project.build_options().has_reproducible_setup());
+    assert!(seeded_project.build_options().has_reproducible_setup());
+}
+
+#[test]
+fn test_project_requires_an_offchain_statement() {
+    let project = Project::new(BuildOptions::for_source(
+        env::temp_dir(),
+        Path::new("addition.ak"),
+    ));
+
+    let result = project.build(
+        Path::new("addition.ak"),
+        "validator example { spend(_d, _r, _o, _t) { True } }",
+        Path::new("validators/addition.ak"),
+    );
+
+    assert!(matches!(result, Err(AikenZkError::MissingOffchain)));
+}
+
+// ---------- AUX ---------- //
+
+fn sandbox_project(project_root: &Path) -> Project {
+    Project::new(BuildOptions::for_source(
+        project_root.to_path_buf(),
+        Path::new("addition.ak"),
+    ))
+}
+
+fn offchain_addition_source() -> String {
+    r#"use cardano/transaction.{OutputReference, Transaction,}
+
+pub type ZK<redeemer_type> {
+  redeemer: redeemer_type,
+  proofs: List<Proof>,
+}
+
+type Redeemer = Void
+
+validator example {
+  spend(
+    datum: Option<Int>,
+    redeemer: ZK<Redeemer>,
+    _own_ref: OutputReference,
+    _self: Transaction,
+  ) {
+    expect Some(b) = datum
+    expect _zk_redeemer = offchain addition(priv, b, 10)
+    True
+  }
+
+  else(_) {
+    fail
+  }
+}
+"#
+    .to_string()
+}
@@ -0,0 +1,102 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::circom_circuit::CircomCircuit;
+use crate::compiler::build_options::BuildOptions;
+use crate::groth16_verifier_bls12_381::Groth16VerifierBls12_381;
+use crate::prover::Prover;
+use crate::tests::utils::create_sandbox;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_prover_returns_a_verifiable_proof_of_the_inputs() {
+    let project_root = create_sandbox();
+    let prover = addition_prover(project_root.path());
+
+    let proof = prover
+        .prove(&json!({"first_addend": "3", "second_addend": "7", "sum": "10"}))
+        .unwrap();
+
+    let verifier = Groth16VerifierBls12_381::from_json(
+        &project_root
+            .path()
+            .join("build/verification_key.json")
+            .to_string_lossy(),
+    )
+    .unwrap();
+    assert_eq!(json!(["3", "7", "10"]), proof.public_signals.to_snarkjs());
+    assert!(
+        verifier
+            .verify(&proof.public_signals.to_snarkjs(), &proof.snarkjs_proof)
+            .unwrap()
+    );
+    assert!(!project_root.path().join("build/proof.json").exists());
+}
+
+#[test]
+fn test_proof_can_be_written_as_snarkjs_files() {
+    let project_root = create_sandbox();
+    let build_dir = project_root.path().join("build");
+    let proof = addition_prover(project_root.path())
+        .prove(&json!({"first_addend": "3", "second_addend": "7", "sum": "10"}))
+        .unwrap();
+
+    proof
+        .write_snarkjs_files(&build_dir.to_string_lossy())
+        .unwrap();
+
+    assert_eq!(
+        json!(["3", "7", "10"]).to_string(),
+        fs::read_to_string(build_dir.join("public.json")).unwrap()
+    );
+    assert!(build_dir.join("proof.json").exists());
+}
+
+#[test]
+fn test_prover_rejects_inputs_that_do_not_match_the_circuit() {
+    let project_root = create_sandbox();
+
+    let result = addition_prover(project_root.path())
+        .prove(&json!({"first_addend": "3", "second_addend": "7"}));
+
+    let Err(AikenZkError::InvalidInputs { path, errors }) = result else {
+        panic!("expected the inputs to be rejected");
+    };
+    assert_eq!("the inputs", path);
+    assert_eq!(1, errors.len());
+}
+
+#[test]
+fn test_prover_for_a_validator_requires_its_manifest() {
+    let result = Prover::for_validator(Path::new("missing/validator.ak"), None);
+
+    assert!(matches!(result, Err(AikenZkError::Io { .. })));
+}
+
+// ---------- AUX ---------- //
+
+fn addition_prover(project_root: &Path) -> Prover {
+    let build_options = BuildOptions::for_circuit(project_root.to_path_buf(), "addition");
+    let circom_path = build_options.in_project("addition.circom");
+    fs::write(
+        &circom_path,
+        r#"pragma circom 2.1.9;
+include "templates/addition.circom";
+component main { public [first_addend,second_addend,sum] } = Addition();"#,
+    )
+    .unwrap();
+    CircomCircuit::from(circom_path.to_string_lossy().to_string())
+        .unwrap()
+        .generate_verification_key_with_name(
+            ("asdasd", "dsadsa"),
+            &build_options,
+            "verification_key",
+        )
+        .unwrap();
+    Prover::for_circuit(
+        project_root,
+        "addition.circom",
+        "build/verification_key.zkey",
+    )
+    .unwrap()
+}
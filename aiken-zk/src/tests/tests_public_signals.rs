@@ -0,0 +1,153 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::compiler::compiled_circuit::PublicInput;
+use crate::inputs_template::CircuitSignal;
+use crate::public_signals::{Expectation, PublicSignalError, PublicSignals};
+use crate::zk_examples::InputVisibility;
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+#[test]
+fn test_public_signals_are_labelled_with_the_circuit_signals_and_aiken_identifiers() {
+    let public_signals = merkle_tree_public_signals();
+
+    assert_eq!(
+        "Public signals of the proof:\n  \
+           [0] leaf (my_leaf) = 7\n  \
+           [1] root (12) = 12\n  \
+           [2] pathIndices[0] (indices[0]) = 0\n  \
+           [3] pathIndices[1] (indices[1]) = 1\n",
+        public_signals.to_string()
+    );
+}
+
+#[test]
+fn test_public_signals_without_aiken_identifiers_are_labelled_with_the_circuit_signals() {
+    let public_signals =
+        PublicSignals::label(values(&["7", "12", "0", "1"]), &merkle_tree_signals(), None);
+
+    assert_eq!(
+        "Public signals of the proof:\n  \
+           [0] leaf = 7\n  \
+           [1] root = 12\n  \
+           [2] pathIndices[0] = 0\n  \
+           [3] pathIndices[1] = 1\n",
+        public_signals.to_string()
+    );
+}
+
+#[test]
+fn test_circuit_outputs_are_labelled_by_position() {
+    let public_signals = PublicSignals::label(
+        values(&["3", "10"]),
+        &[signal("sum", vec![], InputVisibility::Public)],
+        None,
+    );
+
+    assert_eq!(
+        "Public signals of the proof:\n  [0] output = 3\n  [1] sum = 10\n",
+        public_signals.to_string()
+    );
+}
+
+#[test]
+fn test_expectations_can_name_signals_or_identifiers() {
+    let public_signals = merkle_tree_public_signals();
+
+    let result = public_signals.check(&[
+        Expectation::parse("my_leaf=7").unwrap(),
+        Expectation::parse("root=0xc").unwrap(),
+        Expectation::parse("indices[1] = 1").unwrap(),
+    ]);
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_negative_expectations_stand_for_their_field_opposite() {
+    let field_opposite_of_one = (BigUint::from(Fr::MODULUS) - 1u32).to_string();
+    let public_signals = PublicSignals::label(
+        vec![field_opposite_of_one],
+        &[signal("sum", vec![], InputVisibility::Public)],
+        None,
+    );
+
+    assert!(
+        public_signals
+            .check(&[Expectation::parse("sum=-1").unwrap()])
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_every_mismatched_or_unknown_expectation_is_reported() {
+    let public_signals = merkle_tree_public_signals();
+
+    let result = public_signals.check(&[
+        Expectation::parse("my_leaf=8").unwrap(),
+        Expectation::parse("root=12").unwrap(),
+        Expectation::parse("nth_fibonacci=13").unwrap(),
+    ]);
+
+    let Err(AikenZkError::UnexpectedPublicSignals { errors }) = result else {
+        panic!("expected unexpected public signals");
+    };
+    assert_eq!(
+        vec![
+            PublicSignalError::Mismatch {
+                name: "my_leaf".to_string(),
+                expected: "8".to_string(),
+                actual: "7".to_string()
+            },
+            PublicSignalError::Unknown {
+                name: "nth_fibonacci".to_string()
+            },
+        ],
+        errors
+    );
+    assert_eq!(
+        "my_leaf is 7 in the proof, expected 8",
+        errors[0].to_string()
+    );
+}
+
+#[test]
+fn test_expectations_must_be_a_name_and_a_number() {
+    assert!(Expectation::parse("root").is_err());
+    assert!(Expectation::parse("root=twelve").is_err());
+}
+
+// ---------- AUX ---------- //
+
+fn merkle_tree_public_signals() -> PublicSignals {
+    PublicSignals::label(
+        values(&["7", "12", "0", "1"]),
+        &merkle_tree_signals(),
+        Some(&[
+            PublicInput::single("my_leaf".to_string()),
+            PublicInput::single("12".to_string()),
+            PublicInput::many("indices".to_string()),
+        ]),
+    )
+}
+
+fn merkle_tree_signals() -> Vec<CircuitSignal> {
+    vec![
+        signal("leaf", vec![], InputVisibility::Public),
+        signal("root", vec![], InputVisibility::Public),
+        signal("pathElements", vec![2], InputVisibility::Private),
+        signal("pathIndices", vec![2], InputVisibility::Public),
+    ]
+}
+
+fn signal(name: &str, dimensions: Vec<usize>, visibility: InputVisibility) -> CircuitSignal {
+    CircuitSignal {
+        name: name.to_string(),
+        dimensions,
+        visibility,
+    }
+}
+
+fn values(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
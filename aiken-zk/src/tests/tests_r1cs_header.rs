@@ -0,0 +1,82 @@
+use crate::r1cs_header::R1csHeader;
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+#[test]
+fn test_header_is_read_from_the_header_section() {
+    let r1cs = r1cs_with_sections(vec![(1, header_section())]);
+
+    let header = R1csHeader::from_bytes(&r1cs).unwrap();
+
+    assert_eq!(BigUint::from(Fr::MODULUS), header.prime);
+    assert_eq!(5, header.n_wires);
+    assert_eq!(1, header.n_public_outputs);
+    assert_eq!(2, header.n_public_inputs);
+    assert_eq!(1, header.n_private_inputs);
+    assert_eq!(6, header.n_labels);
+    assert_eq!(3, header.n_constraints);
+    assert_eq!(3, header.n_public());
+}
+
+#[test]
+fn test_sections_before_the_header_are_skipped() {
+    let r1cs = r1cs_with_sections(vec![(2, vec![0xff; 12]), (1, header_section())]);
+
+    let header = R1csHeader::from_bytes(&r1cs).unwrap();
+
+    assert_eq!(3, header.n_constraints);
+}
+
+#[test]
+fn test_files_without_the_r1cs_magic_number_are_rejected() {
+    let mut r1cs = r1cs_with_sections(vec![(1, header_section())]);
+    r1cs[0] = b'x';
+
+    assert_eq!(Err("wrong magic number"), R1csHeader::from_bytes(&r1cs));
+}
+
+#[test]
+fn test_truncated_files_are_rejected() {
+    let r1cs = r1cs_with_sections(vec![(1, header_section())]);
+
+    assert_eq!(
+        Err("unexpected end of file"),
+        R1csHeader::from_bytes(&r1cs[..r1cs.len() - 1])
+    );
+}
+
+#[test]
+fn test_files_without_a_header_section_are_rejected() {
+    let r1cs = r1cs_with_sections(vec![(2, vec![0; 4])]);
+
+    assert_eq!(Err("missing header section"), R1csHeader::from_bytes(&r1cs));
+}
+
+// ---------- AUX ---------- //
+
+fn r1cs_with_sections(sections: Vec<(u32, Vec<u8>)>) -> Vec<u8> {
+    let mut bytes = b"r1cs".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend((sections.len() as u32).to_le_bytes());
+    for (section_type, section) in sections {
+        bytes.extend(section_type.to_le_bytes());
+        bytes.extend((section.len() as u64).to_le_bytes());
+        bytes.extend(section);
+    }
+    bytes
+}
+
+fn header_section() -> Vec<u8> {
+    let mut prime = BigUint::from(Fr::MODULUS).to_bytes_le();
+    prime.resize(32, 0);
+
+    let mut section = 32u32.to_le_bytes().to_vec();
+    section.extend(prime);
+    for value in [5u32, 1, 2, 1] {
+        section.extend(value.to_le_bytes());
+    }
+    section.extend(6u64.to_le_bytes());
+    section.extend(3u32.to_le_bytes());
+    section
+}
@@ -1,4 +1,5 @@
-use crate::entropy_generator::EntropyGenerator;
+use crate::entropy_generator::{EntropyGenerator, SeedCommitment};
+use sha2::Digest;
 
 #[test]
 fn test_minimum_length_of_random_for_verification_key_entropy() {
@@ -14,3 +15,85 @@ fn test_random_for_verification_key_entropy_is_alphanumeric() {
     let entropy = entropy_generator.generate();
     assert!(entropy.chars().all(|character| character.is_alphanumeric()));
 }
+
+#[test]
+fn test_seeded_generators_are_reproducible() {
+    let first_generator = EntropyGenerator::from_seed(b"my secret seed");
+    let second_generator = EntropyGenerator::from_seed(b"my secret seed");
+
+    assert_eq!(first_generator.generate(), second_generator.generate());
+    assert_eq!(first_generator.generate(), second_generator.generate());
+}
+
+#[test]
+fn test_seeded_generators_produce_a_different_value_on_each_call() {
+    let generator = EntropyGenerator::from_seed(b"my secret seed");
+
+    assert_ne!(generator.generate(), generator.generate());
+}
+
+#[test]
+fn test_different_seeds_produce_different_values() {
+    let first_generator = EntropyGenerator::from_seed(b"my secret seed");
+    let second_generator = EntropyGenerator::from_seed(b"another secret seed");
+
+    assert_ne!(first_generator.generate(), second_generator.generate());
+}
+
+#[test]
+fn test_seeded_values_can_be_used_as_beacon_hashes() {
+    let generator = EntropyGenerator::from_seed(b"my secret seed");
+
+    let value = generator.generate();
+
+    assert_eq!(64, value.len());
+    assert!(value.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+#[test]
+fn test_only_generators_built_from_a_seed_are_seeded() {
+    let seeded_generator = EntropyGenerator::from_seed(b"abc");
+    let random_generator = EntropyGenerator::new();
+
+    assert!(seeded_generator.is_seeded());
+    assert!(!random_generator.is_seeded());
+    assert!(random_generator.seed_commitment().is_none());
+}
+
+#[test]
+fn test_seed_commitments_are_salted_and_slow() {
+    let seeded_generator = EntropyGenerator::from_seed(b"abc");
+
+    let commitment = seeded_generator.seed_commitment().unwrap();
+
+    assert_eq!(SeedCommitment::ITERATIONS, commitment.iterations);
+    assert_eq!(32, commitment.salt.len());
+    assert_ne!(
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        commitment.hash
+    );
+    assert_ne!(commitment, seeded_generator.seed_commitment().unwrap());
+}
+
+#[test]
+fn test_seed_commitments_are_iterated_salted_sha256() {
+    let commitment = SeedCommitment::with_salt(b"abc", b"salt", 1);
+
+    assert_eq!("73616c74", commitment.salt);
+    assert_eq!(
+        hex::encode(sha2::Sha256::digest(b"saltabc")),
+        commitment.hash
+    );
+    assert_ne!(
+        commitment.hash,
+        SeedCommitment::with_salt(b"abc", b"salt", 2).hash
+    );
+}
+
+#[test]
+fn test_seed_commitments_only_match_their_seed() {
+    let commitment = SeedCommitment::with_salt(b"my secret seed", b"salt", 10);
+
+    assert!(commitment.matches(b"my secret seed"));
+    assert!(!commitment.matches(b"another secret seed"));
+}
@@ -1,7 +1,9 @@
+use crate::aiken_zk_error::AikenZkError;
 use crate::compiler::lexer;
 use crate::compiler::token_zk::TokenZK as Token;
 use crate::tests::token_examples::{int_token, multiple_variable_token, single_variable_token};
 use crate::zk_examples::*;
+use aiken_lang::ast::Span;
 // --------- Addition --------- //
 
 #[test]
@@ -452,6 +454,186 @@ fn test_lexer_translates_polynomial_evaluations_parameters() {
     );
 }
 
+// --------- RangeCheck --------- //
+
+#[test]
+fn test_lexer_translates_range_check_parameters() {
+    let program = "offchain range_check(8, priv, pub minimum_age, 256)";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::RangeCheck {
+                n_bits: CircuitTemplateParameter {
+                    token: Box::new(int_token(8).unwrap().extract_single().unwrap())
+                },
+                value: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+                lower_bound: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("minimum_age")
+                },
+                upper_bound: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: int_token(256)
+                },
+            }
+        },
+        *offchain_token
+    );
+}
+
+// --------- Comparators --------- //
+
+#[test]
+fn test_lexer_translates_less_than_parameters() {
+    let program = "offchain less_than(32, priv, pub threshold, priv)";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::LessThan {
+                n_bits: CircuitTemplateParameter {
+                    token: Box::new(int_token(32).unwrap().extract_single().unwrap())
+                },
+                lhs: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+                rhs: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("threshold")
+                },
+                out: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+            }
+        },
+        *offchain_token
+    );
+}
+
+#[test]
+fn test_lexer_translates_greater_equal_parameters() {
+    let program = "offchain greater_equal(64, pub balance, 100, pub 1)";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::GreaterEqual {
+                n_bits: CircuitTemplateParameter {
+                    token: Box::new(int_token(64).unwrap().extract_single().unwrap())
+                },
+                lhs: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("balance")
+                },
+                rhs: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: int_token(100)
+                },
+                out: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: int_token(1)
+                },
+            }
+        },
+        *offchain_token
+    );
+}
+
+#[test]
+fn test_lexer_translates_is_zero_parameters() {
+    let program = "offchain is_zero(priv, is_empty)";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::IsZero {
+                r#in: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+                out: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("is_empty")
+                },
+            }
+        },
+        *offchain_token
+    );
+}
+
+#[test]
+fn test_lexer_translates_eddsa_verify_parameters() {
+    let program = "offchain eddsa_verify(pub issuer_x, pub issuer_y, priv, priv, priv, priv)";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::EddsaVerify {
+                pub_key_x: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("issuer_x")
+                },
+                pub_key_y: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("issuer_y")
+                },
+                msg: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+                r_x: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+                r_y: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+                s: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+            }
+        },
+        *offchain_token
+    );
+}
+
+#[test]
+fn test_lexer_translates_membership_with_nullifier_parameters() {
+    let program = "offchain membership_with_nullifier(2, pub root, pub nullifier, 2026)";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::MembershipWithNullifier {
+                levels: CircuitTemplateParameter {
+                    token: Box::new(int_token(2).unwrap().extract_single().unwrap())
+                },
+                root: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("root")
+                },
+                nullifier: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: single_variable_token("nullifier")
+                },
+                external_nullifier: InputZK {
+                    visibility: InputVisibility::Public,
+                    token: int_token(2026)
+                },
+            }
+        },
+        *offchain_token
+    );
+}
+
 // --------- Custom --------- //
 
 #[test]
@@ -490,22 +672,158 @@ fn test_lexer_translates_custom_circom_with_list_variable() {
 }
 
 #[test]
-#[should_panic(expected = "Private parameters cannot be followed by an identifier")]
+fn test_lexer_translates_custom_circom_with_expressions() {
+    let program =
+        r#"offchain custom("main.circom", [datum.amount, @datum.prices, list.at(xs, 0)])"#;
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::CustomCircom {
+                path: String::from("main.circom"),
+                public_inputs: vec![
+                    Box::new(TokenWithCardinality::new_single(expression("datum.amount"))),
+                    Box::new(TokenWithCardinality::new_multiple(expression(
+                        "datum.prices"
+                    ))),
+                    Box::new(TokenWithCardinality::new_single(expression(
+                        "list.at(xs, 0)"
+                    ))),
+                ]
+            },
+        },
+        *offchain_token
+    );
+}
+
+// --------- Expressions --------- //
+
+#[test]
+fn test_lexer_translates_field_accesses_and_calls_as_expressions() {
+    let program = "offchain addition(datum.amount, pub list.length(xs), priv)";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::Addition {
+                lhs: public_expression("datum.amount"),
+                rhs: public_expression("list.length(xs)"),
+                res: InputZK {
+                    visibility: InputVisibility::Private,
+                    token: None
+                },
+            }
+        },
+        *offchain_token
+    );
+}
+
+#[test]
+fn test_lexer_copies_expressions_verbatim() {
+    let program = r#"offchain assert_eq(((a + b) * 2), dict.get(self.extra, "a(b").1st)"#;
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    let offchain_token = &tokens[0].0;
+    assert_eq!(
+        Token::Offchain {
+            example: ZkExample::AssertEq {
+                lhs: public_expression("((a + b) * 2)"),
+                rhs: public_expression(r#"dict.get(self.extra, "a(b").1st"#),
+            }
+        },
+        *offchain_token
+    );
+}
+
+#[test]
+fn test_lexer_does_not_allow_expressions_for_private_parameters() {
+    let program = "offchain assert_eq(priv datum.amount, 5)";
+    let result = lexer::Lexer::new().run(program);
+    assert_offchain_argument_error(
+        result,
+        OffchainArgumentError::PrivateWithIdentifier,
+        Span::create(0, program.len()),
+    );
+}
+
+#[test]
+fn test_lexer_does_not_allow_expressions_as_template_parameters() {
+    let program = "offchain fibonacci(1, 1, datum.n, pub res)";
+    let result = lexer::Lexer::new().run(program);
+    assert_offchain_argument_error(
+        result,
+        OffchainArgumentError::NonConstantTemplateParameter,
+        Span::create(0, program.len()),
+    );
+}
+
+#[test]
 fn test_lexer_does_not_allow_identifiers_for_private_parameters() {
     let program = "offchain assert_eq(priv a, pub 5)";
-    let lexer::LexInfo { .. } = lexer::Lexer::new().run(program).unwrap();
+    let result = lexer::Lexer::new().run(program);
+    assert_offchain_argument_error(
+        result,
+        OffchainArgumentError::PrivateWithIdentifier,
+        Span::create(0, program.len()),
+    );
 }
 
 #[test]
-#[should_panic(expected = "A circuit template parameter cannot have visibility")]
 fn test_lexer_doesnt_allow_visibility_in_as_template_parameters() {
     let program = "offchain merkle_tree_checker(pub 4, priv, pub root, priv, priv)";
-    let lexer::LexInfo { .. } = lexer::Lexer::new().run(program).unwrap();
+    let result = lexer::Lexer::new().run(program);
+    assert_offchain_argument_error(
+        result,
+        OffchainArgumentError::TemplateParameterWithVisibility,
+        Span::create(0, program.len()),
+    );
 }
 
 #[test]
-#[should_panic(expected = "A circuit template parameter must be constant")]
 fn test_lexer_only_allows_constants_as_template_parameters() {
     let program = "offchain merkle_tree_checker(a, priv, pub root, priv, priv)";
-    let lexer::LexInfo { .. } = lexer::Lexer::new().run(program).unwrap();
+    let result = lexer::Lexer::new().run(program);
+    assert_offchain_argument_error(
+        result,
+        OffchainArgumentError::NonConstantTemplateParameter,
+        Span::create(0, program.len()),
+    );
+}
+
+#[test]
+fn test_lexer_reports_the_span_of_the_offending_offchain_call() {
+    let program = "let a = 5\noffchain addition(priv a, pub b, pub c)\nlet b = 3";
+    let result = lexer::Lexer::new().run(program);
+    assert_offchain_argument_error(
+        result,
+        OffchainArgumentError::PrivateWithIdentifier,
+        Span::create(10, 39),
+    );
+}
+
+fn assert_offchain_argument_error(
+    result: Result<lexer::LexInfo, AikenZkError>,
+    expected_reason: OffchainArgumentError,
+    expected_span: Span,
+) {
+    match result {
+        Err(AikenZkError::InvalidOffchainArgument { reason, span }) => {
+            assert_eq!(expected_reason, reason);
+            assert_eq!(expected_span, span);
+        }
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("The program should not lex"),
+    }
+}
+
+fn expression(text: &str) -> Token {
+    Token::Expression {
+        text: text.to_string(),
+    }
+}
+
+fn public_expression(text: &str) -> InputZK {
+    InputZK {
+        visibility: InputVisibility::Public,
+        token: Some(Box::new(TokenWithCardinality::new_single(expression(text)))),
+    }
 }
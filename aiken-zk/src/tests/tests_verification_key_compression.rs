@@ -0,0 +1,78 @@
+use crate::compressed_verification_key_bls12_381::{
+    Groth16CompressedData, VerificationKeyCompressionError,
+};
+use crate::point_compressor_bls12_381::PointCompressionError;
+use crate::tests::utils::manifest_path;
+use serde_json::{Value, json};
+use std::fs;
+
+#[test]
+fn test_verification_key_compression_matches_the_output_of_the_js_compressor() {
+    let js_compressed_vk = js_compressed_verification_key();
+    let snarkjs_vk = snarkjs_verification_key();
+
+    let compressed_vk = Groth16CompressedData::from_snarkjs_verification_key(&snarkjs_vk).unwrap();
+
+    assert_eq!(js_compressed_vk.vk_alpha_1, compressed_vk.vk_alpha_1);
+    assert_eq!(js_compressed_vk.vk_beta_2, compressed_vk.vk_beta_2);
+    assert_eq!(js_compressed_vk.vk_gamma_2, compressed_vk.vk_gamma_2);
+    assert_eq!(js_compressed_vk.vk_delta_2, compressed_vk.vk_delta_2);
+    assert_eq!(js_compressed_vk.IC, compressed_vk.IC);
+}
+
+#[test]
+fn test_verification_key_without_ic_cannot_be_compressed() {
+    let mut snarkjs_vk = snarkjs_verification_key();
+    snarkjs_vk.as_object_mut().unwrap().remove("IC");
+
+    let result = Groth16CompressedData::from_snarkjs_verification_key(&snarkjs_vk);
+
+    assert!(matches!(
+        result,
+        Err(VerificationKeyCompressionError::MissingField("IC"))
+    ));
+}
+
+#[test]
+fn test_verification_key_with_an_invalid_ic_point_reports_its_position() {
+    let mut snarkjs_vk = snarkjs_verification_key();
+    snarkjs_vk["IC"][2] = json!(["1", "2", "1"]);
+
+    let result = Groth16CompressedData::from_snarkjs_verification_key(&snarkjs_vk);
+
+    match result {
+        Err(VerificationKeyCompressionError::InvalidPoint { field, source }) => {
+            assert_eq!("IC[2]", field);
+            assert_eq!(PointCompressionError::NotOnCurve { curve: "G1" }, source);
+        }
+        _ => panic!("Expected the third IC point to be rejected"),
+    }
+}
+
+// ---------- AUX ---------- //
+
+// Output of the former compressedVerificationKey.js for the addition circuit with every
+// input public.
+fn js_compressed_verification_key() -> Groth16CompressedData {
+    Groth16CompressedData {
+        vk_alpha_1: "85e3f8a13a670514351a68677ea0e2fc51150daeea496b85a34d97751695e26b2ae4f1a5a3b60e17bb7bfd6d474154c5".to_string(),
+        vk_beta_2: "b1abf58f58af5981cd24f996e53626a4157eeed4aa814498885b3a547c35d5efb877834602508255c030708552b353e21631f16475e35b977e39a068ac9fb5bc4c25d383139b721da0a878b663c4df52c94a51f7c06a019bb40324713d2bbf0f".to_string(),
+        vk_gamma_2: "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8".to_string(),
+        vk_delta_2: "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8".to_string(),
+        IC: vec![
+            "b42a4610c5c2722df0cae5b696d0e212dd41e471a5246217751ae313dceba2b4d25c1be296ee8e00454027b7c4a45208".to_string(),
+            "87ef7b539de25c06493f7cd054a78da2819084b7027038d28b31fe88ce0b833f243723fbd9c4e502a3d0c2246aa69560".to_string(),
+            "a680399022e0bd33fa72396626b4bfc5d1d42e6d9207f3bc64f9fd26a32e5d150ba63a7c28d61db724d362bb9cf96680".to_string(),
+            "87ac4ff5d2863dd744e3ad397dfde8fe657c09c9c059e25ab8f37b85822eb8f34604d7ca2fe2622d1003ed258319bbf2".to_string(),
+        ],
+    }
+}
+
+// The verification key snarkjs exports for the same circuit before the phase 2 contributions,
+// which leave delta as the generator: the key the compressed key above was computed from.
+fn snarkjs_verification_key() -> Value {
+    let verification_key =
+        fs::read_to_string(manifest_path() + "/src/tests/fixtures/verification_key_0000.json")
+            .unwrap();
+    serde_json::from_str(&verification_key).unwrap()
+}
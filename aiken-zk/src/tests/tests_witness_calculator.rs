@@ -0,0 +1,124 @@
+use crate::aiken_zk_error::AikenZkError;
+use crate::circom_circuit::CircomCircuit;
+use crate::tests::utils::create_sandbox;
+use crate::witness_calculator::{WitnessCalculator, WitnessError};
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_witness_calculator_computes_the_witness_of_the_inputs() {
+    let sandbox = create_sandbox();
+    let mut witness_calculator = addition_witness_calculator(sandbox.path());
+
+    let witness = witness_calculator
+        .calculate(
+            json!({"first_addend": 1, "second_addend": "2", "sum": "0x3"})
+                .as_object()
+                .unwrap(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            BigUint::from(1u32),
+            BigUint::from(1u32),
+            BigUint::from(2u32),
+            BigUint::from(3u32)
+        ],
+        witness
+    );
+}
+
+#[test]
+fn test_witness_calculator_reports_the_failing_constraint() {
+    let sandbox = create_sandbox();
+    let mut witness_calculator = addition_witness_calculator(sandbox.path());
+
+    let result = witness_calculator.calculate(
+        json!({"first_addend": 1, "second_addend": 2, "sum": 4})
+            .as_object()
+            .unwrap(),
+    );
+
+    let Err(WitnessError::Failed { reason }) = result else {
+        panic!("expected the circuit to reject the inputs");
+    };
+    assert!(reason.starts_with("assert failed"));
+    assert!(reason.contains("Addition"));
+}
+
+#[test]
+fn test_witness_calculator_writes_a_wtns_file() {
+    let sandbox = create_sandbox();
+    let mut witness_calculator = addition_witness_calculator(sandbox.path());
+    let inputs_path = sandbox.path().join("inputs.json");
+    let witness_path = sandbox.path().join("witness.wtns");
+    fs::write(
+        &inputs_path,
+        r#"{"first_addend": "1", "second_addend": "2", "sum": "3"}"#,
+    )
+    .unwrap();
+
+    witness_calculator
+        .write_witness_file(
+            &inputs_path.to_string_lossy(),
+            &witness_path.to_string_lossy(),
+        )
+        .unwrap();
+
+    let wtns = fs::read(witness_path).unwrap();
+    assert_eq!(b"wtns", &wtns[0..4]);
+    assert_eq!(
+        BigUint::from(Fr::MODULUS),
+        BigUint::from_bytes_le(&wtns[28..60])
+    );
+    assert_eq!(4, u32::from_le_bytes(wtns[60..64].try_into().unwrap()));
+    assert_eq!(64 + 12 + 4 * 32, wtns.len());
+}
+
+#[test]
+fn test_witness_calculator_requires_a_circom_witness_generator() {
+    let missing_wasm = WitnessCalculator::from_file("missing.wasm");
+    let invalid_wasm = WitnessCalculator::from_bytes(b"not a wasm module");
+
+    assert!(matches!(missing_wasm, Err(WitnessError::Io { .. })));
+    assert!(invalid_wasm.is_err());
+}
+
+#[test]
+fn test_a_witness_generator_that_cannot_be_read_is_an_io_error() {
+    let Err(error) = WitnessCalculator::from_file("missing.wasm") else {
+        panic!("expected the witness generator to be missing");
+    };
+
+    assert_eq!(
+        AikenZkError::IO_EXIT_CODE,
+        AikenZkError::from(error).exit_code()
+    );
+}
+
+// ---------- AUX ---------- //
+
+fn addition_witness_calculator(sandbox: &Path) -> WitnessCalculator {
+    fs::write(
+        sandbox.join("addition.circom"),
+        r#"pragma circom 2.1.9;
+include "templates/addition.circom";
+component main { public [first_addend,second_addend,sum] } = Addition();"#,
+    )
+    .unwrap();
+    CircomCircuit::run_command_or_fail(
+        Command::new("circom")
+            .args(["addition.circom", "--wasm", "-p", "bls12381"])
+            .current_dir(sandbox),
+        "circom",
+    )
+    .unwrap();
+    WitnessCalculator::from_file(&sandbox.join("addition_js/addition.wasm").to_string_lossy())
+        .unwrap()
+}
@@ -1,4 +1,4 @@
-use crate::compiler::token_zk;
+use crate::compiler::{lexer, token_zk};
 use crate::zk_examples::{
     CircuitTemplateParameter, InputVisibility, InputZK, TokenWithCardinality, ZkExample,
 };
@@ -295,3 +295,147 @@ pub fn polynomial_evaluations_token(grade: u32, amount_of_evaluations: u32) -> t
         },
     }
 }
+
+// ----- RANGE CHECK ----- //
+
+pub fn range_check_token_with_mixed_visibility(n_bits: u32) -> token_zk::TokenZK {
+    token_zk::TokenZK::Offchain {
+        example: ZkExample::RangeCheck {
+            n_bits: CircuitTemplateParameter {
+                token: Box::new(int_token(n_bits).unwrap().extract_single().unwrap()),
+            },
+            value: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+            lower_bound: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("minimum_age"),
+            },
+            upper_bound: InputZK {
+                visibility: InputVisibility::Public,
+                token: int_token(256),
+            },
+        },
+    }
+}
+
+// ----- COMPARATORS ----- //
+
+pub fn less_than_token_with_mixed_visibility(n_bits: u32) -> token_zk::TokenZK {
+    token_zk::TokenZK::Offchain {
+        example: ZkExample::LessThan {
+            n_bits: CircuitTemplateParameter {
+                token: Box::new(int_token(n_bits).unwrap().extract_single().unwrap()),
+            },
+            lhs: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+            rhs: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("threshold"),
+            },
+            out: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+        },
+    }
+}
+
+pub fn greater_equal_token_with_public_inputs(n_bits: u32) -> token_zk::TokenZK {
+    token_zk::TokenZK::Offchain {
+        example: ZkExample::GreaterEqual {
+            n_bits: CircuitTemplateParameter {
+                token: Box::new(int_token(n_bits).unwrap().extract_single().unwrap()),
+            },
+            lhs: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("balance"),
+            },
+            rhs: InputZK {
+                visibility: InputVisibility::Public,
+                token: int_token(100),
+            },
+            out: InputZK {
+                visibility: InputVisibility::Public,
+                token: int_token(1),
+            },
+        },
+    }
+}
+
+pub fn is_zero_token_with_mixed_visibility() -> token_zk::TokenZK {
+    token_zk::TokenZK::Offchain {
+        example: ZkExample::IsZero {
+            r#in: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+            out: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("is_empty"),
+            },
+        },
+    }
+}
+
+pub fn eddsa_verify_token_with_public_key() -> token_zk::TokenZK {
+    token_zk::TokenZK::Offchain {
+        example: ZkExample::EddsaVerify {
+            pub_key_x: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("issuer_x"),
+            },
+            pub_key_y: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("issuer_y"),
+            },
+            msg: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+            r_x: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+            r_y: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+            s: InputZK {
+                visibility: InputVisibility::Private,
+                token: None,
+            },
+        },
+    }
+}
+
+pub fn membership_with_nullifier_token_with_public_inputs(levels: u32) -> token_zk::TokenZK {
+    token_zk::TokenZK::Offchain {
+        example: ZkExample::MembershipWithNullifier {
+            levels: CircuitTemplateParameter {
+                token: Box::new(int_token(levels).unwrap().extract_single().unwrap()),
+            },
+            root: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("root"),
+            },
+            nullifier: InputZK {
+                visibility: InputVisibility::Public,
+                token: single_variable_token("nullifier"),
+            },
+            external_nullifier: InputZK {
+                visibility: InputVisibility::Public,
+                token: int_token(2026),
+            },
+        },
+    }
+}
+
+pub fn offchain_block_token_with_mixed_visibility() -> token_zk::TokenZK {
+    let program = "offchain {\n    let sum = a + b\n    assert sum * priv c == d\n}";
+    let lexer::LexInfo { tokens, .. } = lexer::Lexer::new().run(program).unwrap();
+    tokens[0].0.clone()
+}
@@ -1,6 +1,14 @@
+use crate::circom_circuit::CircomCircuit;
+use crate::circom_templates::CircomTemplates;
+use crate::witness_calculator::{WitnessCalculator, WitnessError};
+use ark_bls12_381::Fr;
+use num_bigint::BigUint;
+use serde_json::Value;
+use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
-use std::{env, fs};
+use std::process::Command;
+use std::str::FromStr;
 use tempfile::{TempDir, tempdir};
 
 fn copy_dir_contents(src: &Path, dst: &Path) -> std::io::Result<()> {
@@ -19,11 +27,11 @@ fn copy_dir_contents(src: &Path, dst: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
-pub fn create_sandbox_and_set_as_current_directory() -> TempDir {
+// A copy of the sandbox project to be used through its path, so tests can run concurrently.
+pub fn create_sandbox() -> TempDir {
     let sandbox_path = &sandbox_path();
     let source = Path::new(sandbox_path);
     let temp_dir = tempdir().expect("Could not create temp dir");
-    env::set_current_dir(temp_dir.path()).expect("Couldn't move to temp directory");
     copy_dir_contents(source, temp_dir.path()).expect("Could not copy template into directory");
 
     let perms = fs::Permissions::from_mode(0o777);
@@ -39,3 +47,55 @@ fn sandbox_path() -> String {
 pub fn manifest_path() -> String {
     env!("CARGO_MANIFEST_DIR").to_string()
 }
+
+// Compiles a circuit whose main component is built from an embedded template.
+pub fn template_witness_calculator(
+    sandbox: &Path,
+    template_file_name: &str,
+    main_component: &str,
+) -> WitnessCalculator {
+    CircomTemplates::write_to_build_dir(template_file_name, sandbox).unwrap();
+    let circuit_name = format!("{}_main", template_file_name);
+    fs::write(
+        sandbox.join(format!("{}.circom", circuit_name)),
+        format!(
+            "pragma circom 2.1.9;\ninclude \"templates/{}.circom\";\ncomponent main = {};",
+            template_file_name, main_component
+        ),
+    )
+    .unwrap();
+    CircomCircuit::run_command_or_fail(
+        Command::new("circom")
+            .args([
+                &format!("{}.circom", circuit_name),
+                "--wasm",
+                "-p",
+                "bls12381",
+            ])
+            .current_dir(sandbox),
+        "circom",
+    )
+    .unwrap();
+    WitnessCalculator::from_file(
+        &sandbox
+            .join(format!("{}_js/{}.wasm", circuit_name, circuit_name))
+            .to_string_lossy(),
+    )
+    .unwrap()
+}
+
+pub fn calculate_witness(
+    witness_calculator: &mut WitnessCalculator,
+    inputs: Value,
+) -> Result<Vec<BigUint>, WitnessError> {
+    witness_calculator.calculate(inputs.as_object().unwrap())
+}
+
+// The constants are the only numbers of the template with more than 20 digits.
+pub fn field_elements_in(source: &str) -> Vec<Fr> {
+    source
+        .split(|character: char| !character.is_ascii_digit())
+        .filter(|number| number.len() > 20)
+        .map(|number| Fr::from(BigUint::from_str(number).unwrap()))
+        .collect()
+}
@@ -0,0 +1,391 @@
+use crate::inputs_validator::InputsValidator;
+use num_bigint::{BigInt, BigUint};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io;
+use std::str::FromStr;
+use thiserror::Error;
+use wasmi::{Caller, Engine, Extern, Instance, Linker, Module, Store, TypedFunc};
+
+#[derive(Debug, Error)]
+pub enum WitnessError {
+    #[error("couldn't access {path}: {source}")]
+    Io { path: String, source: io::Error },
+    #[error("{path} is not a circom witness generator: {reason}")]
+    InvalidWitnessGenerator { path: String, reason: String },
+    #[error("{signal} can't be assigned: {reason}")]
+    Input { signal: String, reason: String },
+    #[error("the circuit rejected the inputs: {reason}")]
+    Failed { reason: String },
+}
+
+// What the witness generator reports through its runtime imports while it runs.
+#[derive(Default)]
+struct RuntimeMessages {
+    exception: Option<i32>,
+    errors: Vec<String>,
+}
+
+// Runs the wasm witness generator compiled by circom (`--wasm`) in process, through the same
+// exports the generated `witness_calculator.js` uses.
+pub struct WitnessCalculator {
+    store: Store<RuntimeMessages>,
+    instance: Instance,
+    field_words: u32,
+    prime: BigUint,
+}
+
+impl WitnessCalculator {
+    const WTNS_MAGIC: &'static [u8] = b"wtns";
+    const WTNS_VERSION: u32 = 2;
+
+    pub fn from_file(wasm_path: &str) -> Result<Self, WitnessError> {
+        let wasm = fs::read(wasm_path).map_err(|source| WitnessError::Io {
+            path: wasm_path.to_string(),
+            source,
+        })?;
+        Self::from_bytes(&wasm).map_err(|reason| WitnessError::InvalidWitnessGenerator {
+            path: wasm_path.to_string(),
+            reason,
+        })
+    }
+
+    pub fn from_bytes(wasm: &[u8]) -> Result<Self, String> {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wasm).map_err(|error| error.to_string())?;
+        let mut store = Store::new(&engine, RuntimeMessages::default());
+        let instance = Self::runtime(&engine)
+            .map_err(|error| error.to_string())?
+            .instantiate(&mut store, &module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|error| error.to_string())?;
+
+        let mut witness_calculator = Self {
+            store,
+            instance,
+            field_words: 0,
+            prime: BigUint::default(),
+        };
+        let version: u32 = witness_calculator.call("getVersion", ())?;
+        if version != 2 {
+            return Err(format!("unsupported circom runtime version {}", version));
+        }
+        witness_calculator.field_words = witness_calculator.call("getFieldNumLen32", ())?;
+        witness_calculator.call::<(), ()>("getRawPrime", ())?;
+        witness_calculator.prime = witness_calculator.read_shared_memory()?;
+        Ok(witness_calculator)
+    }
+
+    // Values may be decimal or `0x` hexadecimal, negative values stand for their field opposite.
+    pub fn calculate(&mut self, inputs: &Map<String, Value>) -> Result<Vec<BigUint>, WitnessError> {
+        self.call_or_fail::<i32, ()>("init", 0)?;
+        let mut assigned_inputs = 0;
+        for (signal, value) in inputs {
+            let values = self.field_elements(signal, value)?;
+            let (hash_msb, hash_lsb) = Self::signal_hash(signal);
+            self.ensure_signal_size(signal, hash_msb, hash_lsb, values.len())?;
+            for (position, value) in values.iter().enumerate() {
+                self.write_shared_memory(value)
+                    .map_err(|reason| WitnessError::Failed { reason })?;
+                self.call_or_fail::<(i32, i32, i32), ()>(
+                    "setInputSignal",
+                    (hash_msb as i32, hash_lsb as i32, position as i32),
+                )?;
+            }
+            assigned_inputs += values.len();
+        }
+        if let Some(input_size) = self.optional_call::<(), u32>("getInputSize", ())?
+            && (assigned_inputs as u32) < input_size
+        {
+            return Err(WitnessError::Failed {
+                reason: format!(
+                    "only {} of the {} inputs were assigned",
+                    assigned_inputs, input_size
+                ),
+            });
+        }
+
+        let witness_size: u32 = self.call_or_fail("getWitnessSize", ())?;
+        (0..witness_size)
+            .map(|index| {
+                self.call_or_fail::<i32, ()>("getWitness", index as i32)?;
+                self.read_shared_memory()
+                    .map_err(|reason| WitnessError::Failed { reason })
+            })
+            .collect()
+    }
+
+    pub fn write_witness_file(
+        &mut self,
+        inputs_path: &str,
+        witness_path: &str,
+    ) -> Result<(), WitnessError> {
+        let inputs = fs::read_to_string(inputs_path).map_err(|source| WitnessError::Io {
+            path: inputs_path.to_string(),
+            source,
+        })?;
+        let inputs = match serde_json::from_str::<Value>(&inputs) {
+            Ok(Value::Object(inputs)) => inputs,
+            _ => {
+                return Err(WitnessError::Input {
+                    signal: inputs_path.to_string(),
+                    reason: "the inputs are not a json object".to_string(),
+                });
+            }
+        };
+        let witness = self.calculate(&inputs)?;
+        fs::write(witness_path, self.to_wtns(&witness)).map_err(|source| WitnessError::Io {
+            path: witness_path.to_string(),
+            source,
+        })
+    }
+
+    // Binary wtns format read by snarkjs: a header section with the prime and the amount of
+    // values, then the values, all little endian.
+    pub fn to_wtns(&self, witness: &[BigUint]) -> Vec<u8> {
+        let field_bytes = self.field_words as usize * 4;
+        let mut wtns = Self::WTNS_MAGIC.to_vec();
+        wtns.extend(Self::WTNS_VERSION.to_le_bytes());
+        wtns.extend(2u32.to_le_bytes());
+
+        wtns.extend(1u32.to_le_bytes());
+        wtns.extend((8 + field_bytes as u64).to_le_bytes());
+        wtns.extend((field_bytes as u32).to_le_bytes());
+        wtns.extend(Self::to_le_bytes(&self.prime, field_bytes));
+        wtns.extend((witness.len() as u32).to_le_bytes());
+
+        wtns.extend(2u32.to_le_bytes());
+        wtns.extend(((witness.len() * field_bytes) as u64).to_le_bytes());
+        for value in witness {
+            wtns.extend(Self::to_le_bytes(value, field_bytes));
+        }
+        wtns
+    }
+
+    // private - runtime
+
+    fn runtime(engine: &Engine) -> Result<Linker<RuntimeMessages>, wasmi::Error> {
+        let mut linker = Linker::new(engine);
+        linker.func_wrap(
+            "runtime",
+            "exceptionHandler",
+            |mut caller: Caller<'_, RuntimeMessages>, code: i32| -> Result<(), wasmi::Error> {
+                caller.data_mut().exception = Some(code);
+                Err(wasmi::Error::i32_exit(code))
+            },
+        )?;
+        linker.func_wrap(
+            "runtime",
+            "printErrorMessage",
+            |mut caller: Caller<'_, RuntimeMessages>| -> Result<(), wasmi::Error> {
+                let message = Self::read_message(&mut caller)?;
+                caller.data_mut().errors.push(message);
+                Ok(())
+            },
+        )?;
+        // Output of circom `log` calls, which proving doesn't show.
+        linker.func_wrap("runtime", "writeBufferMessage", || {})?;
+        linker.func_wrap("runtime", "showSharedRWMemory", || {})?;
+        Ok(linker)
+    }
+
+    fn read_message(caller: &mut Caller<'_, RuntimeMessages>) -> Result<String, wasmi::Error> {
+        let get_message_char = caller
+            .get_export("getMessageChar")
+            .and_then(Extern::into_func)
+            .ok_or_else(|| wasmi::Error::new("getMessageChar is not exported"))?
+            .typed::<(), i32>(&*caller)?;
+        let mut message = vec![];
+        loop {
+            match get_message_char.call(&mut *caller, ())? {
+                0 => break,
+                character => message.push(character as u8),
+            }
+        }
+        Ok(String::from_utf8_lossy(&message).to_string())
+    }
+
+    fn exception_reason(code: i32) -> &'static str {
+        match code {
+            1 => "signal not found",
+            2 => "too many signals set",
+            3 => "signal already set",
+            4 => "assert failed",
+            5 => "not enough memory",
+            6 => "input signal array access exceeds the size",
+            _ => "unknown error",
+        }
+    }
+
+    // private - exports
+
+    fn typed_func<Params, Results>(&self, name: &str) -> Option<TypedFunc<Params, Results>>
+    where
+        Params: wasmi::WasmParams,
+        Results: wasmi::WasmResults,
+    {
+        self.instance
+            .get_typed_func::<Params, Results>(&self.store, name)
+            .ok()
+    }
+
+    fn call<Params, Results>(&mut self, name: &str, params: Params) -> Result<Results, String>
+    where
+        Params: wasmi::WasmParams,
+        Results: wasmi::WasmResults,
+    {
+        self.typed_func::<Params, Results>(name)
+            .ok_or_else(|| format!("{} is not exported", name))?
+            .call(&mut self.store, params)
+            .map_err(|error| error.to_string())
+    }
+
+    fn optional_call<Params, Results>(
+        &mut self,
+        name: &str,
+        params: Params,
+    ) -> Result<Option<Results>, WitnessError>
+    where
+        Params: wasmi::WasmParams,
+        Results: wasmi::WasmResults,
+    {
+        match self.typed_func::<Params, Results>(name) {
+            Some(func) => func
+                .call(&mut self.store, params)
+                .map(Some)
+                .map_err(|error| self.failure(error)),
+            None => Ok(None),
+        }
+    }
+
+    fn call_or_fail<Params, Results>(
+        &mut self,
+        name: &str,
+        params: Params,
+    ) -> Result<Results, WitnessError>
+    where
+        Params: wasmi::WasmParams,
+        Results: wasmi::WasmResults,
+    {
+        self.optional_call(name, params)?
+            .ok_or_else(|| WitnessError::Failed {
+                reason: format!("{} is not exported", name),
+            })
+    }
+
+    // Errors raised by the circuit come with the messages it printed, e.g. the failing template
+    // and line of an assert.
+    fn failure(&mut self, error: wasmi::Error) -> WitnessError {
+        let messages = std::mem::take(&mut self.store.data_mut().errors);
+        let reason = match self.store.data_mut().exception.take() {
+            Some(code) => Self::exception_reason(code).to_string(),
+            None => error.to_string(),
+        };
+        WitnessError::Failed {
+            reason: std::iter::once(reason)
+                .chain(
+                    messages
+                        .into_iter()
+                        .map(|message| message.trim().to_string()),
+                )
+                .collect::<Vec<_>>()
+                .join(". "),
+        }
+    }
+
+    // Signal sizes are only exported by newer circom runtimes; older ones fail when setting a
+    // signal out of range.
+    fn ensure_signal_size(
+        &mut self,
+        signal: &str,
+        hash_msb: u32,
+        hash_lsb: u32,
+        amount: usize,
+    ) -> Result<(), WitnessError> {
+        let Some(size) = self.optional_call::<(i32, i32), i32>(
+            "getInputSignalSize",
+            (hash_msb as i32, hash_lsb as i32),
+        )?
+        else {
+            return Ok(());
+        };
+        if size < 0 {
+            return Err(WitnessError::Input {
+                signal: signal.to_string(),
+                reason: "it is not an input of the circuit".to_string(),
+            });
+        }
+        if size as usize != amount {
+            return Err(WitnessError::Input {
+                signal: signal.to_string(),
+                reason: format!("expected {} values, got {}", size, amount),
+            });
+        }
+        Ok(())
+    }
+
+    // private - values
+
+    fn field_elements(&self, signal: &str, value: &Value) -> Result<Vec<BigUint>, WitnessError> {
+        match value {
+            Value::Array(elements) => Ok(elements
+                .iter()
+                .map(|element| self.field_elements(signal, element))
+                .collect::<Result<Vec<_>, _>>()?
+                .concat()),
+            Value::Number(number) => BigInt::from_str(&number.to_string())
+                .ok()
+                .map(|number| vec![self.reduce(number)])
+                .ok_or_else(|| Self::not_a_number(signal, value)),
+            Value::String(text) => InputsValidator::parse_integer(text)
+                .map(|number| vec![self.reduce(number)])
+                .ok_or_else(|| Self::not_a_number(signal, value)),
+            _ => Err(Self::not_a_number(signal, value)),
+        }
+    }
+
+    fn not_a_number(signal: &str, value: &Value) -> WitnessError {
+        WitnessError::Input {
+            signal: signal.to_string(),
+            reason: format!("{} is not a number", value),
+        }
+    }
+
+    fn reduce(&self, value: BigInt) -> BigUint {
+        let prime = BigInt::from(self.prime.clone());
+        (((value % &prime) + &prime) % &prime)
+            .to_biguint()
+            .expect("a value reduced modulo the prime is not negative")
+    }
+
+    // Signals are looked up by the 64 bits FNV-1a hash of their name.
+    fn signal_hash(signal: &str) -> (u32, u32) {
+        let hash = signal.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        ((hash >> 32) as u32, hash as u32)
+    }
+
+    // The shared memory holds one field element as 32 bits words, least significant first.
+    fn read_shared_memory(&mut self) -> Result<BigUint, String> {
+        let words = (0..self.field_words)
+            .map(|index| self.call::<i32, u32>("readSharedRWMemory", index as i32))
+            .collect::<Result<Vec<u32>, String>>()?;
+        Ok(BigUint::from_slice(&words))
+    }
+
+    fn write_shared_memory(&mut self, value: &BigUint) -> Result<(), String> {
+        let mut words = value.to_u32_digits();
+        words.resize(self.field_words as usize, 0);
+        for (index, word) in words.into_iter().enumerate() {
+            self.call::<(i32, u32), ()>("writeSharedRWMemory", (index as i32, word))?;
+        }
+        Ok(())
+    }
+
+    fn to_le_bytes(value: &BigUint, length: usize) -> Vec<u8> {
+        let mut bytes = value.to_bytes_le();
+        bytes.resize(length, 0);
+        bytes
+    }
+}
@@ -2,6 +2,7 @@ use chumsky::prelude::*;
 use std::fmt;
 use std::fmt::Formatter;
 
+use crate::compiler::offchain_block::OffchainBlock;
 use crate::compiler::parsers::int_parser;
 use crate::compiler::token_zk::{TokenZK as Token, TokenZK};
 use crate::zk_examples::TokenWithCardinality::{Multiple, Single};
@@ -10,6 +11,7 @@ use chumsky::{
     Parser,
     prelude::{choice, just},
 };
+use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum InputVisibility {
@@ -60,28 +62,54 @@ impl TokenWithCardinality {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum OffchainArgumentError {
+    #[error("Public parameters must be followed by an identifier")]
+    PublicWithoutIdentifier,
+    #[error("Private parameters cannot be followed by an identifier")]
+    PrivateWithIdentifier,
+    #[error("A circuit template parameter cannot have visibility (pub or priv)")]
+    TemplateParameterWithVisibility,
+    #[error("A circuit template parameter must be constant")]
+    NonConstantTemplateParameter,
+    #[error("{name} is marked both pub and priv in the offchain block")]
+    ConflictingVisibility { name: String },
+    #[error("{name} is already defined in the offchain block")]
+    RedefinedName { name: String },
+    #[error(
+        "{name} is bound by a let in the offchain block, so it cannot have visibility (pub or priv)"
+    )]
+    LetBindingWithVisibility { name: String },
+    #[error("{name} cannot be used in an offchain block: it is a circom keyword or starts with _")]
+    ReservedName { name: String },
+    #[error("An offchain block must assert something")]
+    BlockWithoutAssertion,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CircuitTemplateParameter {
     pub token: Box<Token>,
 }
 
 impl CircuitTemplateParameter {
-    pub fn from(visibility_token: (Option<InputVisibility>, Option<Token>)) -> Self {
+    pub fn from(
+        visibility_token: (Option<InputVisibility>, Option<Token>),
+    ) -> Result<Self, OffchainArgumentError> {
         let (maybe_input_visibility, maybe_token) = visibility_token;
 
         if maybe_input_visibility.is_some() {
-            panic!("A circuit template parameter cannot have visibility (pub or priv)");
+            return Err(OffchainArgumentError::TemplateParameterWithVisibility);
         }
 
         let identifier_token = if let Some(Token::Int { value, base }) = maybe_token {
             Box::new(TokenZK::Int { value, base })
         } else {
-            panic!("A circuit template parameter must be constant");
+            return Err(OffchainArgumentError::NonConstantTemplateParameter);
         };
 
-        Self {
+        Ok(Self {
             token: identifier_token,
-        }
+        })
     }
 }
 
@@ -92,31 +120,33 @@ pub struct InputZK {
 }
 
 impl InputZK {
-    pub fn from(visibility_token: (Option<InputVisibility>, Option<TokenZK>)) -> Self {
+    pub fn from(
+        visibility_token: (Option<InputVisibility>, Option<TokenZK>),
+    ) -> Result<Self, OffchainArgumentError> {
         let (maybe_input_visibility, maybe_cardinality_token) = visibility_token;
 
         match maybe_input_visibility {
             None | Some(InputVisibility::Public) => {
-                if maybe_cardinality_token.is_none() {
-                    panic!("Public parameters must be followed by an identifier");
-                }
+                let Some(cardinality_token) = maybe_cardinality_token else {
+                    return Err(OffchainArgumentError::PublicWithoutIdentifier);
+                };
 
-                Self {
+                Ok(Self {
                     visibility: InputVisibility::Public,
                     token: Some(Box::new(TokenWithCardinality::new_single(
-                        maybe_cardinality_token.unwrap(),
+                        cardinality_token,
                     ))),
-                }
+                })
             }
             Some(InputVisibility::Private) => {
                 if maybe_cardinality_token.is_some() {
-                    panic!("Private parameters cannot be followed by an identifier");
+                    return Err(OffchainArgumentError::PrivateWithIdentifier);
                 }
 
-                Self {
+                Ok(Self {
                     visibility: InputVisibility::Private,
                     token: None,
-                }
+                })
             }
         }
     }
@@ -189,6 +219,45 @@ pub enum ZkExample {
         domain: InputZK,
         evaluations: InputZK,
     },
+    RangeCheck {
+        n_bits: CircuitTemplateParameter,
+        value: InputZK,
+        lower_bound: InputZK,
+        upper_bound: InputZK,
+    },
+    LessThan {
+        n_bits: CircuitTemplateParameter,
+        lhs: InputZK,
+        rhs: InputZK,
+        out: InputZK,
+    },
+    GreaterEqual {
+        n_bits: CircuitTemplateParameter,
+        lhs: InputZK,
+        rhs: InputZK,
+        out: InputZK,
+    },
+    IsZero {
+        r#in: InputZK,
+        out: InputZK,
+    },
+    EddsaVerify {
+        pub_key_x: InputZK,
+        pub_key_y: InputZK,
+        msg: InputZK,
+        r_x: InputZK,
+        r_y: InputZK,
+        s: InputZK,
+    },
+    MembershipWithNullifier {
+        levels: CircuitTemplateParameter,
+        root: InputZK,
+        nullifier: InputZK,
+        external_nullifier: InputZK,
+    },
+    ExpressionBlock {
+        block: OffchainBlock,
+    },
 }
 
 impl ZkExample {
@@ -196,12 +265,64 @@ impl ZkExample {
         text::ident().map(|name| Token::Name { name }).padded()
     }
 
+    // Field accesses, calls and parenthesized expressions are not parsed as Aiken: their text is
+    // copied to the verify call, which Aiken then type checks.
+    fn expression_parser() -> impl Parser<char, Token, Error = ParseError> {
+        let field_access = just('.')
+            .ignore_then(
+                filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_')
+                    .repeated()
+                    .at_least(1)
+                    .collect::<String>(),
+            )
+            .map(|field| format!(".{}", field));
+        let postfix = choice((field_access, Self::parenthesized_parser())).repeated();
+        let named_expression = text::ident()
+            .then(postfix.clone().at_least(1))
+            .map(|(name, postfixes): (String, Vec<String>)| name + &postfixes.concat());
+        let parenthesized_expression = Self::parenthesized_parser()
+            .then(postfix)
+            .map(|(expression, postfixes)| expression + &postfixes.concat());
+
+        choice((named_expression, parenthesized_expression))
+            .map(|text| Token::Expression { text })
+            .padded()
+    }
+
+    fn parenthesized_parser() -> impl Parser<char, String, Error = ParseError> + Clone {
+        recursive(|parenthesized| {
+            let string_literal = just('"')
+                .ignore_then(
+                    choice((
+                        just('\\')
+                            .ignore_then(any())
+                            .map(|escaped| format!("\\{}", escaped)),
+                        filter(|c: &char| *c != '"' && *c != '\\').map(String::from),
+                    ))
+                    .repeated(),
+                )
+                .then_ignore(just('"'))
+                .map(|parts| format!("\"{}\"", parts.concat()));
+            let inner = choice((
+                string_literal,
+                parenthesized,
+                filter(|c: &char| !"()\"".contains(*c)).map(String::from),
+            ))
+            .repeated();
+
+            just('(')
+                .ignore_then(inner)
+                .then_ignore(just(')'))
+                .map(|parts| format!("({})", parts.concat()))
+        })
+    }
+
     fn int_or_var()
     -> impl Parser<char, (Option<InputVisibility>, Option<Token>), Error = ParseError> {
         choice((just("priv"), just("pub")))
             .padded()
             .or_not()
-            .then(choice((int_parser(), Self::name_parser())).or_not())
+            .then(choice((Self::expression_parser(), int_parser(), Self::name_parser())).or_not())
             .map(|(maybe_visibility, maybe_token)| {
                 (InputVisibility::from(maybe_visibility), maybe_token)
             })
@@ -223,12 +344,14 @@ impl ZkExample {
         just("addition")
             .padded()
             .ignore_then(Self::parameters(3))
-            .map(|args| Token::Offchain {
-                example: ZkExample::Addition {
-                    lhs: InputZK::from(args[0].clone()),
-                    rhs: InputZK::from(args[1].clone()),
-                    res: InputZK::from(args[2].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::Addition {
+                        lhs: InputZK::from(args[0].clone())?,
+                        rhs: InputZK::from(args[1].clone())?,
+                        res: InputZK::from(args[2].clone())?,
+                    })
+                })
             })
     }
 
@@ -236,12 +359,14 @@ impl ZkExample {
         just("subtraction")
             .padded()
             .ignore_then(Self::parameters(3))
-            .map(|args| Token::Offchain {
-                example: ZkExample::Subtraction {
-                    lhs: InputZK::from(args[0].clone()),
-                    rhs: InputZK::from(args[1].clone()),
-                    res: InputZK::from(args[2].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::Subtraction {
+                        lhs: InputZK::from(args[0].clone())?,
+                        rhs: InputZK::from(args[1].clone())?,
+                        res: InputZK::from(args[2].clone())?,
+                    })
+                })
             })
     }
 
@@ -249,12 +374,14 @@ impl ZkExample {
         just("multiplication")
             .padded()
             .ignore_then(Self::parameters(3))
-            .map(|args| Token::Offchain {
-                example: ZkExample::Multiplication {
-                    lhs: InputZK::from(args[0].clone()),
-                    rhs: InputZK::from(args[1].clone()),
-                    res: InputZK::from(args[2].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::Multiplication {
+                        lhs: InputZK::from(args[0].clone())?,
+                        rhs: InputZK::from(args[1].clone())?,
+                        res: InputZK::from(args[2].clone())?,
+                    })
+                })
             })
     }
 
@@ -262,13 +389,15 @@ impl ZkExample {
         just("fibonacci")
             .padded()
             .ignore_then(Self::parameters(4))
-            .map(|args| Token::Offchain {
-                example: ZkExample::Fibonacci {
-                    fib_0: InputZK::from(args[0].clone()),
-                    fib_1: InputZK::from(args[1].clone()),
-                    n: CircuitTemplateParameter::from(args[2].clone()),
-                    res: InputZK::from(args[3].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::Fibonacci {
+                        fib_0: InputZK::from(args[0].clone())?,
+                        fib_1: InputZK::from(args[1].clone())?,
+                        n: CircuitTemplateParameter::from(args[2].clone())?,
+                        res: InputZK::from(args[3].clone())?,
+                    })
+                })
             })
     }
 
@@ -276,13 +405,15 @@ impl ZkExample {
         just("if")
             .padded()
             .ignore_then(Self::parameters(4))
-            .map(|args| Token::Offchain {
-                example: ZkExample::If {
-                    condition: InputZK::from(args[0].clone()),
-                    assigned: InputZK::from(args[1].clone()),
-                    true_branch: InputZK::from(args[2].clone()),
-                    false_branch: InputZK::from(args[3].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::If {
+                        condition: InputZK::from(args[0].clone())?,
+                        assigned: InputZK::from(args[1].clone())?,
+                        true_branch: InputZK::from(args[2].clone())?,
+                        false_branch: InputZK::from(args[3].clone())?,
+                    })
+                })
             })
     }
 
@@ -290,11 +421,13 @@ impl ZkExample {
         just("assert_eq")
             .padded()
             .ignore_then(Self::parameters(2))
-            .map(|args| Token::Offchain {
-                example: ZkExample::AssertEq {
-                    lhs: InputZK::from(args[0].clone()),
-                    rhs: InputZK::from(args[1].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::AssertEq {
+                        lhs: InputZK::from(args[0].clone())?,
+                        rhs: InputZK::from(args[1].clone())?,
+                    })
+                })
             })
     }
 
@@ -302,12 +435,14 @@ impl ZkExample {
         just("sha256")
             .padded()
             .ignore_then(Self::parameters(3))
-            .map(|args| Token::Offchain {
-                example: ZkExample::Sha256 {
-                    n_bits: CircuitTemplateParameter::from(args[0].clone()),
-                    r#in: InputZK::from(args[1].clone()),
-                    out: InputZK::from(args[2].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::Sha256 {
+                        n_bits: CircuitTemplateParameter::from(args[0].clone())?,
+                        r#in: InputZK::from(args[1].clone())?,
+                        out: InputZK::from(args[2].clone())?,
+                    })
+                })
             })
     }
 
@@ -315,12 +450,14 @@ impl ZkExample {
         just("poseidon")
             .padded()
             .ignore_then(Self::parameters(3))
-            .map(|args| Token::Offchain {
-                example: ZkExample::Poseidon {
-                    n_inputs: CircuitTemplateParameter::from(args[0].clone()),
-                    r#in: InputZK::from(args[1].clone()),
-                    out: InputZK::from(args[2].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::Poseidon {
+                        n_inputs: CircuitTemplateParameter::from(args[0].clone())?,
+                        r#in: InputZK::from(args[1].clone())?,
+                        out: InputZK::from(args[2].clone())?,
+                    })
+                })
             })
     }
 
@@ -328,14 +465,16 @@ impl ZkExample {
         just("merkle_tree_checker")
             .padded()
             .ignore_then(Self::parameters(5))
-            .map(|args| Token::Offchain {
-                example: ZkExample::MerkleTreeChecker {
-                    levels: CircuitTemplateParameter::from(args[0].clone()),
-                    leaf: InputZK::from(args[1].clone()),
-                    root: InputZK::from(args[2].clone()),
-                    path_elements: InputZK::from(args[3].clone()),
-                    path_indices: InputZK::from(args[4].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::MerkleTreeChecker {
+                        levels: CircuitTemplateParameter::from(args[0].clone())?,
+                        leaf: InputZK::from(args[1].clone())?,
+                        root: InputZK::from(args[2].clone())?,
+                        path_elements: InputZK::from(args[3].clone())?,
+                        path_indices: InputZK::from(args[4].clone())?,
+                    })
+                })
             })
     }
 
@@ -349,15 +488,20 @@ impl ZkExample {
 
     fn name_parser_multiple() -> impl Parser<char, TokenWithCardinality, Error = ParseError> {
         just("@")
-            .then(Self::name_parser())
+            .then(choice((Self::expression_parser(), Self::name_parser())))
             .map(|(_, id)| TokenWithCardinality::new_multiple(id))
     }
 
+    fn expression_parser_single() -> impl Parser<char, TokenWithCardinality, Error = ParseError> {
+        Self::expression_parser().map(TokenWithCardinality::new_single)
+    }
+
     fn custom_circom_parser() -> impl Parser<char, Token, Error = ParseError> {
         let string_literal_parser = just('"')
             .ignore_then(filter(|c| *c != '"').repeated().collect::<String>())
             .then_ignore(just('"'));
         let identifiers_parser = choice((
+            Self::expression_parser_single(),
             Self::int_parser_single(),
             Self::name_parser_single(),
             Self::name_parser_multiple(),
@@ -389,6 +533,15 @@ impl ZkExample {
             })
     }
 
+    // Arguments that parse but break the visibility rules still yield a token, so the lexer can
+    // report the error together with the span of the whole offchain call.
+    fn offchain_token(example: impl FnOnce() -> Result<ZkExample, OffchainArgumentError>) -> Token {
+        match example() {
+            Ok(example) => Token::Offchain { example },
+            Err(error) => Token::InvalidOffchain { error },
+        }
+    }
+
     pub fn parser() -> impl Parser<char, Token, Error = ParseError> {
         choice((
             Self::addition_parser(),
@@ -401,7 +554,14 @@ impl ZkExample {
             Self::poseidon_parser(),
             Self::merkle_tree_checker_parser(),
             Self::polynomial_evaluations(),
+            Self::range_check_parser(),
+            Self::less_than_parser(),
+            Self::greater_equal_parser(),
+            Self::is_zero_parser(),
+            Self::eddsa_verify_parser(),
+            Self::membership_with_nullifier_parser(),
             Self::custom_circom_parser(),
+            Self::expression_block_parser(),
         ))
     }
 
@@ -409,14 +569,122 @@ impl ZkExample {
         just("polynomial_evaluations")
             .padded()
             .ignore_then(Self::parameters(5))
-            .map(|args| Token::Offchain {
-                example: ZkExample::PolynomialEvaluations {
-                    grade: CircuitTemplateParameter::from(args[0].clone()),
-                    coefficients: InputZK::from(args[1].clone()),
-                    amount_of_evaluations: CircuitTemplateParameter::from(args[2].clone()),
-                    domain: InputZK::from(args[3].clone()),
-                    evaluations: InputZK::from(args[4].clone()),
-                },
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::PolynomialEvaluations {
+                        grade: CircuitTemplateParameter::from(args[0].clone())?,
+                        coefficients: InputZK::from(args[1].clone())?,
+                        amount_of_evaluations: CircuitTemplateParameter::from(args[2].clone())?,
+                        domain: InputZK::from(args[3].clone())?,
+                        evaluations: InputZK::from(args[4].clone())?,
+                    })
+                })
+            })
+    }
+
+    fn range_check_parser() -> impl Parser<char, Token, Error = ParseError> {
+        just("range_check")
+            .padded()
+            .ignore_then(Self::parameters(4))
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::RangeCheck {
+                        n_bits: CircuitTemplateParameter::from(args[0].clone())?,
+                        value: InputZK::from(args[1].clone())?,
+                        lower_bound: InputZK::from(args[2].clone())?,
+                        upper_bound: InputZK::from(args[3].clone())?,
+                    })
+                })
+            })
+    }
+
+    fn less_than_parser() -> impl Parser<char, Token, Error = ParseError> {
+        just("less_than")
+            .padded()
+            .ignore_then(Self::parameters(4))
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::LessThan {
+                        n_bits: CircuitTemplateParameter::from(args[0].clone())?,
+                        lhs: InputZK::from(args[1].clone())?,
+                        rhs: InputZK::from(args[2].clone())?,
+                        out: InputZK::from(args[3].clone())?,
+                    })
+                })
+            })
+    }
+
+    fn greater_equal_parser() -> impl Parser<char, Token, Error = ParseError> {
+        just("greater_equal")
+            .padded()
+            .ignore_then(Self::parameters(4))
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::GreaterEqual {
+                        n_bits: CircuitTemplateParameter::from(args[0].clone())?,
+                        lhs: InputZK::from(args[1].clone())?,
+                        rhs: InputZK::from(args[2].clone())?,
+                        out: InputZK::from(args[3].clone())?,
+                    })
+                })
+            })
+    }
+
+    fn is_zero_parser() -> impl Parser<char, Token, Error = ParseError> {
+        just("is_zero")
+            .padded()
+            .ignore_then(Self::parameters(2))
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::IsZero {
+                        r#in: InputZK::from(args[0].clone())?,
+                        out: InputZK::from(args[1].clone())?,
+                    })
+                })
+            })
+    }
+
+    fn eddsa_verify_parser() -> impl Parser<char, Token, Error = ParseError> {
+        just("eddsa_verify")
+            .padded()
+            .ignore_then(Self::parameters(6))
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::EddsaVerify {
+                        pub_key_x: InputZK::from(args[0].clone())?,
+                        pub_key_y: InputZK::from(args[1].clone())?,
+                        msg: InputZK::from(args[2].clone())?,
+                        r_x: InputZK::from(args[3].clone())?,
+                        r_y: InputZK::from(args[4].clone())?,
+                        s: InputZK::from(args[5].clone())?,
+                    })
+                })
+            })
+    }
+
+    fn membership_with_nullifier_parser() -> impl Parser<char, Token, Error = ParseError> {
+        just("membership_with_nullifier")
+            .padded()
+            .ignore_then(Self::parameters(4))
+            .map(|args| {
+                Self::offchain_token(|| {
+                    Ok(ZkExample::MembershipWithNullifier {
+                        levels: CircuitTemplateParameter::from(args[0].clone())?,
+                        root: InputZK::from(args[1].clone())?,
+                        nullifier: InputZK::from(args[2].clone())?,
+                        external_nullifier: InputZK::from(args[3].clone())?,
+                    })
+                })
+            })
+    }
+
+    fn expression_block_parser() -> impl Parser<char, Token, Error = ParseError> {
+        OffchainBlock::parser().map(|statements| {
+            Self::offchain_token(|| {
+                Ok(ZkExample::ExpressionBlock {
+                    block: OffchainBlock::from_statements(statements)?,
+                })
             })
+        })
     }
 }